@@ -0,0 +1,219 @@
+//! instruction addresses at slot granularity.
+//!
+//! `IA64::Address` is a bundle address, which is as precise as the decoder needs to be. anything
+//! that names an individual instruction (`psr.ri`, unwind regions, perf samples, debugger
+//! breakpoints, kernel oopses) uses a bundle address and a slot number, conventionally written
+//! with the slot in the low four bits of the address: `0xa000000100012342` is slot 2 of the bundle
+//! at `0xa000000100012340`.
+
+use core::fmt;
+use core::ops::{Add, Sub};
+use core::str::FromStr;
+
+use crate::{BUNDLE_TAGS, InstructionType};
+
+/// the address of one slot in an instruction bundle.
+///
+/// ordering is by bundle address, then slot, so a sorted list of `SlotAddress` is in program
+/// order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SlotAddress {
+    bundle: u64,
+    slot: u8,
+}
+
+/// number of distinct bundle addresses; slot indices wrap modulo three times this.
+const BUNDLE_COUNT: i128 = 1 << 60;
+
+impl SlotAddress {
+    /// slot `slot` of the bundle at `bundle`. `None` if `bundle` is not 16-byte aligned or `slot`
+    /// is not 0, 1, or 2.
+    pub fn new(bundle: u64, slot: u8) -> Option<SlotAddress> {
+        if bundle & 0xf != 0 || slot > 2 {
+            return None;
+        }
+        Some(SlotAddress { bundle, slot })
+    }
+
+    /// interpret `addr` in the usual slot-in-low-bits form. `None` if the low four bits are not
+    /// a valid slot number.
+    pub fn from_linear(addr: u64) -> Option<SlotAddress> {
+        SlotAddress::new(addr & !0xf, (addr & 0xf) as u8)
+    }
+
+    /// this address in the usual slot-in-low-bits form.
+    pub fn to_linear(&self) -> u64 {
+        self.bundle | self.slot as u64
+    }
+
+    /// address of the bundle this slot is in.
+    pub fn bundle(&self) -> u64 {
+        self.bundle
+    }
+
+    /// slot number, 0 through 2. this is the value `psr.ri` would hold for this instruction.
+    pub fn slot(&self) -> u8 {
+        self.slot
+    }
+
+    /// index of this slot counting from slot 0 of the bundle at address 0. one bundle is three
+    /// slots.
+    fn slot_index(&self) -> u64 {
+        (self.bundle >> 4) * 3 + self.slot as u64
+    }
+
+    fn from_slot_index(index: u64) -> SlotAddress {
+        SlotAddress {
+            bundle: (index / 3) << 4,
+            slot: (index % 3) as u8,
+        }
+    }
+
+    /// the address `slots` slots away, wrapping at the ends of the address space.
+    ///
+    /// this counts raw slots, so stepping from slot 1 of an `MLX` bundle lands on slot 2, the
+    /// `X` half of the long instruction. use `next_instruction` to step by instructions.
+    pub fn offset(&self, slots: i64) -> SlotAddress {
+        let index = (self.slot_index() as i128 + slots as i128).rem_euclid(BUNDLE_COUNT * 3);
+        SlotAddress::from_slot_index(index as u64)
+    }
+
+    /// the signed number of slots from `other` to `self`.
+    pub fn slots_since(&self, other: SlotAddress) -> i64 {
+        self.slot_index().wrapping_sub(other.slot_index()) as i64
+    }
+
+    /// the next slot, in the same bundle or the next one.
+    pub fn next_slot(&self) -> SlotAddress {
+        self.offset(1)
+    }
+
+    /// slot 0 of the bundle after this one.
+    pub fn next_bundle(&self) -> SlotAddress {
+        SlotAddress { bundle: self.bundle.wrapping_add(16), slot: 0 }
+    }
+
+    /// the address of the instruction following this one, given the tag of the bundle this
+    /// address is in. for an `MLX` bundle slot 1 and 2 are one instruction, so both step to the
+    /// next bundle.
+    pub fn next_instruction(&self, bundle_tag: u8) -> SlotAddress {
+        if self.slot >= 1 && is_long_bundle(bundle_tag) {
+            self.next_bundle()
+        } else {
+            self.next_slot()
+        }
+    }
+
+    /// the address an instruction at this slot is executed from. `psr.ri` is never 2 in an `MLX`
+    /// bundle, so slot 2 of an `MLX` bundle is normalized to slot 1; every other address is
+    /// unchanged.
+    pub fn canonical(&self, bundle_tag: u8) -> SlotAddress {
+        if self.slot == 2 && is_long_bundle(bundle_tag) {
+            SlotAddress { bundle: self.bundle, slot: 1 }
+        } else {
+            *self
+        }
+    }
+}
+
+/// is `bundle_tag` a template whose last two slots are one `L`+`X` instruction?
+pub(crate) fn is_long_bundle(bundle_tag: u8) -> bool {
+    matches!(BUNDLE_TAGS.get(bundle_tag as usize), Some(Some((types, _))) if types[2] == InstructionType::X)
+}
+
+impl Add<i64> for SlotAddress {
+    type Output = SlotAddress;
+    fn add(self, slots: i64) -> SlotAddress {
+        self.offset(slots)
+    }
+}
+
+impl Sub<i64> for SlotAddress {
+    type Output = SlotAddress;
+    fn sub(self, slots: i64) -> SlotAddress {
+        self.offset(slots.wrapping_neg())
+    }
+}
+
+impl Sub<SlotAddress> for SlotAddress {
+    type Output = i64;
+    fn sub(self, other: SlotAddress) -> i64 {
+        self.slots_since(other)
+    }
+}
+
+/// `0xa000000100012340.2`: bundle address, then slot.
+impl fmt::Display for SlotAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}.{}", self.bundle, self.slot)
+    }
+}
+
+/// `a000000100012342`: the slot-in-low-bits form. respects `#` and padding like `u64` does.
+impl fmt::LowerHex for SlotAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.to_linear(), f)
+    }
+}
+
+impl fmt::UpperHex for SlotAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.to_linear(), f)
+    }
+}
+
+/// reasons a string is not a slot address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseSlotAddressError {
+    /// the address or offset is not a hex number.
+    BadNumber,
+    /// the slot is not 0, 1, or 2 - either after a `.`, or in the low bits of the address.
+    BadSlot,
+    /// a bundle address with an explicit `.slot` has non-zero low bits.
+    Misaligned,
+}
+
+impl fmt::Display for ParseSlotAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSlotAddressError::BadNumber => f.write_str("bad number"),
+            ParseSlotAddressError::BadSlot => f.write_str("bad slot"),
+            ParseSlotAddressError::Misaligned => f.write_str("misaligned bundle address"),
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Result<u64, ParseSlotAddressError> {
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    u64::from_str_radix(digits, 16).map_err(|_| ParseSlotAddressError::BadNumber)
+}
+
+/// accepts the forms slot addresses are usually written in, all hexadecimal with optional `0x`:
+///
+/// * `a000000100012342`: slot in the low bits, as in perf samples and `addr2line` input.
+/// * `a000000100012340.2`: bundle address and slot, as `Display` writes it.
+/// * `a000000100012340+0x22`: a base and an offset in slot-in-low-bits form, as in a kernel
+///   oops's `func+0x22`.
+impl FromStr for SlotAddress {
+    type Err = ParseSlotAddressError;
+
+    fn from_str(s: &str) -> Result<SlotAddress, ParseSlotAddressError> {
+        let s = s.trim();
+        if let Some(dot) = s.find('.') {
+            let bundle = parse_hex(&s[..dot])?;
+            let slot = s[dot + 1..].parse::<u8>().map_err(|_| ParseSlotAddressError::BadSlot)?;
+            if bundle & 0xf != 0 {
+                return Err(ParseSlotAddressError::Misaligned);
+            }
+            SlotAddress::new(bundle, slot).ok_or(ParseSlotAddressError::BadSlot)
+        } else if let Some(plus) = s.find('+') {
+            let base = parse_hex(s[..plus].trim())?;
+            let offset = parse_hex(s[plus + 1..].trim())?;
+            let base = SlotAddress::from_linear(base).ok_or(ParseSlotAddressError::BadSlot)?;
+            let offset = SlotAddress::from_linear(offset).ok_or(ParseSlotAddressError::BadSlot)?;
+            Ok(base + ((offset.bundle() >> 4) * 3 + offset.slot() as u64) as i64)
+        } else {
+            SlotAddress::from_linear(parse_hex(s)?).ok_or(ParseSlotAddressError::BadSlot)
+        }
+    }
+}
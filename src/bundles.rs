@@ -0,0 +1,80 @@
+//! a run of decoded bundles, for analyses that look at more than one bundle at a time.
+
+use yaxpeax_arch::{Decoder, U8Reader};
+
+use crate::{DecodeError, InstDecoder, Instruction, InstructionBundle, SlotAddress};
+use crate::group::InstructionGroups;
+
+/// every bundle in a contiguous range of code, decoded up front.
+///
+/// instructions are borrowed from a `Bundles` rather than decoded on demand, so iterators over
+/// instruction groups (or anything else spanning bundles) can hand out `&Instruction`.
+#[derive(Debug, Clone)]
+pub struct Bundles {
+    base: u64,
+    bundles: Vec<InstructionBundle>,
+}
+
+impl Bundles {
+    /// decode every whole bundle in `bytes`, which holds code starting at the bundle address
+    /// `base`. trailing bytes that are not a whole bundle are ignored. a reserved template
+    /// anywhere in `bytes` is an error.
+    pub fn decode(decoder: &InstDecoder, bytes: &[u8], base: u64) -> Result<Bundles, DecodeError> {
+        let mut bundles = Vec::with_capacity(bytes.len() / 16);
+        for chunk in bytes.chunks_exact(16) {
+            let mut reader = U8Reader::new(chunk);
+            bundles.push(decoder.decode(&mut reader)?);
+        }
+        Ok(Bundles { base: base & !0xf, bundles })
+    }
+
+    /// wrap already-decoded `bundles`, the first of which is at `base`.
+    pub fn from_bundles(base: u64, bundles: Vec<InstructionBundle>) -> Bundles {
+        Bundles { base: base & !0xf, bundles }
+    }
+
+    /// address of the first bundle.
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// address just past the last bundle.
+    pub fn end(&self) -> u64 {
+        self.base.wrapping_add(self.bundles.len() as u64 * 16)
+    }
+
+    pub fn bundles(&self) -> &[InstructionBundle] {
+        &self.bundles
+    }
+
+    /// does the bundle containing `address` lie within this range?
+    pub fn contains(&self, address: SlotAddress) -> bool {
+        self.bundle_at(address.bundle()).is_some()
+    }
+
+    /// the bundle at or containing `address`, if it is in this range.
+    pub fn bundle_at(&self, address: u64) -> Option<&InstructionBundle> {
+        let offset = (address & !0xf).wrapping_sub(self.base);
+        self.bundles.get((offset / 16) as usize)
+    }
+
+    /// the instruction at `address`. slot 2 of an `MLX` bundle yields the `LX` instruction, same
+    /// as slot 1.
+    pub fn instruction_at(&self, address: SlotAddress) -> Option<&Instruction> {
+        self.bundle_at(address.bundle())?.instruction_at(address.slot())
+    }
+
+    /// every instruction in program order, with its address. the `LX` instruction of an `MLX`
+    /// bundle is reported once, at slot 1.
+    pub fn instructions(&self) -> impl Iterator<Item=(SlotAddress, &Instruction)> {
+        let base = self.base;
+        self.bundles.iter().enumerate().flat_map(move |(i, bundle)| {
+            bundle.instructions_at(base.wrapping_add(i as u64 * 16))
+        })
+    }
+
+    /// the instruction groups in this range of code. see `group::InstructionGroups`.
+    pub fn groups(&self) -> InstructionGroups<'_> {
+        InstructionGroups::new(self)
+    }
+}
@@ -0,0 +1,259 @@
+//! which functions call which.
+//!
+//! `CallGraph::build` takes the functions of a range of code from their starts, each running to
+//! the next, and finds every call out of each: direct `br.call` and `brl.call`, indirect calls
+//! resolved through function descriptors by `indirect::resolve_calls`, and tail calls, direct
+//! branches to the start of another function. calls to PLT entries go to a node for the entry,
+//! named for the function it calls, and calls out of the range go to an external node named
+//! from the image's symbols.
+//!
+//! the graph can be written out as Graphviz DOT or as JSON. in both, addresses are hex strings
+//! and call sites are slot addresses in the slot-in-low-bits form, `0x1012` for slot 2 of the
+//! bundle at `0x1010`.
+
+use core::fmt::Write;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Bundles, SlotAddress};
+use crate::debug::{self, BranchState, SuccessorKind};
+use crate::functions::FunctionStart;
+use crate::image::Image;
+use crate::indirect;
+use crate::plt;
+use crate::render::dot_string;
+
+/// what a node of the graph is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// a function in the range of code.
+    Function,
+    /// a PLT entry or import stub in the range of code.
+    Stub,
+    /// code outside the range.
+    External,
+}
+
+/// a function or stub, identified by the address of its first bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    pub address: u64,
+    pub kind: NodeKind,
+    /// the symbol at `address`, or for a stub its label, `printf@plt`.
+    pub name: Option<String>,
+}
+
+impl Node {
+    /// `name`, or the address if there is no name.
+    pub fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| format!("{:#x}", self.address))
+    }
+}
+
+/// how a call is made.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CallKind {
+    /// a `br.call` or `brl.call` to an address in the instruction.
+    Direct,
+    /// a `br.call` through a branch register, resolved through a function descriptor.
+    Indirect,
+    /// a branch, not a call, to the start of another function.
+    Tail,
+}
+
+impl CallKind {
+    fn name(&self) -> &'static str {
+        match self {
+            CallKind::Direct => "direct",
+            CallKind::Indirect => "indirect",
+            CallKind::Tail => "tail",
+        }
+    }
+}
+
+/// one call site.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Call {
+    /// the branch making the call.
+    pub site: SlotAddress,
+    /// the node the branch is in.
+    pub caller: u64,
+    /// the node called.
+    pub callee: u64,
+    pub kind: CallKind,
+}
+
+/// the call graph of a range of code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraph {
+    nodes: BTreeMap<u64, Node>,
+    calls: Vec<Call>,
+    unresolved: Vec<SlotAddress>,
+}
+
+impl CallGraph {
+    /// the graph of the functions in `bundles` that start at `starts`, for a module whose global
+    /// pointer is `gp`. PLT entries are found with `plt::find_stubs` and need not be in `starts`.
+    pub fn build<I: Image + ?Sized>(bundles: &Bundles, starts: &[FunctionStart], gp: u64, image: &I) -> CallGraph {
+        let mut nodes = BTreeMap::new();
+        for stub in plt::find_stubs(bundles, gp, image) {
+            nodes.insert(stub.address, Node { address: stub.address, kind: NodeKind::Stub, name: stub.label() });
+        }
+        for start in starts {
+            let address = start.address.bundle();
+            if bundles.contains(start.address) && !nodes.contains_key(&address) {
+                nodes.insert(address, Node { address, kind: NodeKind::Function, name: image.symbol(address).map(|name| name.to_string()) });
+            }
+        }
+
+        // the callee of a call to `target`, adding a node for it if there isn't one.
+        let callee = |nodes: &mut BTreeMap<u64, Node>, target: u64| {
+            let kind = if bundles.contains(SlotAddress::new(target, 0)?) { NodeKind::Function } else { NodeKind::External };
+            nodes.entry(target).or_insert_with(|| Node { address: target, kind, name: image.symbol(target).map(|name| name.to_string()) });
+            Some(target)
+        };
+
+        let mut found: Vec<(SlotAddress, u64, CallKind)> = Vec::new();
+        for (i, bundle) in bundles.bundles().iter().enumerate() {
+            let bundle_address = bundles.base().wrapping_add(i as u64 * 16);
+            for (address, inst) in bundle.instructions_at(bundle_address) {
+                if !inst.opcode().is_branch() {
+                    continue;
+                }
+                for successor in debug::successors(bundle, address, &BranchState::default()) {
+                    let target = match successor.target {
+                        Some(target) => target.bundle(),
+                        None => { continue; }
+                    };
+                    match successor.kind {
+                        SuccessorKind::Call => found.push((address, target, CallKind::Direct)),
+                        SuccessorKind::Branch => found.push((address, target, CallKind::Tail)),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        let mut unresolved = Vec::new();
+        for call in indirect::resolve_calls(bundles, gp, image) {
+            match call.target {
+                Some(target) => found.push((call.address, target, CallKind::Indirect)),
+                None => unresolved.push(call.address),
+            }
+        }
+        found.sort();
+
+        let starts: BTreeSet<u64> = nodes.keys().cloned().collect();
+        let caller_of = |site: SlotAddress| starts.range(..=site.bundle()).next_back().cloned();
+        let mut calls = Vec::new();
+        for (site, target, kind) in found {
+            let caller = match caller_of(site) {
+                Some(caller) if nodes[&caller].kind == NodeKind::Function => caller,
+                _ => { continue; }
+            };
+            if kind == CallKind::Tail {
+                // a branch within the caller, or into the middle of another function, isn't a
+                // call.
+                let outside = SlotAddress::new(target, 0).map(|target| !bundles.contains(target)).unwrap_or(false);
+                if target == caller || !(starts.contains(&target) || outside) {
+                    continue;
+                }
+            }
+            if let Some(callee) = callee(&mut nodes, target) {
+                calls.push(Call { site, caller, callee, kind });
+            }
+        }
+        CallGraph { nodes, calls, unresolved }
+    }
+
+    /// every node, in address order.
+    pub fn nodes(&self) -> impl Iterator<Item=&Node> {
+        self.nodes.values()
+    }
+
+    /// the node starting at `address`.
+    pub fn node(&self, address: u64) -> Option<&Node> {
+        self.nodes.get(&address)
+    }
+
+    /// every call, in order of call site.
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// the calls made by the node at `caller`.
+    pub fn callees(&self, caller: u64) -> impl Iterator<Item=&Call> {
+        self.calls.iter().filter(move |call| call.caller == caller)
+    }
+
+    /// the calls to the node at `callee`.
+    pub fn callers(&self, callee: u64) -> impl Iterator<Item=&Call> {
+        self.calls.iter().filter(move |call| call.callee == callee)
+    }
+
+    /// indirect calls whose targets weren't resolved.
+    pub fn unresolved(&self) -> &[SlotAddress] {
+        &self.unresolved
+    }
+
+    /// the graph in Graphviz DOT. stubs are boxes and external nodes plain text; indirect calls
+    /// are dashed and tail calls dotted. calls of the same kind between the same nodes are drawn
+    /// once.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph calls {\n");
+        for node in self.nodes.values() {
+            let shape = match node.kind {
+                NodeKind::Function => "",
+                NodeKind::Stub => ", shape=box",
+                NodeKind::External => ", shape=plaintext",
+            };
+            writeln!(dot, "    \"{:#x}\" [label={}{}];", node.address, dot_string(&node.label()), shape).unwrap();
+        }
+        let edges: BTreeSet<(u64, u64, CallKind)> = self.calls.iter().map(|call| (call.caller, call.callee, call.kind)).collect();
+        for (caller, callee, kind) in edges {
+            let style = match kind {
+                CallKind::Direct => "",
+                CallKind::Indirect => " [style=dashed]",
+                CallKind::Tail => " [style=dotted]",
+            };
+            writeln!(dot, "    \"{:#x}\" -> \"{:#x}\"{};", caller, callee, style).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// the graph in JSON: an object with a `nodes` array of `address`, `kind`, and `name`, a
+    /// `calls` array of `site`, `caller`, `callee`, and `kind`, and an `unresolved` array of
+    /// call sites.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self.nodes.values().map(|node| {
+            let kind = match node.kind {
+                NodeKind::Function => "function",
+                NodeKind::Stub => "stub",
+                NodeKind::External => "external",
+            };
+            let name = node.name.as_ref().map(|name| json_string(name)).unwrap_or_else(|| "null".to_string());
+            format!("{{\"address\":\"{:#x}\",\"kind\":\"{}\",\"name\":{}}}", node.address, kind, name)
+        }).collect();
+        let calls: Vec<String> = self.calls.iter().map(|call| {
+            format!("{{\"site\":\"{:#x}\",\"caller\":\"{:#x}\",\"callee\":\"{:#x}\",\"kind\":\"{}\"}}",
+                call.site, call.caller, call.callee, call.kind.name())
+        }).collect();
+        let unresolved: Vec<String> = self.unresolved.iter().map(|site| format!("\"{:#x}\"", site)).collect();
+        format!("{{\"nodes\":[{}],\"calls\":[{}],\"unresolved\":[{}]}}", nodes.join(","), calls.join(","), unresolved.join(","))
+    }
+}
+
+/// `text` as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => { write!(quoted, "\\u{:04x}", c as u32).unwrap(); }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
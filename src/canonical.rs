@@ -0,0 +1,199 @@
+//! checks for bundles that could not have come out of a conforming assembler.
+//!
+//! many instruction formats have fields the processor ignores, or fields that are required to be
+//! zero. the decoder reads past these (an `ld8` with garbage in bits 13..20 of its slot decodes
+//! exactly like the zero-filled encoding), so `Display` of a decoded bundle can't tell the two
+//! apart. `check` re-examines the raw bundle with the same format lookup the decoder uses and
+//! reports any slot that would not re-encode to the same bits.
+//!
+//! field positions are with respect to the 41-bit slot word, where bit 0 is the low bit of `qp`.
+//! this is the same bit numbering used by the format diagrams in the manual's `4.x` sections.
+
+use bitvec::prelude::*;
+
+use crate::{BUNDLE_TAGS, DecodeError, InstructionType};
+use crate::{OperandEncodingA, OperandEncodingB, OperandEncodingF, OperandEncodingI, OperandEncodingM, OperandEncodingX};
+use crate::{get_a_opcode_and_encoding, get_b_opcode_and_encoding, get_f_opcode_and_encoding, get_i_opcode_and_encoding, get_l_opcode_and_encoding, get_m_opcode_and_encoding};
+
+/// why the bits in an `Irregularity` are unexpected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IrregularityKind {
+    /// bits in a field the instruction's format marks as ignored were set.
+    IgnoredBits,
+    /// a field required to be zero was not zero. `cmp` in the `A7` format, for example, always
+    /// compares against `r0`, but still has a (nominally zero) `r2` field.
+    ReservedBits,
+    /// prefetch hint bits were set on a memory instruction that has no hint. the decoder drops
+    /// the hint for these, so `getf.sig.nt1` and `getf.sig` display the same.
+    UnusedHint,
+}
+
+/// one unexpected field in a bundle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Irregularity {
+    /// slot the bits were found in. for `MLX` bundles, bits in the `L` slot are reported against
+    /// slot 1 and bits in the `X` slot against slot 2, even though both are one instruction.
+    pub slot: u8,
+    pub kind: IrregularityKind,
+    /// the offending bits, positioned within the slot's 41-bit word.
+    pub bits: u64,
+}
+
+/// mask of bits `[start, end)`, matching the `word[start..end]` ranges the decoder reads.
+const fn field(start: u32, end: u32) -> u64 {
+    ((1u64 << (end - start)) - 1) << start
+}
+
+/// fields that are ignored or must be zero for each format. only fields the decoder itself skips
+/// over are listed here; bits that select an opcode are never "ignored" even when the decoder
+/// reads them into a `_` binding.
+fn irregular_fields_m(encoding: OperandEncodingM) -> (u64, u64, u64) {
+    use OperandEncodingM::*;
+    // (ignored, reserved, hint)
+    match encoding {
+        M1 | M6 | M31 | M33 | M43 | M46 => (field(13, 20), 0, 0),
+        M4 | M9 | M14 | M15 | M29 | M30 | M32 | M42 | M45 => (field(6, 13), 0, 0),
+        M13 | M28 | M47 => (field(6, 20), 0, 0),
+        // `setf` and `getf` sit in the same tables as loads and stores, but have no hint. see
+        // the fix-up in `decode_instruction` for `M`-unit hints.
+        M18 | M19 => (field(20, 27), 0, field(28, 30)),
+        M24 | M25 => (field(6, 27), 0, 0),
+        M26 | M27 => (field(13, 27), 0, 0),
+        M35 => (field(6, 13) | field(20, 27), 0, 0),
+        M36 => (field(13, 27), 0, 0),
+        // `break.m`: bit 26 is only meaningful for `nop.m`/`hint.m`.
+        M37 => (field(26, 27), 0, 0),
+        M39 => (field(15, 20), 0, 0),
+        M40 => (field(6, 13) | field(15, 20), 0, 0),
+        M41 => (field(6, 13) | field(20, 27), 0, 0),
+        _ => (0, 0, 0),
+    }
+}
+
+fn irregular_fields_i(encoding: OperandEncodingI) -> (u64, u64, u64) {
+    use OperandEncodingI::*;
+    match encoding {
+        I6 => (field(13, 14) | field(19, 20), 0, 0),
+        // `I9` (`popcnt`, `clz`) has a `z` field in place of `r2`.
+        I9 => (0, field(13, 20), 0),
+        I19 => (field(26, 27), 0, 0),
+        I22 => (field(16, 27), 0, 0),
+        I25 => (field(13, 27), 0, 0),
+        I26 | I27 => (field(6, 13), 0, 0),
+        I28 | I29 => (field(13, 20), 0, 0),
+        // `I30` (`tf`) has a `z` field in place of `r3`.
+        I30 => (0, field(20, 27), 0),
+        _ => (0, 0, 0),
+    }
+}
+
+fn irregular_fields_a(encoding: OperandEncodingA) -> (u64, u64, u64) {
+    use OperandEncodingA::*;
+    match encoding {
+        A7 => (0, field(13, 20), 0),
+        _ => (0, 0, 0),
+    }
+}
+
+fn irregular_fields_f(encoding: OperandEncodingF) -> (u64, u64, u64) {
+    use OperandEncodingF::*;
+    match encoding {
+        F7 => (field(13, 20), 0, 0),
+        F10 | F11 => (field(20, 27), 0, 0),
+        F12 => (field(6, 13), 0, 0),
+        F13 => (field(6, 27), 0, 0),
+        F15 => (field(26, 27), 0, 0),
+        _ => (0, 0, 0),
+    }
+}
+
+fn irregular_fields_b(encoding: OperandEncodingB) -> (u64, u64, u64) {
+    use OperandEncodingB::*;
+    match encoding {
+        B1 | B2 | B3 => (field(9, 12), 0, 0),
+        B4 => (field(9, 12) | field(16, 27), 0, 0),
+        // indirect `br.call` has no `x6`, so its ignored field runs up to `wh`.
+        B5 => (field(9, 12) | field(16, 32), 0, 0),
+        // `brp` reuses the `qp` field for `wh`, leaving the rest of it unused.
+        B6 => (field(0, 3) | field(5, 6), 0, 0),
+        B7 => (field(0, 3) | field(5, 6) | field(16, 27), 0, 0),
+        B8 => (field(6, 27), 0, 0),
+        B9 => (field(26, 27), 0, 0),
+        _ => (0, 0, 0),
+    }
+}
+
+/// irregular fields in the `X` slot, and in the paired `L` slot, for a long instruction.
+fn irregular_fields_x(encoding: OperandEncodingX) -> ((u64, u64, u64), u64) {
+    use OperandEncodingX::*;
+    match encoding {
+        X1 => ((field(26, 27), 0, 0), 0),
+        // `brl`: the low two bits of the `L` slot are not part of `imm39`.
+        X3 => ((field(9, 12), field(6, 9), 0), field(0, 2)),
+        X4 => ((field(9, 12), 0, 0), field(0, 2)),
+        _ => ((0, 0, 0), 0),
+    }
+}
+
+fn report(slot: u8, word: u64, (ignored, reserved, hint): (u64, u64, u64), out: &mut Vec<Irregularity>) {
+    for &(mask, kind) in [
+        (ignored, IrregularityKind::IgnoredBits),
+        (reserved, IrregularityKind::ReservedBits),
+        (hint, IrregularityKind::UnusedHint),
+    ].iter() {
+        if word & mask != 0 {
+            out.push(Irregularity { slot, kind, bits: word & mask });
+        }
+    }
+}
+
+/// check the first 16 bytes of `bytes` for fields that would not survive decoding and
+/// re-encoding. an empty list means every slot in the bundle is canonically encoded, at least as
+/// far as this crate knows the formats.
+///
+/// reserved opcodes (`Opcode::Purple` and friends) are not reported here; they decode fine and are
+/// reported by `yaxpeax_arch::Instruction::well_defined` instead.
+pub fn check(bytes: &[u8]) -> Result<Vec<Irregularity>, DecodeError> {
+    if bytes.len() < 16 {
+        return Err(DecodeError::ExhaustedInput);
+    }
+    let bits = bytes[..16].view_bits::<Lsb0>();
+    let bundle_tag = bits[0..5].load::<u8>();
+    let (types, _) = BUNDLE_TAGS[bundle_tag as usize].ok_or(DecodeError::BadBundle)?;
+    let words = [
+        &bits[5..46],
+        &bits[46..87],
+        &bits[87..128],
+    ];
+
+    let mut irregularities = Vec::new();
+    for (i, (word, ty)) in words.iter().zip(types.iter().copied()).enumerate() {
+        let raw = word.load_le::<u64>();
+        let tag = word[37..41].load::<u8>();
+        let fields = match ty {
+            InstructionType::L => {
+                // the opcode of a long instruction lives in the `X` slot, which is the next word.
+                let x = words[i + 1];
+                let (_, encoding) = get_l_opcode_and_encoding(x[37..41].load::<u8>(), x);
+                let (x_fields, l_ignored) = irregular_fields_x(encoding);
+                report(i as u8, raw, (l_ignored, 0, 0), &mut irregularities);
+                report(i as u8 + 1, x.load_le::<u64>(), x_fields, &mut irregularities);
+                break;
+            }
+            InstructionType::X => {
+                unreachable!("X slots are handled with the preceding L slot");
+            }
+            InstructionType::M | InstructionType::I if tag >= 8 => {
+                irregular_fields_a(get_a_opcode_and_encoding(tag, word).1)
+            }
+            InstructionType::A => irregular_fields_a(get_a_opcode_and_encoding(tag, word).1),
+            InstructionType::M => irregular_fields_m(get_m_opcode_and_encoding(tag, word).1),
+            InstructionType::I => irregular_fields_i(get_i_opcode_and_encoding(tag, word).1),
+            InstructionType::F => irregular_fields_f(get_f_opcode_and_encoding(tag, word).1),
+            InstructionType::B => irregular_fields_b(get_b_opcode_and_encoding(tag, word).1),
+        };
+        report(i as u8, raw, fields, &mut irregularities);
+    }
+
+    Ok(irregularities)
+}
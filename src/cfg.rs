@@ -0,0 +1,186 @@
+//! basic blocks and the control flow between them.
+//!
+//! a block ends at every branch, including calls, and at `chk.s`/`chk.a`, and starts at every
+//! target of a direct branch or jump table in the range and after every instruction that ends a
+//! block. a call's block falls through to the return address; the callee is not part of the
+//! graph. edges may leave the range of code the graph was built from, for tail calls and
+//! branches to other functions, and there are no edges for returns or for indirect branches
+//! other than through a recovered jump table.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Bundles, Instruction, Opcode, SlotAddress};
+use crate::debug::{self, BranchState, SuccessorKind};
+use crate::switch::JumpTable;
+
+/// why control can go along an edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// to the next instruction, because the last one is not a branch, might not be taken, or is
+    /// a call that returns.
+    FallThrough,
+    /// a taken direct branch.
+    Branch,
+    /// to the recovery code of a `chk.s` or `chk.a`.
+    Recovery,
+    /// a taken indirect branch, to one of the targets of a jump table.
+    JumpTable,
+}
+
+/// an edge out of a block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub kind: EdgeKind,
+    pub target: SlotAddress,
+    /// the qualifying predicate of the block's last instruction. a branch edge is taken when it
+    /// is true, and a fall-through edge past a branch when it is false. 0 if unconditional.
+    pub predicate: u8,
+}
+
+/// a basic block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Block {
+    /// address of the first instruction.
+    pub start: SlotAddress,
+    /// address of the last instruction.
+    pub last: SlotAddress,
+    pub edges: Vec<Edge>,
+}
+
+impl Block {
+    /// the instructions in this block, with their addresses.
+    pub fn instructions<'a>(&self, bundles: &'a Bundles) -> impl Iterator<Item=(SlotAddress, &'a Instruction)> + 'a {
+        let (start, last) = (self.start, self.last);
+        // start at the block's own bundle rather than walking the range from its beginning.
+        let base = bundles.base();
+        let first = (start.bundle().wrapping_sub(base) / 16) as usize;
+        let rest = bundles.bundles().get(first..).unwrap_or(&[]);
+        rest.iter().enumerate()
+            .flat_map(move |(i, bundle)| bundle.instructions_at(base.wrapping_add((first + i) as u64 * 16)))
+            .skip_while(move |(address, _)| *address < start)
+            .take_while(move |(address, _)| *address <= last)
+    }
+}
+
+/// the control flow graph of a range of code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    blocks: BTreeMap<SlotAddress, Block>,
+}
+
+/// does `opcode` end a basic block?
+fn ends_block(opcode: Opcode) -> bool {
+    opcode.is_branch() || matches!(opcode,
+        Opcode::Chk_s_m_int | Opcode::Chk_s_i_int | Opcode::Chk_s_fp |
+        Opcode::Chk_a_nc_int | Opcode::Chk_a_clr_int | Opcode::Chk_a_nc_fp | Opcode::Chk_a_clr_fp)
+}
+
+impl Cfg {
+    /// the graph of `bundles`, with indirect branches left without edges.
+    pub fn new(bundles: &Bundles) -> Cfg {
+        Cfg::with_jump_tables(bundles, &[])
+    }
+
+    /// the graph of `bundles`, with an edge from each branch in `tables` to each of its targets.
+    pub fn with_jump_tables(bundles: &Bundles, tables: &[JumpTable]) -> Cfg {
+        let tables: BTreeMap<SlotAddress, &JumpTable> = tables.iter().map(|table| (table.branch, table)).collect();
+
+        // the edges out of each instruction that ends a block.
+        let mut exits: BTreeMap<SlotAddress, Vec<Edge>> = BTreeMap::new();
+        let mut leaders = BTreeSet::new();
+        let mut first = true;
+        for (bundle_index, bundle) in bundles.bundles().iter().enumerate() {
+            let bundle_address = bundles.base().wrapping_add(bundle_index as u64 * 16);
+            for (address, inst) in bundle.instructions_at(bundle_address) {
+                if first {
+                    leaders.insert(address);
+                    first = false;
+                }
+                if !ends_block(inst.opcode()) {
+                    continue;
+                }
+                let next = address.next_instruction(bundle.bundle_tag());
+                let predicate = inst.predicate();
+                let mut edges = Vec::new();
+                if let Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh = inst.opcode() {
+                    // a call returns to the next instruction whether or not it is made.
+                    edges.push(Edge { kind: EdgeKind::FallThrough, target: next, predicate: 0 });
+                }
+                for successor in debug::successors(bundle, address, &BranchState::default()) {
+                    let kind = match successor.kind {
+                        SuccessorKind::FallThrough if edges.is_empty() => EdgeKind::FallThrough,
+                        SuccessorKind::Branch => EdgeKind::Branch,
+                        SuccessorKind::Recovery => EdgeKind::Recovery,
+                        _ => { continue; }
+                    };
+                    match successor.target {
+                        Some(target) => edges.push(Edge { kind, target, predicate }),
+                        None => {
+                            if let Some(table) = tables.get(&address) {
+                                let targets: BTreeSet<SlotAddress> = table.targets.iter().cloned().collect();
+                                edges.extend(targets.into_iter().map(|target| Edge { kind: EdgeKind::JumpTable, target, predicate }));
+                            }
+                        }
+                    }
+                }
+                for edge in edges.iter() {
+                    if bundles.contains(edge.target) {
+                        leaders.insert(edge.target);
+                    }
+                }
+                if bundles.contains(next) {
+                    leaders.insert(next);
+                }
+                exits.insert(address, edges);
+            }
+        }
+
+        let mut blocks = BTreeMap::new();
+        // the start of the block being built.
+        let mut current: Option<SlotAddress> = None;
+        let mut instructions = bundles.instructions().peekable();
+        while let Some((address, _)) = instructions.next() {
+            let start = match current {
+                Some(start) if !leaders.contains(&address) => start,
+                _ => address,
+            };
+            current = Some(start);
+            let next_is_leader = instructions.peek().map(|(next, _)| leaders.contains(next)).unwrap_or(true);
+            if let Some(edges) = exits.remove(&address) {
+                blocks.insert(start, Block { start, last: address, edges });
+                current = None;
+            } else if next_is_leader {
+                let edges = match instructions.peek() {
+                    Some((next, _)) => vec![Edge { kind: EdgeKind::FallThrough, target: *next, predicate: 0 }],
+                    None => Vec::new(),
+                };
+                blocks.insert(start, Block { start, last: address, edges });
+                current = None;
+            }
+        }
+        Cfg { blocks }
+    }
+
+    /// every block, in address order.
+    pub fn blocks(&self) -> impl Iterator<Item=&Block> {
+        self.blocks.values()
+    }
+
+    /// the block starting at `start`.
+    pub fn block(&self, start: SlotAddress) -> Option<&Block> {
+        self.blocks.get(&start)
+    }
+
+    /// the block containing the instruction at `address`.
+    pub fn block_containing(&self, address: SlotAddress) -> Option<&Block> {
+        self.blocks.range(..=address).next_back().map(|(_, block)| block).filter(|block| address <= block.last)
+    }
+
+    /// the start of every block with an edge to the block starting at `start`.
+    pub fn predecessors(&self, start: SlotAddress) -> Vec<SlotAddress> {
+        self.blocks.values()
+            .filter(|block| block.edges.iter().any(|edge| edge.target == start))
+            .map(|block| block.start)
+            .collect()
+    }
+}
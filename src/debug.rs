@@ -0,0 +1,209 @@
+//! support for debuggers: planting breakpoints and finding where execution can go next.
+//!
+//! a breakpoint on ia64 replaces one slot of a bundle with a `break` for the slot's unit, leaving
+//! the other two slots and the template alone. single-stepping without hardware support means
+//! planting breakpoints at every slot the current instruction can transfer control to, which
+//! `successors` enumerates.
+
+use core::fmt;
+
+use bitvec::prelude::*;
+
+use crate::{BUNDLE_TAGS, InstructionBundle, InstructionType, Opcode, Operand, SlotAddress};
+
+/// reasons a bundle can't have a `break` patched into it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// fewer than 16 bytes were provided.
+    ExhaustedInput,
+    /// the bundle's template is reserved, so there's no telling what unit a slot is for.
+    BadBundle,
+    /// the slot was not 0, 1, or 2.
+    BadSlot,
+    /// the immediate does not fit in the `break`. `break.x` takes 62 bits, every other `break`
+    /// takes 21.
+    ImmediateTooLarge,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::ExhaustedInput => f.write_str("exhausted input"),
+            PatchError::BadBundle => f.write_str("bad bundle"),
+            PatchError::BadSlot => f.write_str("bad slot"),
+            PatchError::ImmediateTooLarge => f.write_str("immediate too large"),
+        }
+    }
+}
+
+/// the `break` that `insert_break` would use for `slot` of a bundle with tag `bundle_tag`. slots
+/// 1 and 2 of an `MLX` bundle are one instruction, and both get `break.x`.
+pub fn break_opcode(bundle_tag: u8, slot: u8) -> Option<Opcode> {
+    let (types, _) = BUNDLE_TAGS.get(bundle_tag as usize).copied().flatten()?;
+    let ty = types.get(slot as usize)?;
+    Some(match ty {
+        InstructionType::M | InstructionType::A => Opcode::Break_m,
+        InstructionType::I => Opcode::Break_i,
+        InstructionType::F => Opcode::Break_f,
+        InstructionType::B => Opcode::Break_b,
+        InstructionType::L | InstructionType::X => Opcode::Break_x,
+    })
+}
+
+/// replace slot `slot` of the bundle in `bytes` with a `break` for that slot's unit, with
+/// immediate `imm`. the result is the new bundle; `bytes` is not modified.
+///
+/// `break.m`, `break.i`, `break.f`, and `break.b` all share one encoding: `imm20a` in bits 6..26
+/// and `i` in bit 36, under major opcode 0 with zero extension fields. `break.x` additionally
+/// carries 41 bits of immediate in the `L` slot.
+pub fn insert_break(bytes: &[u8], slot: u8, imm: u64) -> Result<[u8; 16], PatchError> {
+    if bytes.len() < 16 {
+        return Err(PatchError::ExhaustedInput);
+    }
+    if slot > 2 {
+        return Err(PatchError::BadSlot);
+    }
+    let mut bundle = [0u8; 16];
+    bundle.copy_from_slice(&bytes[..16]);
+    let opcode = break_opcode(bundle[0] & 0x1f, slot).ok_or(PatchError::BadBundle)?;
+
+    let bits = bundle.view_bits_mut::<Lsb0>();
+    if opcode == Opcode::Break_x {
+        // `imm62` is `i`, then `imm41` from the `L` slot, then `imm20a` from the `X` slot.
+        if imm >= 1 << 62 {
+            return Err(PatchError::ImmediateTooLarge);
+        }
+        let imm20a = imm & 0xfffff;
+        let imm41 = (imm >> 20) & ((1 << 41) - 1);
+        let i = imm >> 61;
+        bits[46..87].store_le::<u64>(imm41);
+        bits[87..128].store_le::<u64>((imm20a << 6) | (i << 36));
+    } else {
+        if imm >= 1 << 21 {
+            return Err(PatchError::ImmediateTooLarge);
+        }
+        let imm20a = imm & 0xfffff;
+        let i = imm >> 20;
+        let start = 5 + 41 * slot as usize;
+        bits[start..start + 41].store_le::<u64>((imm20a << 6) | (i << 36));
+    }
+
+    Ok(bundle)
+}
+
+/// machine state that decides where some control transfers go.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BranchState {
+    /// values of `b0` through `b7`, where known. indirect branches through an unknown register
+    /// have a successor with an unknown target.
+    pub branch_registers: [Option<u64>; 8],
+    /// where `rfi` returns to: `cr.iip`, with `cr.ipsr.ri` as the slot.
+    pub interruption_ip: Option<SlotAddress>,
+}
+
+/// how control gets to a successor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SuccessorKind {
+    /// the next instruction, because this one is not a branch or might not be taken.
+    FallThrough,
+    /// a taken branch, including loop branches (`br.cloop`, `br.ctop`, ...).
+    Branch,
+    /// the target of `br.call` or `brl.call`.
+    Call,
+    /// the target of `br.ret`.
+    Return,
+    /// the recovery code for a failed `chk.s` or `chk.a`.
+    Recovery,
+    /// the return from an interruption via `rfi`.
+    InterruptionReturn,
+    /// the IA-32 target of `br.ia`. the target is a bundle-aligned address, but is executed as
+    /// IA-32 code.
+    Ia32,
+}
+
+/// one place control can go after an instruction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Successor {
+    pub kind: SuccessorKind,
+    /// `None` if the target depends on state not provided in the `BranchState`.
+    pub target: Option<SlotAddress>,
+}
+
+/// every address control can reach directly after executing the instruction at `address`, which
+/// is in `bundle`. a predicated branch is assumed to be possibly taken and possibly not.
+///
+/// faults, traps (including `break`), and interrupts are not considered.
+pub fn successors(bundle: &InstructionBundle, address: SlotAddress, state: &BranchState) -> Vec<Successor> {
+    let address = address.canonical(bundle.bundle_tag());
+    let mut out = Vec::new();
+    let inst = if let Some(inst) = bundle.instruction_at(address.slot()) {
+        inst
+    } else {
+        return out;
+    };
+
+    let ip_relative = |op: &Operand| {
+        if let Operand::ImmI64(disp) = op {
+            SlotAddress::new(address.bundle().wrapping_add(*disp as u64), 0)
+        } else {
+            None
+        }
+    };
+    let indirect = |op: &Operand| {
+        if let Operand::BranchRegister(reg) = op {
+            state.branch_registers[reg.0 as usize].and_then(|target| SlotAddress::new(target & !0xf, 0))
+        } else {
+            None
+        }
+    };
+    let target = |op: &Operand| {
+        match op {
+            Operand::ImmI64(_) => ip_relative(op),
+            _ => indirect(op),
+        }
+    };
+
+    let operands = inst.operands();
+    // whether the instruction can complete without transferring control elsewhere.
+    let mut falls_through = inst.predicate() != 0;
+    match inst.opcode() {
+        Opcode::Br_cond | Opcode::Brl_cond_bwh_ph_dh => {
+            out.push(Successor { kind: SuccessorKind::Branch, target: target(&operands[0]) });
+        }
+        Opcode::Br_wexit | Opcode::Br_wtop |
+        Opcode::Br_cloop | Opcode::Br_cexit | Opcode::Br_ctop => {
+            // whether these are taken depends on `ar.lc`, `ar.ec`, and the qualifying predicate
+            out.push(Successor { kind: SuccessorKind::Branch, target: target(&operands[0]) });
+            falls_through = true;
+        }
+        Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+            out.push(Successor { kind: SuccessorKind::Call, target: target(&operands[1]) });
+        }
+        Opcode::Br_ret => {
+            out.push(Successor { kind: SuccessorKind::Return, target: target(&operands[0]) });
+        }
+        Opcode::Br_ia => {
+            out.push(Successor { kind: SuccessorKind::Ia32, target: target(&operands[0]) });
+        }
+        Opcode::Rfi => {
+            out.push(Successor { kind: SuccessorKind::InterruptionReturn, target: state.interruption_ip });
+        }
+        Opcode::Chk_s_m_int | Opcode::Chk_s_i_int | Opcode::Chk_s_fp |
+        Opcode::Chk_a_nc_int | Opcode::Chk_a_clr_int | Opcode::Chk_a_nc_fp | Opcode::Chk_a_clr_fp => {
+            out.push(Successor { kind: SuccessorKind::Recovery, target: target(&operands[1]) });
+            falls_through = true;
+        }
+        _ => {
+            falls_through = true;
+        }
+    }
+
+    if falls_through {
+        out.insert(0, Successor {
+            kind: SuccessorKind::FallThrough,
+            target: Some(address.next_instruction(bundle.bundle_tag())),
+        });
+    }
+
+    out
+}
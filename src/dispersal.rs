@@ -0,0 +1,288 @@
+//! a model of how Itanium 2 family processors disperse a pair of bundles to issue ports.
+//!
+//! each cycle the front end looks at two bundles and disperses their instructions, in order, to
+//! the functional unit ports: four memory ports (`M0`-`M3`), two integer ports (`I0`, `I1`), two
+//! floating-point ports (`F0`, `F1`), and three branch ports (`B0`-`B2`). every instruction takes
+//! a port, `nop`s included. dispersal stops at the first stop, or at the first instruction that
+//! cannot be given a port; the rest issue in a later cycle. the latter is a "split issue", and is
+//! what `disperse` is for: `MII` followed by `MII` splits at the third `I`-slot instruction,
+//! even if two of the four are `nop.i`.
+//!
+//! the port restrictions modeled here are the ones that matter for reading compiler output:
+//!
+//! * loads (including `lfetch`, semaphores, and `ld.c`/`chk.a`) issue on `M0` or `M1`, and stores
+//!   on `M2` or `M3`. on the 9500 series any `M` port takes either.
+//! * moves to and from application and control registers, `getf`, `alloc`, and the system
+//!   instructions (`itc`, `ptc`, `probe`, `srlz`, `mf`, ...) issue on `M0` or `M2`.
+//! * on the `I` side, `mov` to and from `ar`, `pr`, `br`, and `ip`, `tbit`/`tnat`/`tf`,
+//!   `chk.s.i`, `dep`, `extr`, `popcnt`, `clz`, `mux`, and the multimedia multiplies issue only
+//!   on `I0`.
+//! * `movl` and the other `X`-unit instructions take an `I` port, except `brl`, which takes a `B`
+//!   port.
+//! * `F`- and `B`-slot instructions can use any port of their kind, and `A`-type instructions
+//!   can use any port of the slot's kind.
+//!
+//! ports are assigned in order, as the hardware does: each instruction takes the first free port
+//! it may use, so a `nop.i` that takes `I0` leaves a later `tbit` without a port even though
+//! `I1` would have done for the `nop.i`. it does not model the front end (a taken branch, or a
+//! bundle pair that was not fetched together) or stalls after dispersal.
+
+use crate::{BUNDLE_TAGS, InstructionBundle, InstructionType, Opcode, Operand};
+
+/// processors whose dispersal rules are modeled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Processor {
+    /// Itanium 2, McKinley and Madison cores.
+    Itanium2,
+    /// Itanium 9300 series (Tukwila). its port counts, the `M0`/`M1` load and `M2`/`M3` store
+    /// restrictions, the `M0`/`M2` and `I0`-only instructions, and in-order dispersal are all
+    /// assumed to be those of `Itanium2`.
+    Itanium9300,
+    /// Itanium 9500 series (Poulson), where loads and stores can issue on any `M` port.
+    Itanium9500,
+}
+
+/// one functional unit issue port.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Port {
+    M0, M1, M2, M3,
+    I0, I1,
+    F0, F1,
+    B0, B1, B2,
+}
+
+/// the kind of port an instruction issues to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PortKind {
+    M,
+    I,
+    F,
+    B,
+}
+
+impl PortKind {
+    fn ports(&self) -> &'static [Port] {
+        match self {
+            PortKind::M => &[Port::M0, Port::M1, Port::M2, Port::M3],
+            PortKind::I => &[Port::I0, Port::I1],
+            PortKind::F => &[Port::F0, Port::F1],
+            PortKind::B => &[Port::B0, Port::B1, Port::B2],
+        }
+    }
+}
+
+/// an instruction that was dispersed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// 0 for the first bundle of the pair, 1 for the second.
+    pub bundle: u8,
+    /// slot in that bundle. the `LX` instruction of an `MLX` bundle is slot 1.
+    pub slot: u8,
+    pub port: Port,
+}
+
+/// why dispersal stopped before the end of the bundle pair.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplitReason {
+    /// there is a stop before this instruction. this is not a split issue as such, but ends the
+    /// cycle's dispersal all the same.
+    Stop,
+    /// every port of this kind is in use by an earlier instruction.
+    NoPort(PortKind),
+    /// the instruction can only issue on these ports, and earlier instructions need them.
+    RestrictedPort(&'static [Port]),
+    /// the bundle's template is reserved.
+    BadBundle,
+}
+
+/// where, and why, dispersal stopped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Split {
+    /// 0 or 1, as in `Issue::bundle`.
+    pub bundle: u8,
+    /// the first slot that was not dispersed.
+    pub slot: u8,
+    pub reason: SplitReason,
+}
+
+/// the result of dispersing a bundle pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dispersal {
+    /// the instructions issued this cycle, in program order, with their ports.
+    pub issued: Vec<Issue>,
+    /// `None` if both bundles issued in full.
+    pub split: Option<Split>,
+}
+
+fn is_load(opcode: Opcode) -> bool {
+    use Opcode::*;
+    matches!(opcode,
+        Ld1 | Ld2 | Ld4 | Ld8 | Ld1_s | Ld2_s | Ld4_s | Ld8_s | Ld1_a | Ld2_a | Ld4_a | Ld8_a |
+        Ld1_sa | Ld2_sa | Ld4_sa | Ld8_sa | Ld1_bias | Ld2_bias | Ld4_bias | Ld8_bias |
+        Ld1_acq | Ld2_acq | Ld4_acq | Ld8_acq | Ld8_fill |
+        Ld1_c_clr | Ld2_c_clr | Ld4_c_clr | Ld8_c_clr | Ld1_c_nc | Ld2_c_nc | Ld4_c_nc | Ld8_c_nc |
+        Ld1_c_clr_acq | Ld2_c_clr_acq | Ld4_c_clr_acq | Ld8_c_clr_acq | Ld16 | Ld16_acq |
+        Ldfe | Ldf8 | Ldfs | Ldfd | Ldfe_s | Ldf8_s | Ldfs_s | Ldfd_s | Ldfe_a | Ldf8_a | Ldfs_a | Ldfd_a |
+        Ldfe_sa | Ldf8_sa | Ldfs_sa | Ldfd_sa | Ldf_fill |
+        Ldfe_c_clr | Ldf8_c_clr | Ldfs_c_clr | Ldfd_c_clr | Ldfe_c_nc | Ldf8_c_nc | Ldfs_c_nc | Ldfd_c_nc |
+        Ldfp8 | Ldfps | Ldfpd | Ldfp8_s | Ldfps_s | Ldfpd_s | Ldfp8_a | Ldfps_a | Ldfpd_a |
+        Ldfp8_sa | Ldfps_sa | Ldfpd_sa | Ldfp_a | Ldfp_sa |
+        Ldfp8_c_clr | Ldfps_c_clr | Ldfpd_c_clr | Ldfp8_c_nc | Ldfps_c_nc | Ldfpd_c_nc |
+        Lfetch | Lfetch_excl | Lfetch_fault | Lfetch_fault_excl |
+        Cmpxchg1_acq | Cmpxchg2_acq | Cmpxchg4_acq | Cmpxchg8_acq |
+        Cmpxchg1_rel | Cmpxchg2_rel | Cmpxchg4_rel | Cmpxchg8_rel | Cmp8xchg16_acq | Cmp8xchg16_rel |
+        Xchg1 | Xchg2 | Xchg4 | Xchg8 | Fetchadd4_acq | Fetchadd8_acq | Fetchadd4_rel | Fetchadd8_rel |
+        Chk_a_nc_int | Chk_a_clr_int | Chk_a_nc_fp | Chk_a_clr_fp)
+}
+
+fn is_store(opcode: Opcode) -> bool {
+    use Opcode::*;
+    matches!(opcode,
+        St1 | St2 | St4 | St8 | St1_rel | St2_rel | St4_rel | St8_rel | St8_spill | St16 | St16_rel |
+        Stfe | Stf8 | Stfs | Stfd | Stf_spill)
+}
+
+/// `M`-unit instructions that issue only on `M0` or `M2`.
+fn is_m0_m2(opcode: Opcode) -> bool {
+    use Opcode::*;
+    matches!(opcode,
+        Mov | Mov_m | Mov_fom_pkr | Getf_sig | Getf_exp | Getf_s | Getf_d | Alloc |
+        Flushrs | Loadrs | Sum | Rum | Ssm | Rsm | Fc | Fwb | Mf | Mf_a | Srlz_d | Srlz_i | Sync_i |
+        Invala | Invala_e_int | Invala_e_fp |
+        Probe_r | Probe_w | Probe_rw_fault | Probe_r_fault | Probe_w_fault |
+        Itc_d | Itc_i | Itr_d | Itr_i | Ptc_l | Ptc_g | Ptc_ga | Ptc_e | Ptr_d | Ptr_i |
+        Thash | Ttag | Tpa | Tak)
+}
+
+/// `I`-unit instructions that issue only on `I0`.
+fn is_i0(opcode: Opcode) -> bool {
+    use Opcode::*;
+    matches!(opcode,
+        Mov | Mov_i | Mov_mwh_ih | Mov_ret_mwh_ih | Chk_s_i_int |
+        Tbit_z | Tnat_z | Tbit_z_unc | Tnat_z_unc | Tbit_z_and | Tnat_z_and | Tbit_nz_and | Tnat_nz_and |
+        Tbit_z_or | Tnat_z_or | Tbit_nz_or | Tnat_nz_or |
+        Tbit_z_or_andcm | Tnat_z_or_andcm | Tbit_nz_or_andcm | Tnat_nz_or_andcm |
+        Tf_z | Tf_z_nc | Tf_z_and | Tf_nz_and | Tf_z_or | Tf_nz_or | Tf_z_or_andcm | Tf_nz_or_andcm |
+        Dep | Dep_z | Extr | Extr_u | Popcnt | Clz | Mux1 | Mux2 | Psad1 |
+        Pmpy2_r | Pmpy2_l | Pmpyshr2 | Pmpyshr2_u | Mpy4 | Mpyshl4)
+}
+
+/// `mov r1=r3` and `mov r1=imm` are `A`-unit `adds` and `addl`, and issue like any other `A`-unit
+/// instruction; every other `mov` involves a special register.
+pub(crate) fn is_register_move(opcode: Opcode, operands: &[Operand]) -> bool {
+    opcode == Opcode::Mov && operands.iter().all(|op| {
+        matches!(op, Operand::GPRegister(_) | Operand::ImmI64(_) | Operand::ImmU64(_) | Operand::None)
+    })
+}
+
+/// the ports an instruction in a slot of type `ty` may issue on.
+fn allowed_ports(processor: Processor, ty: InstructionType, opcode: Opcode, operands: &[Operand]) -> &'static [Port] {
+    match ty {
+        InstructionType::M => {
+            if is_m0_m2(opcode) && !is_register_move(opcode, operands) {
+                return &[Port::M0, Port::M2];
+            }
+            if processor != Processor::Itanium9500 {
+                if is_load(opcode) {
+                    return &[Port::M0, Port::M1];
+                }
+                if is_store(opcode) {
+                    return &[Port::M2, Port::M3];
+                }
+            }
+            PortKind::M.ports()
+        }
+        InstructionType::I => {
+            if is_i0(opcode) && !is_register_move(opcode, operands) {
+                &[Port::I0]
+            } else {
+                PortKind::I.ports()
+            }
+        }
+        InstructionType::A => PortKind::M.ports(),
+        InstructionType::F => PortKind::F.ports(),
+        InstructionType::B => PortKind::B.ports(),
+        InstructionType::L | InstructionType::X => {
+            if opcode.is_branch() {
+                PortKind::B.ports()
+            } else {
+                PortKind::I.ports()
+            }
+        }
+    }
+}
+
+/// disperse `first` and `second`, the two bundles in the issue window.
+pub fn disperse(processor: Processor, first: &InstructionBundle, second: &InstructionBundle) -> Dispersal {
+    disperse_from(processor, first, 0, Some(second))
+}
+
+/// disperse a window that starts at slot `start` of `first`, as it does in the cycle after a
+/// split issue. `second` is `None` if there is no bundle after `first` to consider.
+pub(crate) fn disperse_from(processor: Processor, first: &InstructionBundle, start: u8, second: Option<&InstructionBundle>) -> Dispersal {
+    let mut issued: Vec<Issue> = Vec::new();
+    let mut split = None;
+
+    let window = [Some(first), second];
+    'bundles: for (index, bundle) in window.iter().enumerate() {
+        let bundle = if let Some(bundle) = bundle {
+            bundle
+        } else {
+            break;
+        };
+        let (types, stops) = match BUNDLE_TAGS[bundle.bundle_tag() as usize] {
+            Some(desc) => desc,
+            None => {
+                split = Some(Split { bundle: index as u8, slot: 0, reason: SplitReason::BadBundle });
+                break;
+            }
+        };
+        let instructions = bundle.instructions();
+        let skip = if index == 0 { start as usize } else { 0 };
+        for (slot, inst) in instructions.iter().enumerate().skip(skip) {
+            let ports = allowed_ports(processor, types[slot], inst.opcode(), inst.operands());
+            let kind = port_kind(ports[0]);
+
+            let port = match ports.iter().find(|port| !issued.iter().any(|issue| issue.port == **port)) {
+                Some(port) => *port,
+                None => {
+                    let count = issued.iter().filter(|issue| port_kind(issue.port) == kind).count();
+                    let reason = if count >= kind.ports().len() {
+                        SplitReason::NoPort(kind)
+                    } else {
+                        SplitReason::RestrictedPort(ports)
+                    };
+                    split = Some(Split { bundle: index as u8, slot: slot as u8, reason });
+                    break 'bundles;
+                }
+            };
+            issued.push(Issue { bundle: index as u8, slot: slot as u8, port });
+
+            if stops & (0b100 >> slot) != 0 {
+                // a stop after the last instruction in the window is no split at all.
+                let next = if slot + 1 < instructions.len() {
+                    Some((index as u8, slot as u8 + 1))
+                } else if index == 0 && second.is_some() {
+                    Some((1, 0))
+                } else {
+                    None
+                };
+                if let Some((bundle, slot)) = next {
+                    split = Some(Split { bundle, slot, reason: SplitReason::Stop });
+                }
+                break 'bundles;
+            }
+        }
+    }
+
+    Dispersal { issued, split }
+}
+
+fn port_kind(port: Port) -> PortKind {
+    match port {
+        Port::M0 | Port::M1 | Port::M2 | Port::M3 => PortKind::M,
+        Port::I0 | Port::I1 => PortKind::I,
+        Port::F0 | Port::F1 => PortKind::F,
+        Port::B0 | Port::B1 | Port::B2 => PortKind::B,
+    }
+}
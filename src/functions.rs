@@ -0,0 +1,161 @@
+//! finding where functions start in code without symbols.
+//!
+//! compilers emit very regular prologues on ia64: `alloc rX=ar.pfs,...` to set up the register
+//! stack frame, `mov rY=b0` to save the return address, and `adds r12=-N,r12` to allocate stack,
+//! all usually in the first bundle or two. `find_starts` looks for those, for direct call
+//! targets, for code after an unconditional `br.ret`, and for unwind table entries, and scores
+//! each candidate by the evidence for it.
+//!
+//! leaf functions often have no `alloc` and no stack frame, so their only evidence may be a call
+//! to them or the return before them. code after a `br.ret` in the middle of a function scores
+//! the same as a leaf function no one calls directly, so low-confidence starts should be
+//! treated as hints.
+
+use std::collections::BTreeMap;
+
+use crate::{Bundles, GPRegister, InstructionBundle, Opcode, Operand, SlotAddress};
+use crate::debug::{self, BranchState, SuccessorKind};
+use crate::unwind::TableEntry;
+
+/// the reasons to believe a function starts somewhere.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Evidence {
+    /// an unwind table entry starts here.
+    pub unwind: bool,
+    /// a direct `br.call` or `brl.call` targets this bundle.
+    pub call_target: bool,
+    /// the first bundle has an `alloc`.
+    pub alloc: bool,
+    /// `b0` is copied to a general register in the first two bundles.
+    pub saves_b0: bool,
+    /// `r12` is decremented in the first two bundles.
+    pub allocates_stack: bool,
+    /// the last bundle before this one, not counting bundles of `nop`s, ends in an unconditional
+    /// `br.ret`.
+    pub after_return: bool,
+}
+
+impl Evidence {
+    /// a score from 0 to 100. an unwind table entry is conclusive; otherwise a call, an `alloc`,
+    /// and a return just before are each good evidence, and the rest of a prologue adds a little.
+    pub fn confidence(&self) -> u8 {
+        if self.unwind {
+            return 100;
+        }
+        let score =
+            if self.call_target { 50 } else { 0 } +
+            if self.alloc { 40 } else { 0 } +
+            if self.after_return { 20 } else { 0 } +
+            if self.saves_b0 { 10 } else { 0 } +
+            if self.allocates_stack { 10 } else { 0 };
+        score.min(100)
+    }
+}
+
+/// a likely function start.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionStart {
+    /// the function's first bundle. functions always start at slot 0.
+    pub address: SlotAddress,
+    pub evidence: Evidence,
+}
+
+impl FunctionStart {
+    pub fn confidence(&self) -> u8 {
+        self.evidence.confidence()
+    }
+}
+
+/// every likely function start in `bundles`, in address order. `unwind` holds the unwind table
+/// entries for the image, if it has any; see `unwind::table_entries`. entries outside `bundles`
+/// are ignored.
+pub fn find_starts(bundles: &Bundles, unwind: &[TableEntry]) -> Vec<FunctionStart> {
+    let mut candidates: BTreeMap<u64, Evidence> = BTreeMap::new();
+
+    for entry in unwind {
+        if entry.start & 0xf == 0 && entry.start >= bundles.base() && entry.start < bundles.end() {
+            candidates.entry(entry.start).or_default().unwind = true;
+        }
+    }
+
+    let mut returned = false;
+    for (i, bundle) in bundles.bundles().iter().enumerate() {
+        let address = bundles.base().wrapping_add(i as u64 * 16);
+        if is_padding(bundle) {
+            continue;
+        }
+        if returned {
+            candidates.entry(address).or_default().after_return = true;
+        }
+        returned = false;
+
+        for (slot, inst) in bundle.instructions_at(address) {
+            match inst.opcode() {
+                Opcode::Alloc => {
+                    candidates.entry(address).or_default().alloc = true;
+                }
+                Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+                    let successors = debug::successors(bundle, slot, &BranchState::default());
+                    for successor in successors {
+                        if let (SuccessorKind::Call, Some(target)) = (successor.kind, successor.target) {
+                            let target = target.bundle();
+                            if target >= bundles.base() && target < bundles.end() {
+                                candidates.entry(target).or_default().call_target = true;
+                            }
+                        }
+                    }
+                }
+                Opcode::Br_ret if inst.predicate() == 0 => {
+                    returned = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    candidates.into_iter().map(|(address, mut evidence)| {
+        let prologue = (0..2).filter_map(|i| bundles.bundle_at(address.wrapping_add(i * 16)));
+        for bundle in prologue {
+            for inst in bundle.instructions() {
+                let operands = inst.operands();
+                match (inst.opcode(), operands) {
+                    (Opcode::Mov, [Operand::GPRegister(_), Operand::BranchRegister(b)]) if b.0 == 0 => {
+                        evidence.saves_b0 = true;
+                    }
+                    (Opcode::Adds, [Operand::GPRegister(GPRegister(12)), Operand::ImmI64(imm), Operand::GPRegister(GPRegister(12))]) if *imm < 0 => {
+                        evidence.allocates_stack = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        FunctionStart {
+            address: SlotAddress::new(address, 0).expect("bundle address is aligned"),
+            evidence,
+        }
+    }).collect()
+}
+
+/// the code of each function in `starts`, from its start up to the next start or the end of
+/// `bundles`. starts outside `bundles` are ignored.
+pub fn bodies(bundles: &Bundles, starts: &[FunctionStart]) -> Vec<Bundles> {
+    let mut addresses: Vec<u64> = starts.iter()
+        .map(|start| start.address.bundle())
+        .filter(|address| *address >= bundles.base() && *address < bundles.end())
+        .collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+    addresses.iter().enumerate().map(|(i, start)| {
+        let end = addresses.get(i + 1).cloned().unwrap_or_else(|| bundles.end());
+        let first = ((start - bundles.base()) / 16) as usize;
+        let last = ((end - bundles.base()) / 16) as usize;
+        Bundles::from_bundles(*start, bundles.bundles()[first..last].to_vec())
+    }).collect()
+}
+
+/// is `bundle` all `nop`s, as between functions?
+fn is_padding(bundle: &InstructionBundle) -> bool {
+    bundle.instructions().iter().all(|inst| {
+        matches!(inst.opcode(), Opcode::Nop_m | Opcode::Nop_i | Opcode::Nop_f | Opcode::Nop_b | Opcode::Nop_x)
+    })
+}
@@ -0,0 +1,121 @@
+//! resolving addresses computed from the global pointer.
+//!
+//! code reaches global data through `gp`, kept in `r1`: `addl r14=@gprel(var),r1` computes the
+//! address of data in the short data area directly, and `addl r14=@ltoff(var),r1` followed by
+//! `ld8 r14=[r14]` loads the address of `var` from a linkage table slot. large offsets are built
+//! with `movl r14=@ltoff(var)` and an `add r14=r14,r1`. given the `gp` of a module (`__gp`,
+//! or `DT_PLTGOT` in a shared object), `resolve` finds these sequences and the addresses they
+//! compute.
+//!
+//! register values come from `values::Values`, over a `Cfg` of the code entered with `r1`
+//! holding `gp`: where paths join a register keeps its value only if they agree on it, and a
+//! load is only followed if the image says the memory loaded from is read-only. an image should
+//! report a linkage table whose contents are fixed once the program is loaded as read-only. an
+//! address is `gp`-relative if it is `r1` plus a constant while `r1` holds `gp`, or is computed
+//! from such an address by adding a constant, moving it, or post-incrementing it.
+
+use crate::{Bundles, GPRegister, Instruction, Opcode, Operand, SlotAddress};
+use crate::cfg::Cfg;
+use crate::image::Image;
+use crate::registers::Location;
+use crate::values::Values;
+
+/// how a reference's target was computed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// `gp` plus an offset: the address of short data, or of a linkage table slot.
+    GpRelative,
+    /// loaded by `ld8` from a `gp`-relative address: typically the address in a linkage table
+    /// slot.
+    Loaded,
+}
+
+/// an instruction that computes an address from `gp`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub address: SlotAddress,
+    pub kind: ReferenceKind,
+    /// the value the instruction writes to its target register.
+    pub target: u64,
+    /// the name of the symbol at `target`, if the image has one.
+    pub symbol: Option<String>,
+}
+
+/// how many instructions `gp_relative` follows an address back through.
+const MAX_DEPTH: usize = 16;
+
+/// is the value of `op`, as read by the instruction at `at`, `gp` plus an offset?
+fn gp_relative<I: Image + ?Sized>(values: &Values<I>, gp: u64, at: SlotAddress, op: &Operand, depth: usize) -> bool {
+    let reg = match op {
+        Operand::GPRegister(reg) => *reg,
+        _ => { return false; }
+    };
+    if reg.0 == 1 {
+        return values.value(at, reg) == Some(gp);
+    }
+    if depth == MAX_DEPTH {
+        return false;
+    }
+    let (def, inst) = match values.definition(at, Location::GPRegister(reg)) {
+        Some(definition) => definition,
+        None => { return false; }
+    };
+    match (inst.opcode(), inst.operands()) {
+        (Opcode::Addl, [Operand::GPRegister(dest), a, b]) |
+        (Opcode::Adds, [Operand::GPRegister(dest), a, b]) |
+        (Opcode::Add, [Operand::GPRegister(dest), a, b]) if *dest == reg => {
+            // `gp` plus a constant. `gp` plus `gp` is not an address.
+            gp_relative(values, gp, def, a, depth + 1) != gp_relative(values, gp, def, b, depth + 1)
+        }
+        (Opcode::Mov, [Operand::GPRegister(dest), source]) if *dest == reg => {
+            gp_relative(values, gp, def, source, depth + 1)
+        }
+        // a post-incremented base register.
+        (_, [Operand::GPRegister(dest), Operand::Memory(base), _]) if *base == reg && *dest != reg => {
+            gp_relative(values, gp, def, &Operand::GPRegister(*base), depth + 1)
+        }
+        (_, [Operand::Memory(base), _, Operand::ImmI64(_)]) if *base == reg => {
+            gp_relative(values, gp, def, &Operand::GPRegister(*base), depth + 1)
+        }
+        _ => false,
+    }
+}
+
+/// if the instruction at `address` computes an address from `gp`, or loads from one, the kind
+/// of reference and its target.
+fn reference<I: Image + ?Sized>(values: &Values<I>, gp: u64, address: SlotAddress, inst: &Instruction) -> Option<(ReferenceKind, u64)> {
+    let (dest, kind) = match (inst.opcode(), inst.operands()) {
+        (Opcode::Addl, [Operand::GPRegister(dest), a, b]) |
+        (Opcode::Adds, [Operand::GPRegister(dest), a, b]) |
+        (Opcode::Add, [Operand::GPRegister(dest), a, b]) => {
+            if gp_relative(values, gp, address, a, 0) == gp_relative(values, gp, address, b, 0) {
+                return None;
+            }
+            (*dest, ReferenceKind::GpRelative)
+        }
+        (opcode, [Operand::GPRegister(dest), Operand::Memory(base), ..]) if opcode.mnemonic_parts().base == "ld8" => {
+            if !gp_relative(values, gp, address, &Operand::GPRegister(*base), 0) {
+                return None;
+            }
+            (*dest, ReferenceKind::Loaded)
+        }
+        _ => { return None; }
+    };
+    Some((kind, values.value_after(address, dest)?))
+}
+
+/// every `gp`-relative address computation and linkage table load in `bundles`, in program
+/// order, for a module whose global pointer is `gp`.
+pub fn resolve<I: Image + ?Sized>(bundles: &Bundles, gp: u64, image: &I) -> Vec<Reference> {
+    let cfg = Cfg::new(bundles);
+    let values = Values::analyze(bundles, &cfg, image, &[(GPRegister(1), gp)]);
+    bundles.instructions().filter_map(|(address, inst)| {
+        let (kind, target) = reference(&values, gp, address, inst)?;
+        Some(Reference {
+            address,
+            kind,
+            target,
+            symbol: image.symbol(target).map(|name| name.to_string()),
+        })
+    }).collect()
+}
@@ -0,0 +1,112 @@
+//! instruction groups: runs of instructions the processor may issue together.
+//!
+//! an instruction group is ended by a stop (`;;`) in a bundle's template, or by a taken branch.
+//! groups are not bundles: a group can end partway through a bundle, and often spans several.
+//! the instructions in one group must not have RAW or WAW register dependencies on one another
+//! (with the exceptions the manual lists in section 3.4), so any dependency or scheduling analysis
+//! works group by group.
+
+use crate::{BUNDLE_TAGS, Bundles, Instruction, SlotAddress};
+
+/// why an instruction group ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupEnd {
+    /// the last instruction is followed by a stop.
+    Stop,
+    /// the last instruction is a branch, and is not followed by a stop. if the branch is taken,
+    /// this is the end of the group. if it is not taken (a predicated branch whose predicate is
+    /// false, for example) the next group yielded continues this one as far as the processor is
+    /// concerned.
+    Branch,
+    /// the code ran out before a stop or branch. the group may continue past the end of the
+    /// decoded range.
+    EndOfInput,
+}
+
+/// one instruction group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionGroup<'a> {
+    instructions: Vec<(SlotAddress, &'a Instruction)>,
+    end: GroupEnd,
+}
+
+impl<'a> InstructionGroup<'a> {
+    /// the instructions in this group in program order, with their addresses. an `LX`
+    /// instruction is one entry, at slot 1 of its bundle.
+    pub fn instructions(&self) -> &[(SlotAddress, &'a Instruction)] {
+        &self.instructions
+    }
+
+    /// address of the first instruction in the group.
+    pub fn start(&self) -> SlotAddress {
+        self.instructions[0].0
+    }
+
+    /// why the group ended.
+    pub fn end(&self) -> GroupEnd {
+        self.end
+    }
+}
+
+/// iterator over the instruction groups in a `Bundles`, from `Bundles::groups`.
+///
+/// the first group starts at the first instruction of the range, which may well be partway
+/// through a group if the range did not start at one.
+#[derive(Debug, Clone)]
+pub struct InstructionGroups<'a> {
+    bundles: &'a Bundles,
+    // position of the next instruction to yield: index into `bundles`, then into that bundle's
+    // `instructions()`.
+    bundle: usize,
+    instruction: usize,
+}
+
+impl<'a> InstructionGroups<'a> {
+    pub(crate) fn new(bundles: &'a Bundles) -> InstructionGroups<'a> {
+        InstructionGroups { bundles, bundle: 0, instruction: 0 }
+    }
+}
+
+impl<'a> Iterator for InstructionGroups<'a> {
+    type Item = InstructionGroup<'a>;
+
+    fn next(&mut self) -> Option<InstructionGroup<'a>> {
+        let mut instructions = Vec::new();
+        while let Some(bundle) = self.bundles.bundles().get(self.bundle) {
+            let stops = match BUNDLE_TAGS[bundle.bundle_tag() as usize] {
+                Some((_, stops)) => stops,
+                None => 0,
+            };
+            let address = self.bundles.base().wrapping_add(self.bundle as u64 * 16);
+            let slots = bundle.instructions();
+            let index = self.instruction;
+            self.instruction += 1;
+            if self.instruction >= slots.len() {
+                self.bundle += 1;
+                self.instruction = 0;
+            }
+            let inst = if let Some(inst) = slots.get(index) {
+                inst
+            } else {
+                // only a reserved template has no instructions, and `Bundles` won't hold one.
+                continue;
+            };
+
+            // the `LX` instruction is at index 1, and its stop is recorded there too.
+            let slot = index as u8;
+            instructions.push((SlotAddress::new(address, slot).expect("slot is in range"), inst));
+            if stops & (0b100 >> slot) != 0 {
+                return Some(InstructionGroup { instructions, end: GroupEnd::Stop });
+            }
+            if inst.opcode().is_branch() {
+                return Some(InstructionGroup { instructions, end: GroupEnd::Branch });
+            }
+        }
+
+        if instructions.is_empty() {
+            None
+        } else {
+            Some(InstructionGroup { instructions, end: GroupEnd::EndOfInput })
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! the memory and symbols of a loaded program, for analyses that read data the code refers to.
+//!
+//! `Image` is how those analyses see a program: the bytes mapped at an address, their byte
+//! order, and the names of symbols and relocations. `MemoryImage` is a simple implementation
+//! over borrowed segments, for callers that have already pulled sections or program headers out
+//! of an ELF file or core dump.
+
+use std::collections::BTreeMap;
+
+/// the byte order of data in an image: little-endian on Linux and Windows, big-endian on HP-UX.
+/// instruction bundles are little-endian in either case.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    pub(crate) fn read_u32(&self, bytes: &[u8]) -> u32 {
+        let mut word = [0u8; 4];
+        word.copy_from_slice(&bytes[..4]);
+        match self {
+            ByteOrder::Little => u32::from_le_bytes(word),
+            ByteOrder::Big => u32::from_be_bytes(word),
+        }
+    }
+
+    pub(crate) fn read_u64(&self, bytes: &[u8]) -> u64 {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[..8]);
+        match self {
+            ByteOrder::Little => u64::from_le_bytes(word),
+            ByteOrder::Big => u64::from_be_bytes(word),
+        }
+    }
+}
+
+/// a program's memory and symbols.
+pub trait Image {
+    /// the bytes mapped starting at `address`, up to the end of whatever region holds them.
+    /// empty if nothing is mapped there.
+    fn bytes_at(&self, address: u64) -> &[u8];
+
+    fn byte_order(&self) -> ByteOrder;
+
+    /// is the byte at `address` mapped read-only, so that what the code reads from it is known
+    /// before the program runs?
+    fn is_read_only(&self, _address: u64) -> bool {
+        false
+    }
+
+    /// the name of the symbol at exactly `address`, if there is one.
+    fn symbol(&self, _address: u64) -> Option<&str> {
+        None
+    }
+
+    /// the name of the symbol a dynamic relocation at `address` refers to, if there is one. this
+    /// names what an import or linkage table slot will hold once the program is loaded.
+    fn relocation(&self, _address: u64) -> Option<&str> {
+        None
+    }
+
+    /// the doubleword at `address`, if all eight bytes are mapped.
+    fn read_u64(&self, address: u64) -> Option<u64> {
+        let bytes = self.bytes_at(address);
+        if bytes.len() < 8 {
+            return None;
+        }
+        Some(self.byte_order().read_u64(bytes))
+    }
+
+    /// the NUL-terminated string at `address`, if it is mapped, terminated, and UTF-8.
+    fn c_string(&self, address: u64) -> Option<&str> {
+        let bytes = self.bytes_at(address);
+        let len = bytes.iter().position(|b| *b == 0)?;
+        core::str::from_utf8(&bytes[..len]).ok()
+    }
+}
+
+/// an `Image` made of borrowed segments and tables of symbols and relocations.
+#[derive(Debug, Clone)]
+pub struct MemoryImage<'a> {
+    order: ByteOrder,
+    /// each segment's address, contents, and whether it is read-only.
+    segments: Vec<(u64, &'a [u8], bool)>,
+    symbols: BTreeMap<u64, String>,
+    relocations: BTreeMap<u64, String>,
+}
+
+impl<'a> MemoryImage<'a> {
+    pub fn new(order: ByteOrder) -> MemoryImage<'a> {
+        MemoryImage {
+            order,
+            segments: Vec::new(),
+            symbols: BTreeMap::new(),
+            relocations: BTreeMap::new(),
+        }
+    }
+
+    /// map `bytes` at `address`. if segments overlap, the one added first wins.
+    pub fn add_segment(&mut self, address: u64, bytes: &'a [u8]) {
+        self.segments.push((address, bytes, false));
+    }
+
+    /// map `bytes` read-only at `address`, as for `add_segment`.
+    pub fn add_read_only_segment(&mut self, address: u64, bytes: &'a [u8]) {
+        self.segments.push((address, bytes, true));
+    }
+
+    fn segment(&self, address: u64) -> Option<&(u64, &'a [u8], bool)> {
+        self.segments.iter().find(|(base, bytes, _)| address >= *base && address - base < bytes.len() as u64)
+    }
+
+    /// name the symbol at `address`, replacing any name it already has.
+    pub fn add_symbol(&mut self, address: u64, name: &str) {
+        self.symbols.insert(address, name.to_string());
+    }
+
+    /// record a dynamic relocation at `address` against the symbol `name`.
+    pub fn add_relocation(&mut self, address: u64, name: &str) {
+        self.relocations.insert(address, name.to_string());
+    }
+}
+
+impl<'a> Image for MemoryImage<'a> {
+    fn bytes_at(&self, address: u64) -> &[u8] {
+        match self.segment(address) {
+            Some((base, bytes, _)) => &bytes[(address - base) as usize..],
+            None => &[],
+        }
+    }
+
+    fn is_read_only(&self, address: u64) -> bool {
+        matches!(self.segment(address), Some((_, _, true)))
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        self.order
+    }
+
+    fn symbol(&self, address: u64) -> Option<&str> {
+        self.symbols.get(&address).map(|name| name.as_str())
+    }
+
+    fn relocation(&self, address: u64) -> Option<&str> {
+        self.relocations.get(&address).map(|name| name.as_str())
+    }
+}
@@ -0,0 +1,105 @@
+//! resolving indirect calls through function descriptors.
+//!
+//! a function pointer on ia64 is the address of a 16-byte function descriptor: the function's
+//! entry point, then its `gp`. an indirect call loads both and branches through a branch
+//! register:
+//!
+//! ```text
+//! ld8 r15=[r14],8     // entry point
+//! ld8 r1=[r14]        // gp
+//! mov b6=r15
+//! br.call.sptk.many b0=b6
+//! ```
+//!
+//! `resolve_calls` follows the branch register of each `br.call` back through `mov b=r` to the
+//! `ld8` of the entry point and, when the descriptor's address is known, reads the target and
+//! its `gp` from the image. register values come from `values::Values`, as they do for
+//! `gp::resolve`, so a descriptor found through a read-only linkage table slot resolves, and one
+//! from a computed pointer or writable memory does not.
+
+use crate::{BranchRegister, Bundles, GPRegister, Opcode, Operand, SlotAddress};
+use crate::cfg::Cfg;
+use crate::image::Image;
+use crate::registers::Location;
+use crate::values::Values;
+
+/// a function descriptor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionDescriptor {
+    pub entry: u64,
+    pub gp: u64,
+}
+
+impl FunctionDescriptor {
+    /// the function descriptor at `address` in `image`, if all 16 bytes are mapped.
+    pub fn read<I: Image + ?Sized>(image: &I, address: u64) -> Option<FunctionDescriptor> {
+        Some(FunctionDescriptor {
+            entry: image.read_u64(address)?,
+            gp: image.read_u64(address.wrapping_add(8))?,
+        })
+    }
+}
+
+/// a `br.call` through a branch register, and as much as is known about where it goes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndirectCall {
+    pub address: SlotAddress,
+    pub register: BranchRegister,
+    /// the `ld8` that loaded the value moved to `register`, if it is a load.
+    pub load: Option<SlotAddress>,
+    /// the address of the function descriptor the target was loaded from, if it is known.
+    pub descriptor: Option<u64>,
+    /// the address called, if it is known.
+    pub target: Option<u64>,
+    /// the callee's `gp`, read from the descriptor, if it is known.
+    pub gp: Option<u64>,
+    /// the name of the symbol at `target`, if the image has one.
+    pub symbol: Option<String>,
+}
+
+/// the `ld8` that loaded the value read from `reg` by the instruction at `address`, and the
+/// address it loaded from, if that is known.
+fn load<I: Image + ?Sized>(values: &Values<I>, address: SlotAddress, reg: GPRegister) -> Option<(SlotAddress, Option<u64>)> {
+    let (at, inst) = values.definition(address, Location::GPRegister(reg))?;
+    match (inst.opcode(), inst.operands()) {
+        (opcode, [Operand::GPRegister(dest), Operand::Memory(base), ..]) if *dest == reg && opcode.mnemonic_parts().base == "ld8" => {
+            Some((at, values.value(at, *base)))
+        }
+        _ => None,
+    }
+}
+
+/// every `br.call` through a branch register in `bundles`, in program order, resolved as far as
+/// possible for a module whose global pointer is `gp`.
+pub fn resolve_calls<I: Image + ?Sized>(bundles: &Bundles, gp: u64, image: &I) -> Vec<IndirectCall> {
+    let cfg = Cfg::new(bundles);
+    let values = Values::analyze(bundles, &cfg, image, &[(GPRegister(1), gp)]);
+    let mut calls = Vec::new();
+    for (address, inst) in bundles.instructions() {
+        if let (Opcode::Br_call, [_, Operand::BranchRegister(register), ..]) = (inst.opcode(), inst.operands()) {
+            // the general register moved to `register`, and where it was moved.
+            let source = values.definition(address, Location::BranchRegister(*register)).and_then(|(at, inst)| {
+                match (inst.opcode(), inst.operands()) {
+                    (Opcode::Mov_mwh_ih, [_, Operand::GPRegister(source), ..]) => Some((at, *source)),
+                    _ => None,
+                }
+            });
+            let target = source.and_then(|(at, source)| values.value(at, source));
+            let load = source.and_then(|(at, source)| load(&values, at, source));
+            let descriptor = load.and_then(|(_, descriptor)| descriptor);
+            calls.push(IndirectCall {
+                address,
+                register: *register,
+                load: load.map(|(at, _)| at),
+                descriptor,
+                target,
+                gp: descriptor
+                    .filter(|descriptor| image.is_read_only(*descriptor))
+                    .and_then(|descriptor| FunctionDescriptor::read(image, descriptor))
+                    .map(|d| d.gp),
+                symbol: target.and_then(|target| image.symbol(target)).map(|name| name.to_string()),
+            });
+        }
+    }
+    calls
+}
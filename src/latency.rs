@@ -0,0 +1,381 @@
+//! static cycle estimates for straight-line code.
+//!
+//! `estimate` walks a basic block the way an in-order Itanium 2 pipeline would: each cycle it
+//! disperses the next bundle pair (see `dispersal`), stalls the whole issue group until every
+//! operand it reads is available, and records when each result will be ready. the results are a
+//! cycle count and the chain of instructions that determines it, which is what matters when
+//! tuning an inner loop.
+//!
+//! latencies are per `LatencyClass`, with defaults for Itanium 2 from the processor's
+//! optimization reference. loads are costed at a caller-chosen cache level, since a static
+//! estimate can't know where data lives. the bypasses modeled are the ones that let a
+//! predicate or branch register feed a branch early: a compare and the branch on its result can
+//! issue in the same cycle, and `mov b=r` reaches a branch after `Latencies::move_to_branch`
+//! rather than the general move latency.
+
+use crate::{Bundles, Instruction, Opcode, Operand, SlotAddress};
+use crate::dispersal::{Processor, disperse_from};
+use crate::registers::{Location, accesses};
+
+/// groups of opcodes that share a latency.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LatencyClass {
+    /// integer arithmetic, logical, shift, and deposit/extract operations.
+    IntegerAlu,
+    /// `cmp`, `tbit`, `tnat`, and `tf`, which write predicates.
+    Compare,
+    /// parallel (multimedia) integer operations, `popcnt`, and `clz`.
+    Multimedia,
+    /// loads to general registers, including semaphores.
+    IntegerLoad,
+    /// loads to floating-point registers.
+    FloatLoad,
+    Store,
+    /// floating-point arithmetic, conversions, and the other `F`-unit operations with an `f`
+    /// register result, `fma` included.
+    FloatArithmetic,
+    /// `fcmp` and `fclass`.
+    FloatCompare,
+    /// floating-point to general register transfer.
+    GetF,
+    /// general to floating-point register transfer.
+    SetF,
+    /// `mov` in its various forms. latency depends on the registers involved.
+    Move,
+    Branch,
+    /// system, cache management, and ordering instructions, and `chk`.
+    System,
+    /// `nop`, `hint`, and `break`.
+    Nop,
+}
+
+impl Opcode {
+    /// the latency class of this opcode.
+    pub fn latency_class(&self) -> LatencyClass {
+        use Opcode::*;
+        if self.is_branch() {
+            return LatencyClass::Branch;
+        }
+        match self {
+            Ld1 | Ld2 | Ld4 | Ld8 | Ld1_s | Ld2_s | Ld4_s | Ld8_s | Ld1_a | Ld2_a | Ld4_a | Ld8_a |
+            Ld1_sa | Ld2_sa | Ld4_sa | Ld8_sa | Ld1_bias | Ld2_bias | Ld4_bias | Ld8_bias |
+            Ld1_acq | Ld2_acq | Ld4_acq | Ld8_acq | Ld8_fill |
+            Ld1_c_clr | Ld2_c_clr | Ld4_c_clr | Ld8_c_clr | Ld1_c_nc | Ld2_c_nc | Ld4_c_nc | Ld8_c_nc |
+            Ld1_c_clr_acq | Ld2_c_clr_acq | Ld4_c_clr_acq | Ld8_c_clr_acq | Ld16 | Ld16_acq |
+            Cmpxchg1_acq | Cmpxchg2_acq | Cmpxchg4_acq | Cmpxchg8_acq |
+            Cmpxchg1_rel | Cmpxchg2_rel | Cmpxchg4_rel | Cmpxchg8_rel | Cmp8xchg16_acq | Cmp8xchg16_rel |
+            Xchg1 | Xchg2 | Xchg4 | Xchg8 |
+            Fetchadd4_acq | Fetchadd8_acq | Fetchadd4_rel | Fetchadd8_rel => LatencyClass::IntegerLoad,
+
+            Ldfe | Ldf8 | Ldfs | Ldfd | Ldfe_s | Ldf8_s | Ldfs_s | Ldfd_s | Ldfe_a | Ldf8_a | Ldfs_a | Ldfd_a |
+            Ldfe_sa | Ldf8_sa | Ldfs_sa | Ldfd_sa | Ldf_fill |
+            Ldfe_c_clr | Ldf8_c_clr | Ldfs_c_clr | Ldfd_c_clr | Ldfe_c_nc | Ldf8_c_nc | Ldfs_c_nc | Ldfd_c_nc |
+            Ldfp8 | Ldfps | Ldfpd | Ldfp8_s | Ldfps_s | Ldfpd_s | Ldfp8_a | Ldfps_a | Ldfpd_a |
+            Ldfp8_sa | Ldfps_sa | Ldfpd_sa | Ldfp_a | Ldfp_sa |
+            Ldfp8_c_clr | Ldfps_c_clr | Ldfpd_c_clr | Ldfp8_c_nc | Ldfps_c_nc | Ldfpd_c_nc => LatencyClass::FloatLoad,
+
+            St1 | St2 | St4 | St8 | St1_rel | St2_rel | St4_rel | St8_rel | St8_spill | St16 | St16_rel |
+            Stfe | Stf8 | Stfs | Stfd | Stf_spill |
+            Lfetch | Lfetch_excl | Lfetch_fault | Lfetch_fault_excl => LatencyClass::Store,
+
+            Cmp4_eq | Cmp4_eq_and | Cmp4_eq_or | Cmp4_eq_or_andcm | Cmp4_eq_unc |
+            Cmp4_ge_and | Cmp4_ge_or | Cmp4_ge_or_andcm | Cmp4_gt_and | Cmp4_gt_or | Cmp4_gt_or_andcm |
+            Cmp4_le_and | Cmp4_le_or | Cmp4_le_or_andcm | Cmp4_lt | Cmp4_lt_and | Cmp4_lt_or |
+            Cmp4_lt_or_andcm | Cmp4_lt_unc | Cmp4_ltu | Cmp4_ltu_unc |
+            Cmp4_ne_and | Cmp4_ne_or | Cmp4_ne_or_andcm |
+            Cmp_eq | Cmp_eq_and | Cmp_eq_or | Cmp_eq_or_andcm | Cmp_eq_unc |
+            Cmp_ge_and | Cmp_ge_or | Cmp_ge_or_andcm | Cmp_gt_and | Cmp_gt_or | Cmp_gt_or_andcm |
+            Cmp_le_and | Cmp_le_or | Cmp_le_or_andcm | Cmp_lt | Cmp_lt_and | Cmp_lt_or |
+            Cmp_lt_or_andcm | Cmp_lt_unc | Cmp_ltu | Cmp_ltu_unc |
+            Cmp_ne_and | Cmp_ne_or | Cmp_ne_or_andcm |
+            Tbit_z | Tnat_z | Tbit_z_unc | Tnat_z_unc | Tbit_z_and | Tnat_z_and | Tbit_nz_and | Tnat_nz_and |
+            Tbit_z_or | Tnat_z_or | Tbit_nz_or | Tnat_nz_or |
+            Tbit_z_or_andcm | Tnat_z_or_andcm | Tbit_nz_or_andcm | Tnat_nz_or_andcm |
+            Tf_z | Tf_z_nc | Tf_z_and | Tf_nz_and | Tf_z_or | Tf_nz_or | Tf_z_or_andcm | Tf_nz_or_andcm => LatencyClass::Compare,
+
+            Padd1 | Padd1_sss | Padd1_uuu | Padd1_uus | Psub1 | Psub1_sss | Psub1_uuu | Psub1_uus |
+            Pavg1 | Pavg1_raz | Pavgsub1 | Pcmp1_eq | Pcmp1_gt |
+            Padd2 | Padd2_sss | Padd2_uuu | Padd2_uus | Psub2 | Psub2_sss | Psub2_uuu | Psub2_uus |
+            Pavg2 | Pavg2_raz | Pavgsub2 | Pshladd2 | Pshradd2 | Pcmp2_eq | Pcmp2_gt |
+            Padd4 | Psub4 | Pcmp4_eq | Pcmp4_gt |
+            Pmin1_u | Pmax1_u | Pmin2 | Pmax2 | Psad1 | Mux1 | Mux2 |
+            Unpack1_h | Unpack1_l | Unpack2_h | Unpack2_l | Unpack4_h | Unpack4_l |
+            Mix1_r | Mix1_l | Mix2_r | Mix2_l | Mix4_r | Mix4_l |
+            Pack2_uss | Pack2_sss | Pack4_sss |
+            Pshr2_u | Pshr2 | Pshl1 | Pshr2_u_fixed | Pshr2_fixed | Pshl2_fixed |
+            Pshr4_u | Pshr4 | Pshl4 | Pshr4_u_fixed | Pshr4_fixed | Pshl4_fixed |
+            Pmpyshr2_u | Pmpyshr2 | Pmpy2_r | Pmpy2_l | Mpy4 | Mpyshl4 |
+            Popcnt | Clz => LatencyClass::Multimedia,
+
+            Frcpa | Frsqta | Fprcpa | Fprsqrta |
+            Fmerge_s | Fmerge_ns | Fmerge_se | Fpmerge_s | Fpmerge_ns | Fpmerge_se |
+            Fmin | Fmax | Famin | Famax | Fpmin | Fpmax | Fpamin | Fpamax |
+            Fcvt_fx | Fcvt_fxu | Fcvt_fx_trunc | Fcvt_fxu_trunc | Fcvt_xf |
+            Fpcvt_fx | Fpcvt_fxu | Fpcvt_fx_trunc | Fpcvt_fxu_trunc |
+            Fpack | Fand | Fandcm | For | Fxor | Fswap | Fswap_nl | Fswap_nr |
+            Fmix_lr | Fmix_r | Fmix_l | Fsxt_r | Fsxt_l | Fselect |
+            Fnorm | Fnorm_d | Fnorm_s | Fmpy | Fmpy_d | Fmpy_s |
+            Fma | Fma_s | Fma_d | Fpma | Fms | Fms_s | Fms_d | Fpms | Fnma | Fnma_s | Fnma_d | Fpnma |
+            Xma_l | Xma_hu | Xma_h | Xmpy_l | Xmpy_hu | Xmpy_h => LatencyClass::FloatArithmetic,
+
+            Fcmp_eq | Fcmp_lt | Fcmp_le | Fcmp_unord |
+            Fcmp_eq_unc | Fcmp_lt_unc | Fcmp_le_unc | Fcmp_unord_unc |
+            Fclass_m | Fclass_m_unc => LatencyClass::FloatCompare,
+
+            Getf_sig | Getf_exp | Getf_s | Getf_d => LatencyClass::GetF,
+            Setf_sig | Setf_exp | Setf_s | Setf_d => LatencyClass::SetF,
+
+            Mov | Mov_m | Mov_i | Mov_mwh_ih | Mov_ret_mwh_ih | Mov_fom_pkr | Alloc => LatencyClass::Move,
+
+            Brp_ipwh_ih | Brp | Brp_ret => LatencyClass::Branch,
+
+            Nop_m | Nop_i | Nop_f | Nop_b | Nop_x | Hint_m | Hint_i | Hint_f | Hint_b | Hint_x |
+            Break_m | Break_i | Break_f | Break_b | Break_x | White => LatencyClass::Nop,
+
+            Chk_s_m_int | Chk_s_i_int | Chk_s_fp |
+            Chk_a_nc_int | Chk_a_clr_int | Chk_a_nc_fp | Chk_a_clr_fp |
+            Invala | Invala_e_int | Invala_e_fp | Fwb | Srlz_d | Srlz_i | Mf | Mf_a | Sync_i |
+            Sum | Rum | Ssm | Rsm | Loadrs | Flushrs | Fc | Fsetc | Fclrf | Fchkf |
+            Probe_r | Probe_w | Probe_rw_fault | Probe_r_fault | Probe_w_fault |
+            Itc_d | Itc_i | Itr_d | Itr_i | Ptc_l | Ptc_g | Ptc_ga | Ptc_e | Ptr_d | Ptr_i |
+            Thash | Ttag | Tpa | Tak |
+            Cover | Clrrb | Clrrb_pr | Bsw_0 | Bsw_1 | Epc | Vmsw_0 | Vmsw_1 => LatencyClass::System,
+
+            _ => LatencyClass::IntegerAlu,
+        }
+    }
+}
+
+/// where loads are assumed to hit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheLevel {
+    L1,
+    L2,
+    L3,
+    Memory,
+}
+
+/// cycles from issue until a result can be used, by class.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Latencies {
+    pub integer: u32,
+    /// compare to a non-branch consumer of its predicates. branches can use a predicate in the
+    /// cycle it's computed.
+    pub compare: u32,
+    pub multimedia: u32,
+    /// integer loads hitting in each of `L1`, `L2`, `L3`, and memory.
+    pub integer_load: [u32; 4],
+    /// floating-point loads hitting in each of `L1`, `L2`, `L3`, and memory. Itanium 2 doesn't
+    /// cache floating-point data in `L1`, so the first two are the same.
+    pub float_load: [u32; 4],
+    pub float: u32,
+    pub float_compare: u32,
+    pub getf: u32,
+    pub setf: u32,
+    /// `mov b=r` to a branch through that register.
+    pub move_to_branch: u32,
+    /// moves from application, control, branch, and predicate registers, `ip`, and `psr`.
+    pub move_from_special: u32,
+    pub system: u32,
+    /// the cache level loads are assumed to hit in.
+    pub cache: CacheLevel,
+}
+
+impl Latencies {
+    /// Itanium 2 latencies, with loads hitting in `cache`.
+    pub fn itanium2(cache: CacheLevel) -> Latencies {
+        Latencies {
+            integer: 1,
+            compare: 1,
+            multimedia: 2,
+            integer_load: [1, 5, 12, 200],
+            float_load: [6, 6, 13, 200],
+            float: 4,
+            float_compare: 2,
+            getf: 5,
+            setf: 6,
+            move_to_branch: 1,
+            move_from_special: 2,
+            system: 1,
+            cache,
+        }
+    }
+
+    fn load(&self, table: &[u32; 4]) -> u32 {
+        table[match self.cache {
+            CacheLevel::L1 => 0,
+            CacheLevel::L2 => 1,
+            CacheLevel::L3 => 2,
+            CacheLevel::Memory => 3,
+        }]
+    }
+
+    /// cycles until `inst`'s write of `location` is available.
+    fn latency(&self, inst: &Instruction, location: &Location) -> u32 {
+        // a post-increment is an integer add, whatever the load's latency.
+        let writes = inst.last_write_index().map(|i| i as usize + 1).unwrap_or(0);
+        let is_base_update = inst.operands().iter().any(|op| {
+            matches!((op, location), (Operand::Memory(base), Location::GPRegister(r)) if base == r)
+        }) && !inst.operands()[..writes].iter().any(|op| {
+            matches!((op, location), (Operand::GPRegister(dest), Location::GPRegister(r)) if dest == r)
+        });
+        match inst.opcode().latency_class() {
+            _ if is_base_update => self.integer,
+            LatencyClass::IntegerAlu | LatencyClass::Store | LatencyClass::Branch => self.integer,
+            LatencyClass::Compare => self.compare,
+            LatencyClass::Multimedia => self.multimedia,
+            LatencyClass::IntegerLoad => self.load(&self.integer_load),
+            LatencyClass::FloatLoad => self.load(&self.float_load),
+            LatencyClass::FloatArithmetic => self.float,
+            LatencyClass::FloatCompare => self.float_compare,
+            LatencyClass::GetF => self.getf,
+            LatencyClass::SetF => self.setf,
+            LatencyClass::Move => {
+                if let Location::BranchRegister(_) = location {
+                    self.move_to_branch
+                } else if inst.operands().iter().all(|op| {
+                    matches!(op, Operand::GPRegister(_) | Operand::ImmI64(_) | Operand::ImmU64(_))
+                }) {
+                    self.integer
+                } else {
+                    self.move_from_special
+                }
+            }
+            LatencyClass::System | LatencyClass::Nop => self.system,
+        }
+    }
+}
+
+impl Default for Latencies {
+    fn default() -> Latencies {
+        Latencies::itanium2(CacheLevel::L1)
+    }
+}
+
+/// an instruction's place in the estimate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Scheduled {
+    pub address: SlotAddress,
+    /// cycle the instruction issues in, counting from 0.
+    pub issue: u32,
+    /// cycle its last result is available. for instructions with no results, one after `issue`.
+    pub complete: u32,
+}
+
+/// the estimate for a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    /// every instruction in the block, in program order.
+    pub schedule: Vec<Scheduled>,
+    /// cycles until the last instruction has issued.
+    pub issue_cycles: u32,
+    /// cycles until every result is available.
+    pub cycles: u32,
+    /// the chain of instructions, first to last, that determines `cycles`: each one waited on a
+    /// result of the one before it, or, issuing in order, was held up behind it.
+    pub critical_path: Vec<SlotAddress>,
+}
+
+/// estimate the cycles taken by `block`, which is assumed to be one basic block entered at its
+/// first instruction and executed in full with every predicate true.
+pub fn estimate(block: &Bundles, processor: Processor, latencies: &Latencies) -> Estimate {
+    let instructions: Vec<(SlotAddress, &Instruction)> = block.instructions().collect();
+    let bundles = block.bundles();
+    // index of the first instruction of each bundle in `instructions`.
+    let mut first_index = Vec::with_capacity(bundles.len());
+    let mut count = 0;
+    for bundle in bundles.iter() {
+        first_index.push(count);
+        count += bundle.instructions().len();
+    }
+
+    let mut schedule: Vec<Scheduled> = Vec::with_capacity(instructions.len());
+    // why each instruction issued when it did: the instruction it waited on, if any.
+    let mut cause: Vec<Option<usize>> = Vec::with_capacity(instructions.len());
+    let mut has_result: Vec<bool> = Vec::with_capacity(instructions.len());
+    // for each register written so far: when its value is ready, and which instruction wrote it.
+    let mut ready: Vec<(Location, u32, usize)> = Vec::new();
+
+    let mut cycle = 0u32;
+    let mut last_issued: Option<usize> = None;
+    let (mut bundle, mut slot) = (0usize, 0u8);
+    while bundle < bundles.len() {
+        let dispersal = disperse_from(processor, &bundles[bundle], slot, bundles.get(bundle + 1));
+        let issued: Vec<usize> = dispersal.issued.iter().map(|issue| {
+            first_index[bundle + issue.bundle as usize] + issue.slot as usize
+        }).collect();
+        if issued.is_empty() {
+            break;
+        }
+
+        // the group stalls until every instruction in it has its operands.
+        let mut issue = cycle;
+        let mut waited_on = last_issued;
+        // a data dependence is the more interesting cause when both would hold.
+        let mut waited_on_data = false;
+        for &index in issued.iter() {
+            let inst = instructions[index].1;
+            let is_branch = inst.opcode().latency_class() == LatencyClass::Branch;
+            for read in accesses(inst).reads.iter() {
+                for &(location, at, producer) in ready.iter() {
+                    if !location.overlaps(read) {
+                        continue;
+                    }
+                    let producer_inst = instructions[producer].1;
+                    let at = match producer_inst.opcode().latency_class() {
+                        LatencyClass::Compare if is_branch => schedule[producer].issue,
+                        _ => at,
+                    };
+                    if at > issue || (at == issue && !waited_on_data) {
+                        issue = at;
+                        waited_on = Some(producer);
+                        waited_on_data = true;
+                    }
+                }
+            }
+        }
+
+        for &index in issued.iter() {
+            let inst = instructions[index].1;
+            let mut complete = issue + 1;
+            let writes = accesses(inst).writes;
+            has_result.push(!writes.is_empty());
+            for write in writes.iter() {
+                let at = issue + latencies.latency(inst, write);
+                complete = complete.max(at);
+                ready.retain(|(location, _, _)| !write.overlaps(location));
+                ready.push((*write, at, index));
+            }
+            schedule.push(Scheduled { address: instructions[index].0, issue, complete });
+            cause.push(waited_on);
+        }
+
+        last_issued = issued.last().copied();
+        cycle = issue + 1;
+        match dispersal.split {
+            Some(split) => {
+                bundle += split.bundle as usize;
+                slot = split.slot;
+            }
+            None => {
+                bundle += 2;
+                slot = 0;
+            }
+        }
+    }
+
+    let issue_cycles = cycle;
+    // the path ends at whatever finishes last, preferring an instruction that computes something
+    // over a `nop` that issued alongside it.
+    let last = (0..schedule.len()).max_by_key(|&i| (schedule[i].complete, has_result[i], i));
+    let cycles = last.map(|i| schedule[i].complete).unwrap_or(0).max(issue_cycles);
+    let mut critical_path = Vec::new();
+    let mut next = last;
+    while let Some(index) = next {
+        critical_path.push(schedule[index].address);
+        next = cause[index];
+    }
+    critical_path.reverse();
+
+    Estimate { schedule, issue_cycles, cycles, critical_path }
+}
@@ -11,8 +11,41 @@ use bitvec::prelude::*;
 
 use core::fmt;
 
-/// TODO: ia64 reference doc
+mod address;
+mod bundles;
+mod parse;
+pub mod callgraph;
+pub mod canonical;
+pub mod cfg;
+pub mod debug;
+pub mod dispersal;
+pub mod functions;
+pub mod gp;
+pub mod group;
+pub mod image;
+pub mod indirect;
+pub mod latency;
+pub mod lift;
+pub mod liveness;
+pub mod metadata;
+pub mod mnemonic;
+pub mod plt;
+pub mod predicate;
+pub mod registers;
+pub mod reloc;
+pub mod render;
+pub mod search;
+pub mod signature;
+pub mod stack;
+pub mod switch;
+pub mod unwind;
+pub mod values;
+
+pub use address::{ParseSlotAddressError, SlotAddress};
+pub use bundles::Bundles;
+pub use parse::ParseError;
 
+/// TODO: ia64 reference doc
 pub struct IA64;
 
 impl Arch for IA64 {
@@ -24,12 +57,25 @@ impl Arch for IA64 {
     type Operand = Operand;
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Opcode {
     fn default() -> Self {
         Opcode::White
     }
 }
 
+impl Opcode {
+    /// does this opcode name a branch? a taken branch ends the current instruction group even
+    /// without a stop. `chk` is not included: its branch to recovery code is taken as a fault
+    /// would be, not as part of normal execution.
+    pub(crate) fn is_branch(&self) -> bool {
+        matches!(self,
+            Opcode::Br_cond | Opcode::Br_call | Opcode::Br_ret | Opcode::Br_ia |
+            Opcode::Br_wexit | Opcode::Br_wtop | Opcode::Br_cloop | Opcode::Br_cexit | Opcode::Br_ctop |
+            Opcode::Brl_cond_bwh_ph_dh | Opcode::Brl_call_bwh_ph_dh | Opcode::Rfi)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 #[allow(non_camel_case_types)]
 pub enum Opcode {
@@ -530,509 +576,534 @@ pub enum Opcode {
     Cmp_ne_or_andcm,
 }
 
+/// `Opcode::name` and `Opcode::from_name`, from one list of each opcode and its mnemonic.
+macro_rules! opcode_names {
+    ($($opcode:ident => $name:literal,)*) => {
+        impl Opcode {
+            /// the mnemonic for this opcode, with its completers.
+            pub(crate) fn name(&self) -> &'static str {
+                match self {
+                    $(Opcode::$opcode => $name,)*
+                }
+            }
+
+            /// the opcode written `name`. where opcodes share a mnemonic, the first listed.
+            #[allow(unreachable_patterns)]
+            pub(crate) fn from_name(name: &str) -> Option<Opcode> {
+                match name {
+                    $($name => Some(Opcode::$opcode),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+opcode_names! {
+    // TODO: what kind of no-op/undefined are these exactly
+    Purple => "purple",
+    Cyan => "cyan",
+    Brown => "brown",
+    White => "white",
+
+    Addp4 => "addp4",
+    Adds => "adds",
+    Addl => "addl",
+    Add => "add",
+    AddPlusOne => "addplusone",
+    Sub => "sub",
+    SubMinusOne => "subminusone",
+    And => "and",
+    Andcm => "andcm",
+    Or => "or",
+    Xor => "xor",
+
+    Ptc_l => "ptc.l",
+    Probe_w => "probe.w",
+    Ptc_g => "ptc.g",
+    Thash => "thash",
+    Mov_m => "mov.m",
+    Mov_i => "mov.i",
+    Ptc_ga => "ptc.ga",
+    Ttag => "ttag",
+    Ptr_d => "ptr.d",
+    Ptr_i => "ptr.i",
+    Itr_d => "itr.d",
+    Tpa => "tpa",
+    Itc_d => "itc.d",
+    Itr_i => "itr.i",
+    Tak => "tak",
+    Itc_i => "itc.i",
+    Chk_s_m_int => "chk.s.m.int",
+    Chk_s_fp => "chk.s.fp",
+    Alloc => "alloc",
+    Ld1 => "ld1",
+    Ld2 => "ld2",
+    Ld4 => "ld4",
+    Ld8 => "ld8",
+    Ld1_s => "ld1.s",
+    Ld2_s => "ld2.s",
+    Ld4_s => "ld4.s",
+    Ld8_s => "ld8.s",
+    Ld1_a => "ld1.a",
+    Ld2_a => "ld2.a",
+    Ld4_a => "ld4.a",
+    Ld8_a => "ld8.a",
+    Ld1_sa => "ld1.sa",
+    Ld2_sa => "ld2.sa",
+    Ld4_sa => "ld4.sa",
+    Ld8_sa => "ld8.sa",
+    Ld1_bias => "ld1.bias",
+    Ld2_bias => "ld2.bias",
+    Ld4_bias => "ld4.bias",
+    Ld8_bias => "ld8.bias",
+    Ld1_acq => "ld1.acq",
+    Ld2_acq => "ld2.acq",
+    Ld4_acq => "ld4.acq",
+    Ld8_acq => "ld8.acq",
+    Ld8_fill => "ld8.fill",
+    Ld1_c_clr => "ld1.c.clr",
+    Ld2_c_clr => "ld2.c.clr",
+    Ld4_c_clr => "ld4.c.clr",
+    Ld8_c_clr => "ld8.c.clr",
+    Ld1_c_nc => "ld1.c.nc",
+    Ld2_c_nc => "ld2.c.nc",
+    Ld4_c_nc => "ld4.c.nc",
+    Ld8_c_nc => "ld8.c.nc",
+    Ld1_c_clr_acq => "ld1.c.clr.acq",
+    Ld2_c_clr_acq => "ld2.c.clr.acq",
+    Ld4_c_clr_acq => "ld4.c.clr.acq",
+    Ld8_c_clr_acq => "ld8.c.clr.acq",
+    St1 => "st1",
+    St2 => "st2",
+    St4 => "st4",
+    St8 => "st8",
+    St1_rel => "st1.rel",
+    St2_rel => "st2.rel",
+    St4_rel => "st4.rel",
+    St8_rel => "st8.rel",
+    St8_spill => "st8.spill",
+    Probe_r => "probe.r",
+    Cmpxchg1_acq => "cmpxchg1.acq",
+    Cmpxchg2_acq => "cmpxchg2.acq",
+    Cmpxchg4_acq => "cmpxchg4.acq",
+    Cmpxchg8_acq => "cmpxchg8.acq",
+    Cmpxchg1_rel => "cmpxchg1.rel",
+    Cmpxchg2_rel => "cmpxchg2.rel",
+    Cmpxchg4_rel => "cmpxchg4.rel",
+    Cmpxchg8_rel => "cmpxchg8.rel",
+    Xchg1 => "xchg1",
+    Xchg2 => "xchg2",
+    Xchg4 => "xchg4",
+    Xchg8 => "xchg8",
+    Fetchadd4_acq => "fetchadd4.acq",
+    Fetchadd8_acq => "fetchadd8.acq",
+    Fetchadd4_rel => "fetchadd4.rel",
+    Fetchadd8_rel => "fetchadd8.rel",
+    Getf_sig => "getf.sig",
+    Getf_exp => "getf.exp",
+    Getf_s => "getf.s",
+    Getf_d => "getf.d",
+    Cmp8xchg16_acq => "cmp8xchg16.acq",
+    Cmp8xchg16_rel => "cmp8xchg16.rel",
+    Ld16 => "ld16",
+    Ld16_acq => "ld16.acq",
+    St16 => "st16",
+    St16_rel => "st16.rel",
+    Ldfe => "ldfe",
+    Ldf8 => "ldf8",
+    Ldfs => "ldfs",
+    Ldfd => "ldfd",
+    Ldfe_s => "ldfe.s",
+    Ldf8_s => "ldf8.s",
+    Ldfs_s => "ldfs.s",
+    Ldfd_s => "ldfd.s",
+    Ldfe_a => "ldfe.a",
+    Ldf8_a => "ldf8.a",
+    Ldfs_a => "ldfs.a",
+    Ldfd_a => "ldfd.a",
+    Ldfe_sa => "ldfe.sa",
+    Ldf8_sa => "ldf8.sa",
+    Ldfs_sa => "ldfs.sa",
+    Ldfd_sa => "ldfd.sa",
+    Ldf_fill => "ldf.fill",
+    Ldfe_c_clr => "ldfe.c.clr",
+    Ldf8_c_clr => "ldf8.c.clr",
+    Ldfs_c_clr => "ldfs.c.clr",
+    Ldfd_c_clr => "ldfd.c.clr",
+    Ldfp8_c_clr => "ldfp8.c.clr",
+    Ldfps_c_clr => "ldfps.c.clr",
+    Ldfpd_c_clr => "ldfpd.c.clr",
+    Ldfp8_c_nc => "ldfp8.c.nc",
+    Ldfps_c_nc => "ldfps.c.nc",
+    Ldfpd_c_nc => "ldfpd.c.nc",
+    Break_m => "break.m",
+    Invala => "invala",
+    Fwb => "fwb",
+    Srlz_d => "srlz.d",
+    Srlz_i => "srlz.i",
+    Invala_e_int => "invala.e.int",
+    Mf => "mf",
+    Invala_e_fp => "invala.e.fp",
+    Mf_a => "mf.a",
+    Sync_i => "sync.i",
+    Sum => "sum",
+    Rum => "rum",
+    Ssm => "ssm",
+    Rsm => "rsm",
+    Loadrs => "loadrs",
+    Flushrs => "flushrs",
+    Hint_m => "hint.m",
+    Nop_m => "nop.m",
+    Chk_a_nc_int => "chk.a.nc.int",
+    Chk_a_clr_int => "chk.a.clr.int",
+    Chk_a_nc_fp => "chk.a.nc.fp",
+    Chk_a_clr_fp => "chk.a.clr.fp",
+    Fc => "fc",
+    Probe_rw_fault => "probe.rw.fault",
+    Probe_r_fault => "probe.r.fault",
+    Mov_fom_pkr => "mov.fom.pkr",
+    Probe_w_fault => "probe.w.fault",
+    Ptc_e => "ptc.e",
+    Ldfp_a => "ldfp.a",
+    Ldfp_sa => "ldfp.sa",
+    Ldfe_c_nc => "ldfe.c.nc",
+    Ldf8_c_nc => "ldf8.c.nc",
+    Ldfs_c_nc => "ldfs.c.nc",
+    Ldfd_c_nc => "ldfd.c.nc",
+    Lfetch => "lfetch",
+    Lfetch_excl => "lfetch.excl",
+    Lfetch_fault => "lfetch.fault",
+    Lfetch_fault_excl => "lfetch.fault.excl",
+    Stfe => "stfe",
+    Stf8 => "stf8",
+    Stfs => "stfs",
+    Stfd => "stfd",
+    Stf_spill => "stf.spill",
+
+    Shladd => "shladd",
+    Shladdp4 => "shladdp4",
+
+    Padd1 => "padd1",
+    Padd1_sss => "padd1.sss",
+    Padd1_uuu => "padd1.uuu",
+    Padd1_uus => "padd1.uus",
+    Psub1 => "psub1",
+    Psub1_sss => "psub1.sss",
+    Psub1_uuu => "psub1.uuu",
+    Psub1_uus => "psub1.uus",
+
+    Ldfp8 => "ldfp8",
+    Ldfps => "ldfps",
+    Ldfpd => "ldfpd",
+    Ldfp8_s => "ldfp8.s",
+    Ldfps_s => "ldfps.s",
+    Ldfpd_s => "ldfpd.s",
+    Ldfp8_a => "ldfp8.a",
+    Ldfps_a => "ldfps.a",
+    Ldfpd_a => "ldfpd.a",
+    Ldfp8_sa => "ldfp8.sa",
+    Ldfps_sa => "ldfps.sa",
+    Ldfpd_sa => "ldfpd.sa",
+
+    Setf_sig => "setf.sig",
+    Setf_exp => "setf.exp",
+    Setf_s => "setf.s",
+    Setf_d => "setf.d",
+    Pavg1 => "pavg1",
+    Pavg1_raz => "pavg1.raz",
+    Pavgsub1 => "pavgsub1",
+    Pcmp1_eq => "pcmp1.eq",
+    Pcmp1_gt => "pcmp1.gt",
+    Padd2 => "padd2",
+    Padd2_sss => "padd2.sss",
+    Padd2_uuu => "padd2.uuu",
+    Padd2_uus => "padd2.uus",
+    Psub2 => "psub2",
+    Psub2_sss => "psub2.sss",
+    Psub2_uuu => "psub2.uuu",
+    Psub2_uus => "psub2.uus",
+    Pavg2 => "pavg2",
+    Pavg2_raz => "pavg2.raz",
+    Pavgsub2 => "pavgsub2",
+    Pshladd2 => "pshladd2",
+    Pshradd2 => "pshradd2",
+    Pcmp2_eq => "pcmp2.eq",
+    Pcmp2_gt => "pcmp2.gt",
+    Padd4 => "padd4",
+    Psub4 => "psub4",
+    Pcmp4_eq => "pcmp4.eq",
+    Pcmp4_gt => "pcmp4.gt",
+    Hint_x => "hint.x",
+    Nop_x => "nop.x",
+    Movl => "movl",
+    Mov => "mov",
+    Brl_cond_bwh_ph_dh => "brl.cond.bwh.ph.dh",
+    Brl_call_bwh_ph_dh => "brl.call.bwh.ph.dh",
+    Br_call => "br.call",
+    Brp_ipwh_ih => "brp.ipwh.ih",
+    Break_x => "break.x",
+    Break_i => "break.i",
+    Zxt1 => "zxt1",
+    Zxt2 => "zxt2",
+    Zxt4 => "zxt4",
+    Sxt1 => "sxt1",
+    Sxt2 => "sxt2",
+    Sxt4 => "sxt4",
+    Czx1_l => "czx1.l",
+    Czx2_l => "czx2.l",
+    Czx1_r => "czx1.r",
+    Czx2_r => "czx2.r",
+    Hint_i => "hint.i",
+    Nop_i => "nop.i",
+    Chk_s_i_int => "chk.s.i.int",
+    Mov_mwh_ih => "mov",
+    Mov_ret_mwh_ih => "mov.ret",
+    Dep => "dep",
+    Tbit_z => "tbit.z",
+    Tnat_z => "tnat.z",
+    Tbit_z_unc => "tbit.z.unc",
+    Tnat_z_unc => "tnat.z.unc",
+    Tbit_z_and => "tbit.z.and",
+    Tnat_z_and => "tnat.z.and",
+    Tbit_nz_and => "tbit.nz.and",
+    Tnat_nz_and => "tnat.nz.and",
+    Tbit_z_or => "tbit.z.or",
+    Tnat_z_or => "tnat.z.or",
+    Tbit_nz_or => "tbit.nz.or",
+    Tnat_nz_or => "tnat.nz.or",
+    Tbit_z_or_andcm => "tbit.z.or.andcm",
+    Tnat_z_or_andcm => "tnat.z.or.andcm",
+    Tbit_nz_or_andcm => "tbit.nz.or.andcm",
+    Tnat_nz_or_andcm => "tnat.nz.or.andcm",
+    Tf_z => "tf.z",
+    Tf_z_nc => "tf.z.unc",
+    Tf_z_and => "tf.z.and",
+    Tf_nz_and => "tf.nz.and",
+    Tf_z_or => "tf.z.or",
+    Tf_nz_or => "tf.nz.or",
+    Tf_z_or_andcm => "tf.z.or.andcm",
+    Tf_nz_or_andcm => "tf.nz.or.andcm",
+    Dep_z => "dep.z",
+    Extr => "extr",
+    Shrp => "shrp",
+    Extr_u => "extr.u",
+    Pmin1_u => "pmin1.u",
+    Unpack1_h => "unpack1.h",
+    Pmax1_u => "pmax1.u",
+    Unpack1_l => "unpack1.l",
+    Mix1_r => "mix1.r",
+    Mix1_l => "mix1.l",
+    Psad1 => "psad1",
+    Mux1 => "mux1",
+    Pshr2_u => "pshr2.u",
+    Pmpyshr2_u => "pmpyshr2.u",
+    Pshr2 => "pshr2",
+    Pmpyshr2 => "pmpyshr2",
+    Pshl1 => "pshl1",
+    Pshr2_u_fixed => "pshr2.u.fixed",
+    Pshr2_fixed => "pshr2.fixed",
+    Popcnt => "popcnt",
+    Clz => "clz",
+    Pack2_uss => "pack2.uss",
+    Pack2_sss => "pack2.sss",
+    Pmin2 => "pmin2",
+    Unpack2_h => "unpack2.h",
+    Unpack2_l => "unpack2.l",
+    Pmax2 => "pmax2",
+    Mix2_r => "mix2.r",
+    Mix2_l => "mix2.l",
+    Pmpy2_r => "pmpy2.r",
+    Pmpy2_l => "pmpy2.l",
+    Pshl2_fixed => "pshl2.fixed",
+    Mux2 => "mux2",
+    Pshr4_u => "pshr4.u",
+    Pshr4 => "pshr4",
+    Pshl4 => "pshl4",
+    Mpy4 => "mpy4",
+    Mpyshl4 => "mpyshl4",
+    Pshr4_u_fixed => "pshr4.u.fixed",
+    Pshr4_fixed => "pshr4.fixed",
+    Pack4_sss => "pack4.sss",
+    Unpack4_h => "unpack4.h",
+    Unpack4_l => "unpack4.l",
+    Mix4_r => "mix4.r",
+    Mix4_l => "mix4.l",
+    Pshl4_fixed => "pshl4.fixed",
+    Shr_u => "shr.u",
+    Shr => "shr",
+    Shl => "shl",
+
+    Break_b => "break.b",
+    Cover => "cover",
+    Clrrb => "clrrb",
+    Clrrb_pr => "clrrb.pr",
+    Rfi => "rfi",
+    Bsw_0 => "bsw.0",
+    Bsw_1 => "bsw.1",
+    Epc => "epc",
+    Vmsw_0 => "vmsw.0",
+    Vmsw_1 => "vmsw.1",
+    Br_cond => "br.cond",
+    Br_ia => "br.ia",
+    Br_ret => "br.ret",
+
+    Nop_b => "nop.b",
+    Hint_b => "hint.b",
+    Brp => "brp",
+    Brp_ret => "brp.ret",
+
+    Br_wexit => "br.wexit",
+    Br_wtop => "br.wtop",
+    Br_cloop => "br.cloop",
+    Br_cexit => "br.cexit",
+    Br_ctop => "br.ctop",
+
+    Frcpa => "frcpa",
+    Frsqta => "frsqta",
+    Break_f => "break.f",
+    Fsetc => "fsetc",
+    Fclrf => "fclrf",
+    Fchkf => "fchkf",
+    Fmerge_s => "fmerge.s",
+    Fmerge_ns => "fmerge.ns",
+    Fmerge_se => "fmerge.se",
+
+    Fmin => "fmin",
+    Fmax => "fmax",
+    Famin => "famin",
+    Famax => "famax",
+    Fcvt_fx => "fcvt.fx",
+    Fcvt_fxu => "fcvt.fxu",
+    Fcvt_fx_trunc => "fcvt.fx.trunc",
+    Fcvt_fxu_trunc => "fcvt.fxu.trunc",
+    Fcvt_xf => "fcvt.xf",
+    Fpack => "fpack",
+    Fand => "fand",
+    Fandcm => "fandcm",
+    For => "for",
+    Fxor => "fxor",
+
+    Fswap => "fswap",
+    Fswap_nl => "fswap.nl",
+    Fswap_nr => "fswap.nr",
+    Fmix_lr => "fmix.lr",
+    Fmix_r => "fmix.r",
+    Fmix_l => "fmix.l",
+
+    Fsxt_r => "fsxt.r",
+    Fsxt_l => "fsxt.l",
+
+    Hint_f => "hint.f",
+    Nop_f => "nop.f",
+
+    Fprcpa => "fprcpa",
+    Fprsqrta => "fprsqrta",
+    Fpmerge_s => "fpmerge.s",
+    Fpmerge_ns => "fpmerge.ns",
+    Fpmerge_se => "fpmerge.se",
+
+    Fpmin => "fpmin",
+    Fpmax => "fpmax",
+    Fpamin => "fpamin",
+    Fpamax => "fpamax",
+    Fpcvt_fx => "fpcvt.fx",
+    Fpcvt_fxu => "fpcvt.fxu",
+    Fpcvt_fx_trunc => "fpcvt.fx.trunc",
+    Fpcvt_fxu_trunc => "fpcvt.fxu.trunc",
+    Fcmp_eq => "fcmp.eq",
+    Fcmp_lt => "fcmp.lt",
+    Fcmp_le => "fcmp.le",
+    Fcmp_unord => "fcmp.unord",
+    Fcmp_eq_unc => "fcmp.eq.unc",
+    Fcmp_lt_unc => "fcmp.lt.unc",
+    Fcmp_le_unc => "fcmp.le.unc",
+    Fcmp_unord_unc => "fcmp.unord.unc",
+    Fclass_m_unc => "fclass.m.unc",
+    Fclass_m => "fclass.m",
+    Fma_s => "fma.s",
+    Fnorm => "fnorm",
+    Fnorm_d => "fnorm.d",
+    Fnorm_s => "fnorm.s",
+    Fmpy => "fmpy",
+    Fmpy_d => "fmpy.d",
+    Fmpy_s => "fmpy.s",
+    Fma => "fma",
+    Fpma => "fpma",
+    Fma_d => "fma.d",
+    Fms_s => "fms.s",
+    Fms => "fms",
+    Fpms => "fpms",
+    Fms_d => "fms.d",
+    Fnma_s => "fnma.s",
+    Fnma => "fnma",
+    Fpnma => "fpnma",
+    Fnma_d => "fnma.d",
+    Xma_l => "xma.l",
+    Xma_hu => "xma.hu",
+    Xma_h => "xma.h",
+    Xmpy_l => "xmpy.l",
+    Xmpy_hu => "xmpy.hu",
+    Xmpy_h => "xmpy.h",
+    Fselect => "fselect",
+
+    Cmp4_eq => "cmp4.eq",
+    Cmp4_eq_and => "cmp4.eq.and",
+    Cmp4_eq_or => "cmp4.eq.or",
+    Cmp4_eq_or_andcm => "cmp4.eq.or.andcm",
+    Cmp4_eq_unc => "cmp4.eq.unc",
+    Cmp4_ge_and => "cmp4.ge.and",
+    Cmp4_ge_or => "cmp4.ge.or",
+    Cmp4_ge_or_andcm => "cmp4.ge.or.andcm",
+    Cmp4_gt_and => "cmp4.gt.and",
+    Cmp4_gt_or => "cmp4.gt.or",
+    Cmp4_gt_or_andcm => "cmp4.gt.or.andcm",
+    Cmp4_le_and => "cmp4.le.and",
+    Cmp4_le_or => "cmp4.le.or",
+    Cmp4_le_or_andcm => "cmp4.le.or.andcm",
+    Cmp4_lt => "cmp4.lt",
+    Cmp4_lt_and => "cmp4.lt.and",
+    Cmp4_lt_or => "cmp4.lt.or",
+    Cmp4_lt_or_andcm => "cmp4.lt.or.andcm",
+    Cmp4_lt_unc => "cmp4.lt.unc",
+    Cmp4_ltu => "cmp4.ltu",
+    Cmp4_ltu_unc => "cmp4.ltu.unc",
+    Cmp4_ne_and => "cmp4.ne.and",
+    Cmp4_ne_or => "cmp4.ne.or",
+    Cmp4_ne_or_andcm => "cmp4.ne.or.andcm",
+    Cmp_eq => "cmp.eq",
+    Cmp_eq_and => "cmp.eq.and",
+    Cmp_eq_or => "cmp.eq.or",
+    Cmp_eq_or_andcm => "cmp.eq.or.andcm",
+    Cmp_eq_unc => "cmp.eq.unc",
+    Cmp_ge_and => "cmp.ge.and",
+    Cmp_ge_or => "cmp.ge.or",
+    Cmp_ge_or_andcm => "cmp.ge.or.andcm",
+    Cmp_gt_and => "cmp.gt.and",
+    Cmp_gt_or => "cmp.gt.or",
+    Cmp_gt_or_andcm => "cmp.gt.or.andcm",
+    Cmp_le_and => "cmp.le.and",
+    Cmp_le_or => "cmp.le.or",
+    Cmp_le_or_andcm => "cmp.le.or.andcm",
+    Cmp_lt => "cmp.lt",
+    Cmp_lt_and => "cmp.lt.and",
+    Cmp_lt_or => "cmp.lt.or",
+    Cmp_lt_or_andcm => "cmp.lt.or.andcm",
+    Cmp_lt_unc => "cmp.lt.unc",
+    Cmp_ltu => "cmp.ltu",
+    Cmp_ltu_unc => "cmp.ltu.unc",
+    Cmp_ne_and => "cmp.ne.and",
+    Cmp_ne_or => "cmp.ne.or",
+    Cmp_ne_or_andcm => "cmp.ne.or.andcm",
+}
+
 impl fmt::Display for Opcode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            // TODO: what kind of no-op/undefined are these exactly
-            Opcode::Purple => { write!(f, "purple") }
-            Opcode::Cyan => { write!(f, "cyan") }
-            Opcode::Brown => { write!(f, "brown") }
-            Opcode::White => { write!(f, "white") }
-
-            Opcode::Addp4 => { write!(f, "addp4") }
-            Opcode::Adds => { write!(f, "adds") }
-            Opcode::Addl => { write!(f, "addl") }
-            Opcode::Add => { write!(f, "add") }
-            Opcode::AddPlusOne => { write!(f, "addplusone") }
-            Opcode::Sub => { write!(f, "sub") }
-            Opcode::SubMinusOne => { write!(f, "subminusone") }
-            Opcode::And => { write!(f, "and") }
-            Opcode::Andcm => { write!(f, "andcm") }
-            Opcode::Or => { write!(f, "or") }
-            Opcode::Xor => { write!(f, "xor") }
-
-            Opcode::Ptc_l => { write!(f, "ptc.l") }
-            Opcode::Probe_w => { write!(f, "probe.w") }
-            Opcode::Ptc_g => { write!(f, "ptc.g") }
-            Opcode::Thash => { write!(f, "thash") }
-            Opcode::Mov_m => { write!(f, "mov.m") }
-            Opcode::Mov_i => { write!(f, "mov.i") }
-            Opcode::Ptc_ga => { write!(f, "ptc.ga") }
-            Opcode::Ttag => { write!(f, "ttag") }
-            Opcode::Ptr_d => { write!(f, "ptr.d") }
-            Opcode::Ptr_i => { write!(f, "ptr.i") }
-            Opcode::Itr_d => { write!(f, "itr.d") }
-            Opcode::Tpa => { write!(f, "tpa") }
-            Opcode::Itc_d => { write!(f, "itc.d") }
-            Opcode::Itr_i => { write!(f, "itr.i") }
-            Opcode::Tak => { write!(f, "tak") }
-            Opcode::Itc_i => { write!(f, "itc.i") }
-            Opcode::Chk_s_m_int => { write!(f, "chk.s.m.int") }
-            Opcode::Chk_s_fp => { write!(f, "chk.s.fp") }
-            Opcode::Alloc => { write!(f, "alloc") }
-            Opcode::Ld1 => { write!(f, "ld1") }
-            Opcode::Ld2 => { write!(f, "ld2") }
-            Opcode::Ld4 => { write!(f, "ld4") }
-            Opcode::Ld8 => { write!(f, "ld8") }
-            Opcode::Ld1_s => { write!(f, "ld1.s") }
-            Opcode::Ld2_s => { write!(f, "ld2.s") }
-            Opcode::Ld4_s => { write!(f, "ld4.s") }
-            Opcode::Ld8_s => { write!(f, "ld8.s") }
-            Opcode::Ld1_a => { write!(f, "ld1.a") }
-            Opcode::Ld2_a => { write!(f, "ld2.a") }
-            Opcode::Ld4_a => { write!(f, "ld4.a") }
-            Opcode::Ld8_a => { write!(f, "ld8.a") }
-            Opcode::Ld1_sa => { write!(f, "ld1.sa") }
-            Opcode::Ld2_sa => { write!(f, "ld2.sa") }
-            Opcode::Ld4_sa => { write!(f, "ld4.sa") }
-            Opcode::Ld8_sa => { write!(f, "ld8.sa") }
-            Opcode::Ld1_bias => { write!(f, "ld1.bias") }
-            Opcode::Ld2_bias => { write!(f, "ld2.bias") }
-            Opcode::Ld4_bias => { write!(f, "ld4.bias") }
-            Opcode::Ld8_bias => { write!(f, "ld8.bias") }
-            Opcode::Ld1_acq => { write!(f, "ld1.acq") }
-            Opcode::Ld2_acq => { write!(f, "ld2.acq") }
-            Opcode::Ld4_acq => { write!(f, "ld4.acq") }
-            Opcode::Ld8_acq => { write!(f, "ld8.acq") }
-            Opcode::Ld8_fill => { write!(f, "ld8.fill") }
-            Opcode::Ld1_c_clr => { write!(f, "ld1.c.clr") }
-            Opcode::Ld2_c_clr => { write!(f, "ld2.c.clr") }
-            Opcode::Ld4_c_clr => { write!(f, "ld4.c.clr") }
-            Opcode::Ld8_c_clr => { write!(f, "ld8.c.clr") }
-            Opcode::Ld1_c_nc => { write!(f, "ld1.c.nc") }
-            Opcode::Ld2_c_nc => { write!(f, "ld2.c.nc") }
-            Opcode::Ld4_c_nc => { write!(f, "ld4.c.nc") }
-            Opcode::Ld8_c_nc => { write!(f, "ld8.c.nc") }
-            Opcode::Ld1_c_clr_acq => { write!(f, "ld1.c.clr.acq") }
-            Opcode::Ld2_c_clr_acq => { write!(f, "ld2.c.clr.acq") }
-            Opcode::Ld4_c_clr_acq => { write!(f, "ld4.c.clr.acq") }
-            Opcode::Ld8_c_clr_acq => { write!(f, "ld8.c.clr.acq") }
-            Opcode::St1 => { write!(f, "st1") }
-            Opcode::St2 => { write!(f, "st2") }
-            Opcode::St4 => { write!(f, "st4") }
-            Opcode::St8 => { write!(f, "st8") }
-            Opcode::St1_rel => { write!(f, "st1.rel") }
-            Opcode::St2_rel => { write!(f, "st2.rel") }
-            Opcode::St4_rel => { write!(f, "st4.rel") }
-            Opcode::St8_rel => { write!(f, "st8.rel") }
-            Opcode::St8_spill => { write!(f, "st8.spill") }
-            Opcode::Probe_r => { write!(f, "probe.r") }
-            Opcode::Cmpxchg1_acq => { write!(f, "cmpxchg1.acq") }
-            Opcode::Cmpxchg2_acq => { write!(f, "cmpxchg2.acq") }
-            Opcode::Cmpxchg4_acq => { write!(f, "cmpxchg4.acq") }
-            Opcode::Cmpxchg8_acq => { write!(f, "cmpxchg8.acq") }
-            Opcode::Cmpxchg1_rel => { write!(f, "cmpxchg1.rel") }
-            Opcode::Cmpxchg2_rel => { write!(f, "cmpxchg2.rel") }
-            Opcode::Cmpxchg4_rel => { write!(f, "cmpxchg4.rel") }
-            Opcode::Cmpxchg8_rel => { write!(f, "cmpxchg8.rel") }
-            Opcode::Xchg1 => { write!(f, "xchg1") }
-            Opcode::Xchg2 => { write!(f, "xchg2") }
-            Opcode::Xchg4 => { write!(f, "xchg4") }
-            Opcode::Xchg8 => { write!(f, "xchg8") }
-            Opcode::Fetchadd4_acq => { write!(f, "fetchadd4.acq") }
-            Opcode::Fetchadd8_acq => { write!(f, "fetchadd8.acq") }
-            Opcode::Fetchadd4_rel => { write!(f, "fetchadd4.rel") }
-            Opcode::Fetchadd8_rel => { write!(f, "fetchadd8.rel") }
-            Opcode::Getf_sig => { write!(f, "getf.sig") }
-            Opcode::Getf_exp => { write!(f, "getf.exp") }
-            Opcode::Getf_s => { write!(f, "getf.s") }
-            Opcode::Getf_d => { write!(f, "getf.d") }
-            Opcode::Cmp8xchg16_acq => { write!(f, "cmp8xchg16.acq") }
-            Opcode::Cmp8xchg16_rel => { write!(f, "cmp8xchg16.rel") }
-            Opcode::Ld16 => { write!(f, "ld16") }
-            Opcode::Ld16_acq => { write!(f, "ld16.acq") }
-            Opcode::St16 => { write!(f, "st16") }
-            Opcode::St16_rel => { write!(f, "st16.rel") }
-            Opcode::Ldfe => { write!(f, "ldfe") }
-            Opcode::Ldf8 => { write!(f, "ldf8") }
-            Opcode::Ldfs => { write!(f, "ldfs") }
-            Opcode::Ldfd => { write!(f, "ldfd") }
-            Opcode::Ldfe_s => { write!(f, "ldfe.s") }
-            Opcode::Ldf8_s => { write!(f, "ldf8.s") }
-            Opcode::Ldfs_s => { write!(f, "ldfs.s") }
-            Opcode::Ldfd_s => { write!(f, "ldfd.s") }
-            Opcode::Ldfe_a => { write!(f, "ldfe.a") }
-            Opcode::Ldf8_a => { write!(f, "ldf8.a") }
-            Opcode::Ldfs_a => { write!(f, "ldfs.a") }
-            Opcode::Ldfd_a => { write!(f, "ldfd.a") }
-            Opcode::Ldfe_sa => { write!(f, "ldfe.sa") }
-            Opcode::Ldf8_sa => { write!(f, "ldf8.sa") }
-            Opcode::Ldfs_sa => { write!(f, "ldfs.sa") }
-            Opcode::Ldfd_sa => { write!(f, "ldfd.sa") }
-            Opcode::Ldf_fill => { write!(f, "ldf.fill") }
-            Opcode::Ldfe_c_clr => { write!(f, "ldfe.c.clr") }
-            Opcode::Ldf8_c_clr => { write!(f, "ldf8.c.clr") }
-            Opcode::Ldfs_c_clr => { write!(f, "ldfs.c.clr") }
-            Opcode::Ldfd_c_clr => { write!(f, "ldfd.c.clr") }
-            Opcode::Ldfp8_c_clr => { write!(f, "ldfp8.c.clr") }
-            Opcode::Ldfps_c_clr => { write!(f, "ldfps.c.clr") }
-            Opcode::Ldfpd_c_clr => { write!(f, "ldfpd.c.clr") }
-            Opcode::Ldfp8_c_nc => { write!(f, "ldfp8.c.nc") }
-            Opcode::Ldfps_c_nc => { write!(f, "ldfps.c.nc") }
-            Opcode::Ldfpd_c_nc => { write!(f, "ldfpd.c.nc") }
-            Opcode::Break_m => { write!(f, "break.m") }
-            Opcode::Invala => { write!(f, "invala") }
-            Opcode::Fwb => { write!(f, "fwb") }
-            Opcode::Srlz_d => { write!(f, "srlz.d") }
-            Opcode::Srlz_i => { write!(f, "srlz.i") }
-            Opcode::Invala_e_int => { write!(f, "invala.e.int") }
-            Opcode::Mf => { write!(f, "mf") }
-            Opcode::Invala_e_fp => { write!(f, "invala.e.fp") }
-            Opcode::Mf_a => { write!(f, "mf.a") }
-            Opcode::Sync_i => { write!(f, "sync.i") }
-            Opcode::Sum => { write!(f, "sum") }
-            Opcode::Rum => { write!(f, "rum") }
-            Opcode::Ssm => { write!(f, "ssm") }
-            Opcode::Rsm => { write!(f, "rsm") }
-            Opcode::Loadrs => { write!(f, "loadrs") }
-            Opcode::Flushrs => { write!(f, "flushrs") }
-            Opcode::Hint_m => { write!(f, "hint.m") }
-            Opcode::Nop_m => { write!(f, "nop.m") }
-            Opcode::Chk_a_nc_int => { write!(f, "chk.a.nc.int") }
-            Opcode::Chk_a_clr_int => { write!(f, "chk.a.clr.int") }
-            Opcode::Chk_a_nc_fp => { write!(f, "chk.a.nc.fp") }
-            Opcode::Chk_a_clr_fp => { write!(f, "chk.a.clr.fp") }
-            Opcode::Fc => { write!(f, "fc") }
-            Opcode::Probe_rw_fault => { write!(f, "probe.rw.fault") }
-            Opcode::Probe_r_fault => { write!(f, "probe.r.fault") }
-            Opcode::Mov_fom_pkr => { write!(f, "mov.fom.pkr") }
-            Opcode::Probe_w_fault => { write!(f, "probe.w.fault") }
-            Opcode::Ptc_e => { write!(f, "ptc.e") }
-            Opcode::Ldfp_a => { write!(f, "ldfp.a") }
-            Opcode::Ldfp_sa => { write!(f, "ldfp.sa") }
-            Opcode::Ldfe_c_nc => { write!(f, "ldfe.c.nc") }
-            Opcode::Ldf8_c_nc => { write!(f, "ldf8.c.nc") }
-            Opcode::Ldfs_c_nc => { write!(f, "ldfs.c.nc") }
-            Opcode::Ldfd_c_nc => { write!(f, "ldfd.c.nc") }
-            Opcode::Lfetch => { write!(f, "lfetch") }
-            Opcode::Lfetch_excl => { write!(f, "lfetch.excl") }
-            Opcode::Lfetch_fault => { write!(f, "lfetch.fault") }
-            Opcode::Lfetch_fault_excl => { write!(f, "lfetch.fault.excl") }
-            Opcode::Stfe => { write!(f, "stfe") }
-            Opcode::Stf8 => { write!(f, "stf8") }
-            Opcode::Stfs => { write!(f, "stfs") }
-            Opcode::Stfd => { write!(f, "stfd") }
-            Opcode::Stf_spill => { write!(f, "stf.spill") }
-
-            Opcode::Shladd => { write!(f, "shladd") }
-            Opcode::Shladdp4 => { write!(f, "shladdp4") }
-
-            Opcode::Padd1 => { write!(f, "padd1") }
-            Opcode::Padd1_sss => { write!(f, "padd1.sss") }
-            Opcode::Padd1_uuu => { write!(f, "padd1.uuu") }
-            Opcode::Padd1_uus => { write!(f, "padd1.uus") }
-            Opcode::Psub1 => { write!(f, "psub1") }
-            Opcode::Psub1_sss => { write!(f, "psub1.sss") }
-            Opcode::Psub1_uuu => { write!(f, "psub1.uuu") }
-            Opcode::Psub1_uus => { write!(f, "psub1.uus") }
-
-            Opcode::Ldfp8 => { write!(f, "ldfp8") }
-            Opcode::Ldfps => { write!(f, "ldfps") }
-            Opcode::Ldfpd => { write!(f, "ldfpd") }
-            Opcode::Ldfp8_s => { write!(f, "ldfp8.s") }
-            Opcode::Ldfps_s => { write!(f, "ldfps.s") }
-            Opcode::Ldfpd_s => { write!(f, "ldfpd.s") }
-            Opcode::Ldfp8_a => { write!(f, "ldfp8.a") }
-            Opcode::Ldfps_a => { write!(f, "ldfps.a") }
-            Opcode::Ldfpd_a => { write!(f, "ldfpd.a") }
-            Opcode::Ldfp8_sa => { write!(f, "ldfp8.sa") }
-            Opcode::Ldfps_sa => { write!(f, "ldfps.sa") }
-            Opcode::Ldfpd_sa => { write!(f, "ldfpd.sa") }
-
-            Opcode::Setf_sig => { write!(f, "setf.sig") }
-            Opcode::Setf_exp => { write!(f, "setf.exp") }
-            Opcode::Setf_s => { write!(f, "setf.s") }
-            Opcode::Setf_d => { write!(f, "setf.d") }
-            Opcode::Pavg1 => { write!(f, "pavg1") }
-            Opcode::Pavg1_raz => { write!(f, "pavg1.raz") }
-            Opcode::Pavgsub1 => { write!(f, "pavgsub1") }
-            Opcode::Pcmp1_eq => { write!(f, "pcmp1.eq") }
-            Opcode::Pcmp1_gt => { write!(f, "pcmp1.gt") }
-            Opcode::Padd2 => { write!(f, "padd2") }
-            Opcode::Padd2_sss => { write!(f, "padd2.sss") }
-            Opcode::Padd2_uuu => { write!(f, "padd2.uuu") }
-            Opcode::Padd2_uus => { write!(f, "padd2.uus") }
-            Opcode::Psub2 => { write!(f, "psub2") }
-            Opcode::Psub2_sss => { write!(f, "psub2.sss") }
-            Opcode::Psub2_uuu => { write!(f, "psub2.uuu") }
-            Opcode::Psub2_uus => { write!(f, "psub2.uus") }
-            Opcode::Pavg2 => { write!(f, "pavg2") }
-            Opcode::Pavg2_raz => { write!(f, "pavg2.raz") }
-            Opcode::Pavgsub2 => { write!(f, "pavgsub2") }
-            Opcode::Pshladd2 => { write!(f, "pshladd2") }
-            Opcode::Pshradd2 => { write!(f, "pshradd2") }
-            Opcode::Pcmp2_eq => { write!(f, "pcmp2.eq") }
-            Opcode::Pcmp2_gt => { write!(f, "pcmp2.gt") }
-            Opcode::Padd4 => { write!(f, "padd4") }
-            Opcode::Psub4 => { write!(f, "psub4") }
-            Opcode::Pcmp4_eq => { write!(f, "pcmp4.eq") }
-            Opcode::Pcmp4_gt => { write!(f, "pcmp4.gt") }
-            Opcode::Hint_x => { write!(f, "hint.x") }
-            Opcode::Nop_x => { write!(f, "nop.x") }
-            Opcode::Movl => { write!(f, "movl") }
-            Opcode::Mov => { write!(f, "mov") }
-            Opcode::Brl_cond_bwh_ph_dh => { write!(f, "brl.cond.bwh.ph.dh") }
-            Opcode::Brl_call_bwh_ph_dh => { write!(f, "brl.call.bwh.ph.dh") }
-            Opcode::Br_call => { write!(f, "br.call") }
-            Opcode::Brp_ipwh_ih => { write!(f, "brp.ipwh.ih") }
-            Opcode::Break_x => { write!(f, "break.x") }
-            Opcode::Break_i => { write!(f, "break.i") }
-            Opcode::Zxt1 => { write!(f, "zxt1") }
-            Opcode::Zxt2 => { write!(f, "zxt2") }
-            Opcode::Zxt4 => { write!(f, "zxt4") }
-            Opcode::Sxt1 => { write!(f, "sxt1") }
-            Opcode::Sxt2 => { write!(f, "sxt2") }
-            Opcode::Sxt4 => { write!(f, "sxt4") }
-            Opcode::Czx1_l => { write!(f, "czx1.l") }
-            Opcode::Czx2_l => { write!(f, "czx2.l") }
-            Opcode::Czx1_r => { write!(f, "czx1.r") }
-            Opcode::Czx2_r => { write!(f, "czx2.r") }
-            Opcode::Hint_i => { write!(f, "hint.i") }
-            Opcode::Nop_i => { write!(f, "nop.i") }
-            Opcode::Chk_s_i_int => { write!(f, "chk.s.i.int") }
-            Opcode::Mov_mwh_ih => { write!(f, "mov") }
-            Opcode::Mov_ret_mwh_ih => { write!(f, "mov.ret") }
-            Opcode::Dep => { write!(f, "dep") }
-            Opcode::Tbit_z => { write!(f, "tbit.z") }
-            Opcode::Tnat_z => { write!(f, "tnat.z") }
-            Opcode::Tbit_z_unc => { write!(f, "tbit.z.unc") }
-            Opcode::Tnat_z_unc => { write!(f, "tnat.z.unc") }
-            Opcode::Tbit_z_and => { write!(f, "tbit.z.and") }
-            Opcode::Tnat_z_and => { write!(f, "tnat.z.and") }
-            Opcode::Tbit_nz_and => { write!(f, "tbit.nz.and") }
-            Opcode::Tnat_nz_and => { write!(f, "tnat.nz.and") }
-            Opcode::Tbit_z_or => { write!(f, "tbit.z.or") }
-            Opcode::Tnat_z_or => { write!(f, "tnat.z.or") }
-            Opcode::Tbit_nz_or => { write!(f, "tbit.nz.or") }
-            Opcode::Tnat_nz_or => { write!(f, "tnat.nz.or") }
-            Opcode::Tbit_z_or_andcm => { write!(f, "tbit.z.or.andcm") }
-            Opcode::Tnat_z_or_andcm => { write!(f, "tnat.z.or.andcm") }
-            Opcode::Tbit_nz_or_andcm => { write!(f, "tbit.nz.or.andcm") }
-            Opcode::Tnat_nz_or_andcm => { write!(f, "tnat.nz.or.andcm") }
-            Opcode::Tf_z => { write!(f, "tf.z") }
-            Opcode::Tf_z_nc => { write!(f, "tf.z.nc") }
-            Opcode::Tf_z_and => { write!(f, "tf.z.and") }
-            Opcode::Tf_nz_and => { write!(f, "tf.nz.and") }
-            Opcode::Tf_z_or => { write!(f, "tf.z.or") }
-            Opcode::Tf_nz_or => { write!(f, "tf.nz.or") }
-            Opcode::Tf_z_or_andcm => { write!(f, "tf.z.or.andcm") }
-            Opcode::Tf_nz_or_andcm => { write!(f, "tf.nz.or.andcm") }
-            Opcode::Dep_z => { write!(f, "dep.z") }
-            Opcode::Extr => { write!(f, "extr") }
-            Opcode::Shrp => { write!(f, "shrp") }
-            Opcode::Extr_u => { write!(f, "extr.u") }
-            Opcode::Pmin1_u => { write!(f, "pmin1.u") }
-            Opcode::Unpack1_h => { write!(f, "unpack1.h") }
-            Opcode::Pmax1_u => { write!(f, "pmax1.u") }
-            Opcode::Unpack1_l => { write!(f, "unpack1.l") }
-            Opcode::Mix1_r => { write!(f, "mix1.r") }
-            Opcode::Mix1_l => { write!(f, "mix1.l") }
-            Opcode::Psad1 => { write!(f, "psad1") }
-            Opcode::Mux1 => { write!(f, "mux1") }
-            Opcode::Pshr2_u => { write!(f, "pshr2.u") }
-            Opcode::Pmpyshr2_u => { write!(f, "pmpyshr2.u") }
-            Opcode::Pshr2 => { write!(f, "pshr2") }
-            Opcode::Pmpyshr2 => { write!(f, "pmpyshr2") }
-            Opcode::Pshl1 => { write!(f, "pshl1") }
-            Opcode::Pshr2_u_fixed => { write!(f, "pshr2.u.fixed") }
-            Opcode::Pshr2_fixed => { write!(f, "pshr2.fixed") }
-            Opcode::Popcnt => { write!(f, "popcnt") }
-            Opcode::Clz => { write!(f, "clz") }
-            Opcode::Pack2_uss => { write!(f, "pack2.uss") }
-            Opcode::Pack2_sss => { write!(f, "pack2.sss") }
-            Opcode::Pmin2 => { write!(f, "pmin2") }
-            Opcode::Unpack2_h => { write!(f, "unpack2.h") }
-            Opcode::Unpack2_l => { write!(f, "unpack2.l") }
-            Opcode::Pmax2 => { write!(f, "pmax2") }
-            Opcode::Mix2_r => { write!(f, "mix2.r") }
-            Opcode::Mix2_l => { write!(f, "mix2.l") }
-            Opcode::Pmpy2_r => { write!(f, "pmpy2.r") }
-            Opcode::Pmpy2_l => { write!(f, "pmpy2.l") }
-            Opcode::Pshl2_fixed => { write!(f, "pshl2.fixed") }
-            Opcode::Mux2 => { write!(f, "mux2") }
-            Opcode::Pshr4_u => { write!(f, "pshr4.u") }
-            Opcode::Pshr4 => { write!(f, "pshr4") }
-            Opcode::Pshl4 => { write!(f, "pshl4") }
-            Opcode::Mpy4 => { write!(f, "mpy4") }
-            Opcode::Mpyshl4 => { write!(f, "mpyshl4") }
-            Opcode::Pshr4_u_fixed => { write!(f, "pshr4.u.fixed") }
-            Opcode::Pshr4_fixed => { write!(f, "pshr4.fixed") }
-            Opcode::Pack4_sss => { write!(f, "pack4.sss") }
-            Opcode::Unpack4_h => { write!(f, "unpack4.h") }
-            Opcode::Unpack4_l => { write!(f, "unpack4.l") }
-            Opcode::Mix4_r => { write!(f, "mix4.r") }
-            Opcode::Mix4_l => { write!(f, "mix4.l") }
-            Opcode::Pshl4_fixed => { write!(f, "pshl4.fixed") }
-            Opcode::Shr_u => { write!(f, "shr.u") }
-            Opcode::Shr => { write!(f, "shr") }
-            Opcode::Shl => { write!(f, "shl") }
-
-            Opcode::Break_b => { write!(f, "break.b") }
-            Opcode::Cover => { write!(f, "cover") }
-            Opcode::Clrrb => { write!(f, "clrrb") }
-            Opcode::Clrrb_pr => { write!(f, "clrrb.pr") }
-            Opcode::Rfi => { write!(f, "rfi") }
-            Opcode::Bsw_0 => { write!(f, "bsw.0") }
-            Opcode::Bsw_1 => { write!(f, "bsw.1") }
-            Opcode::Epc => { write!(f, "epc") }
-            Opcode::Vmsw_0 => { write!(f, "vmsw.0") }
-            Opcode::Vmsw_1 => { write!(f, "vmsw.1") }
-            Opcode::Br_cond => { write!(f, "br.cond") }
-            Opcode::Br_ia => { write!(f, "br.ia") }
-            Opcode::Br_ret => { write!(f, "br.ret") }
-
-            Opcode::Nop_b => { write!(f, "nop.b") }
-            Opcode::Hint_b => { write!(f, "hint.b") }
-            Opcode::Brp => { write!(f, "brp") }
-            Opcode::Brp_ret => { write!(f, "brp.ret") }
-
-            Opcode::Br_wexit => { write!(f, "br.wexit") }
-            Opcode::Br_wtop => { write!(f, "br.wtop") }
-            Opcode::Br_cloop => { write!(f, "br.cloop") }
-            Opcode::Br_cexit => { write!(f, "br.cexit") }
-            Opcode::Br_ctop => { write!(f, "br.ctop") }
-
-            Opcode::Frcpa => { write!(f, "frcpa") }
-            Opcode::Frsqta => { write!(f, "frsqta") }
-            Opcode::Break_f => { write!(f, "break.f") }
-            Opcode::Fsetc => { write!(f, "fsetc") }
-            Opcode::Fclrf => { write!(f, "fclrf") }
-            Opcode::Fchkf => { write!(f, "fchkf") }
-            Opcode::Fmerge_s => { write!(f, "fmerge.s") }
-            Opcode::Fmerge_ns => { write!(f, "fmerge.ns") }
-            Opcode::Fmerge_se => { write!(f, "fmerge.se") }
-
-            Opcode::Fmin => { write!(f, "fmin") }
-            Opcode::Fmax => { write!(f, "fmax") }
-            Opcode::Famin => { write!(f, "famin") }
-            Opcode::Famax => { write!(f, "famax") }
-            Opcode::Fcvt_fx => { write!(f, "fcvt.fx") }
-            Opcode::Fcvt_fxu => { write!(f, "fcvt.fxu") }
-            Opcode::Fcvt_fx_trunc => { write!(f, "fcvt.fx.trunc") }
-            Opcode::Fcvt_fxu_trunc => { write!(f, "fcvt.fxu.trunc") }
-            Opcode::Fcvt_xf => { write!(f, "fcvt.xf") }
-            Opcode::Fpack => { write!(f, "fpack") }
-            Opcode::Fand => { write!(f, "fand") }
-            Opcode::Fandcm => { write!(f, "fandcm") }
-            Opcode::For => { write!(f, "for") }
-            Opcode::Fxor => { write!(f, "fxor") }
-
-            Opcode::Fswap => { write!(f, "fswap") }
-            Opcode::Fswap_nl => { write!(f, "fswap.nl") }
-            Opcode::Fswap_nr => { write!(f, "fswap.nr") }
-            Opcode::Fmix_lr => { write!(f, "fmix.lr") }
-            Opcode::Fmix_r => { write!(f, "fmix.r") }
-            Opcode::Fmix_l => { write!(f, "fmix.l") }
-
-            Opcode::Fsxt_r => { write!(f, "fsxt.r") }
-            Opcode::Fsxt_l => { write!(f, "fsxt.l") }
-
-            Opcode::Hint_f => { write!(f, "hint.f") }
-            Opcode::Nop_f => { write!(f, "nop.f") }
-
-            Opcode::Fprcpa => { write!(f, "fprcpa") }
-            Opcode::Fprsqrta => { write!(f, "fprsqrta") }
-            Opcode::Fpmerge_s => { write!(f, "fpmerge.s") }
-            Opcode::Fpmerge_ns => { write!(f, "fpmerge.ns") }
-            Opcode::Fpmerge_se => { write!(f, "fpmerge.se") }
-
-            Opcode::Fpmin => { write!(f, "fpmin") }
-            Opcode::Fpmax => { write!(f, "fpmax") }
-            Opcode::Fpamin => { write!(f, "fpamin") }
-            Opcode::Fpamax => { write!(f, "fpamax") }
-            Opcode::Fpcvt_fx => { write!(f, "fpcvt.fx") }
-            Opcode::Fpcvt_fxu => { write!(f, "fpcvt.fxu") }
-            Opcode::Fpcvt_fx_trunc => { write!(f, "fpcvt.fx.trunc") }
-            Opcode::Fpcvt_fxu_trunc => { write!(f, "fpcvt.fxu.trunc") }
-            Opcode::Fcmp_eq => { write!(f, "fcmp.eq") }
-            Opcode::Fcmp_lt => { write!(f, "fcmp.lt") }
-            Opcode::Fcmp_le => { write!(f, "fcmp.le") }
-            Opcode::Fcmp_unord => { write!(f, "fcmp.unord") }
-            Opcode::Fcmp_eq_unc => { write!(f, "fcmp.eq.unc") }
-            Opcode::Fcmp_lt_unc => { write!(f, "fcmp.lt.unc") }
-            Opcode::Fcmp_le_unc => { write!(f, "fcmp.le.unc") }
-            Opcode::Fcmp_unord_unc => { write!(f, "fcmp.unord.unc") }
-            Opcode::Fclass_m_unc => { write!(f, "fclass.m.unc") }
-            Opcode::Fclass_m => { write!(f, "fclass.m") }
-            Opcode::Fma_s => { write!(f, "fma.s") }
-            Opcode::Fnorm => { write!(f, "fnorm") }
-            Opcode::Fnorm_d => { write!(f, "fnorm.d") }
-            Opcode::Fnorm_s => { write!(f, "fnorm.s") }
-            Opcode::Fmpy => { write!(f, "fmpy") }
-            Opcode::Fmpy_d => { write!(f, "fmpy.d") }
-            Opcode::Fmpy_s => { write!(f, "fmpy.s") }
-            Opcode::Fma => { write!(f, "fma") }
-            Opcode::Fpma => { write!(f, "fpma") }
-            Opcode::Fma_d => { write!(f, "fma.d") }
-            Opcode::Fms_s => { write!(f, "fms.s") }
-            Opcode::Fms => { write!(f, "fms") }
-            Opcode::Fpms => { write!(f, "fpms") }
-            Opcode::Fms_d => { write!(f, "fms.d") }
-            Opcode::Fnma_s => { write!(f, "fnma.s") }
-            Opcode::Fnma => { write!(f, "fnma") }
-            Opcode::Fpnma => { write!(f, "fpnma") }
-            Opcode::Fnma_d => { write!(f, "fnma.d") }
-            Opcode::Xma_l => { write!(f, "xma.l") }
-            Opcode::Xma_hu => { write!(f, "xma.hu") }
-            Opcode::Xma_h => { write!(f, "xma.h") }
-            Opcode::Xmpy_l => { write!(f, "xmpy.l") }
-            Opcode::Xmpy_hu => { write!(f, "xmpy.hu") }
-            Opcode::Xmpy_h => { write!(f, "xmpy.h") }
-            Opcode::Fselect => { write!(f, "fselect") }
-
-            Opcode::Cmp4_eq => { write!(f, "cmp4.eq") },
-            Opcode::Cmp4_eq_and => { write!(f, "cmp4.eq.and") },
-            Opcode::Cmp4_eq_or => { write!(f, "cmp4.eq.or") },
-            Opcode::Cmp4_eq_or_andcm => { write!(f, "cmp4.eq.or.andcm") },
-            Opcode::Cmp4_eq_unc => { write!(f, "cmp4.eq.unc") },
-            Opcode::Cmp4_ge_and => { write!(f, "cmp4.ge.and") },
-            Opcode::Cmp4_ge_or => { write!(f, "cmp4.ge.or") },
-            Opcode::Cmp4_ge_or_andcm => { write!(f, "cmp4.ge.or.andcm") },
-            Opcode::Cmp4_gt_and => { write!(f, "cmp4.gt.and") },
-            Opcode::Cmp4_gt_or => { write!(f, "cmp4.gt.or") },
-            Opcode::Cmp4_gt_or_andcm => { write!(f, "cmp4.gt.or.andcm") },
-            Opcode::Cmp4_le_and => { write!(f, "cmp4.le.and") },
-            Opcode::Cmp4_le_or => { write!(f, "cmp4.le.or") },
-            Opcode::Cmp4_le_or_andcm => { write!(f, "cmp4.le.or.andcm") },
-            Opcode::Cmp4_lt => { write!(f, "cmp4.lt") },
-            Opcode::Cmp4_lt_and => { write!(f, "cmp4.lt.and") },
-            Opcode::Cmp4_lt_or => { write!(f, "cmp4.lt.or") },
-            Opcode::Cmp4_lt_or_andcm => { write!(f, "cmp4.lt.or.andcm") },
-            Opcode::Cmp4_lt_unc => { write!(f, "cmp4.lt.unc") },
-            Opcode::Cmp4_ltu => { write!(f, "cmp4.ltu") },
-            Opcode::Cmp4_ltu_unc => { write!(f, "cmp4.ltu.unc") },
-            Opcode::Cmp4_ne_and => { write!(f, "cmp4.ne.and") },
-            Opcode::Cmp4_ne_or => { write!(f, "cmp4.ne.or") },
-            Opcode::Cmp4_ne_or_andcm => { write!(f, "cmp4.ne.or.andcm") },
-            Opcode::Cmp_eq => { write!(f, "cmp.eq") },
-            Opcode::Cmp_eq_and => { write!(f, "cmp.eq.and") },
-            Opcode::Cmp_eq_or => { write!(f, "cmp.eq.or") },
-            Opcode::Cmp_eq_or_andcm => { write!(f, "cmp.eq.or.andcm") },
-            Opcode::Cmp_eq_unc => { write!(f, "cmp.eq.unc") },
-            Opcode::Cmp_ge_and => { write!(f, "cmp.ge.and") },
-            Opcode::Cmp_ge_or => { write!(f, "cmp.ge.or") },
-            Opcode::Cmp_ge_or_andcm => { write!(f, "cmp.ge.or.andcm") },
-            Opcode::Cmp_gt_and => { write!(f, "cmp.gt.and") },
-            Opcode::Cmp_gt_or => { write!(f, "cmp.gt.or") },
-            Opcode::Cmp_gt_or_andcm => { write!(f, "cmp.gt.or.andcm") },
-            Opcode::Cmp_le_and => { write!(f, "cmp.le.and") },
-            Opcode::Cmp_le_or => { write!(f, "cmp.le.or") },
-            Opcode::Cmp_le_or_andcm => { write!(f, "cmp.le.or.andcm") },
-            Opcode::Cmp_lt => { write!(f, "cmp.lt") },
-            Opcode::Cmp_lt_and => { write!(f, "cmp.lt.and") },
-            Opcode::Cmp_lt_or => { write!(f, "cmp.lt.or") },
-            Opcode::Cmp_lt_or_andcm => { write!(f, "cmp.lt.or.andcm") },
-            Opcode::Cmp_lt_unc => { write!(f, "cmp.lt.unc") },
-            Opcode::Cmp_ltu => { write!(f, "cmp.ltu") },
-            Opcode::Cmp_ltu_unc => { write!(f, "cmp.ltu.unc") },
-            Opcode::Cmp_ne_and => { write!(f, "cmp.ne.and") },
-            Opcode::Cmp_ne_or => { write!(f, "cmp.ne.or") },
-            Opcode::Cmp_ne_or_andcm => { write!(f, "cmp.ne.or.andcm") },
-        }
+        f.write_str(self.name())
     }
 }
 
-#[derive(Default, Debug, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Instruction {
     opcode: Opcode,
     sf: Option<u8>, // for float instructions, `sf` may indicate which FPSR status field to use.
@@ -1121,6 +1192,42 @@ impl fmt::Display for Instruction {
                 },
                 self.operands[0],
             )
+        } else if let Opcode::Brl_cond_bwh_ph_dh = self.opcode {
+            if self.predicate == 0 && self.operands[2].as_unsigned_imm() == 0 {
+                // same as `br`, `qp == 0` and `bwh == .sptk` is an unconditional branch
+                return write!(f, "brl{}{} {}{}",
+                    [".few", ".many"][self.operands[1].as_unsigned_imm() as usize],
+                    ["", ".clr"][self.operands[3].as_unsigned_imm() as usize],
+                    if self.operands[0].as_signed_imm() >= 0 { "$+" } else { "$" },
+                    self.operands[0],
+                )
+            }
+            return write!(f, "brl{}{}{}{} {}{}",
+                if self.predicate == 0 { "" } else { ".cond" },
+                [".sptk", ".spnt", ".dptk", ".dpnt"][self.operands[2].as_unsigned_imm() as usize],
+                [".few", ".many"][self.operands[1].as_unsigned_imm() as usize],
+                ["", ".clr"][self.operands[3].as_unsigned_imm() as usize],
+                if self.operands[0].as_signed_imm() >= 0 { "$+" } else { "$" },
+                self.operands[0],
+            )
+        } else if let Opcode::Brl_call_bwh_ph_dh = self.opcode {
+            return write!(f, "brl.call{}{}{} {}={}{}",
+                [".sptk", ".spnt", ".dptk", ".dpnt"][self.operands[3].as_unsigned_imm() as usize],
+                [".few", ".many"][self.operands[2].as_unsigned_imm() as usize],
+                ["", ".clr"][self.operands[4].as_unsigned_imm() as usize],
+                self.operands[0],
+                if self.operands[1].as_signed_imm() >= 0 { "$+" } else { "$" },
+                self.operands[1],
+            )
+        } else if let Opcode::Chk_s_m_int | Opcode::Chk_s_i_int | Opcode::Chk_s_fp |
+                Opcode::Chk_a_nc_int | Opcode::Chk_a_clr_int | Opcode::Chk_a_nc_fp | Opcode::Chk_a_clr_fp = self.opcode {
+            // recovery targets are ip-relative, like branch targets
+            return write!(f, "{} {},{}{}",
+                self.opcode,
+                self.operands[0],
+                if self.operands[1].as_signed_imm() >= 0 { "$+" } else { "$" },
+                self.operands[1],
+            )
         } else if let Opcode::Br_call = self.opcode {
             return write!(f, "br.call{}{}{} {}={}{}",
                 [".sptk", ".spnt", ".dptk", ".dpnt"][self.operands[3].as_unsigned_imm() as usize],
@@ -1266,7 +1373,7 @@ impl fmt::Display for Instruction {
         Ok(())
     }
 }
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct InstructionBundle {
     bundle_tag: u8,
     instructions: [Instruction; 3],
@@ -1294,6 +1401,25 @@ impl InstructionBundle {
             &self.instructions[..3]
         }
     }
+
+    /// retrieve the instruction executed from slot `slot` of this bundle. slots 1 and 2 of an
+    /// `MLX` bundle both name the `LX` instruction.
+    pub fn instruction_at(&self, slot: u8) -> Option<&Instruction> {
+        let instructions = self.instructions();
+        if slot == 2 && instructions.len() == 2 {
+            return instructions.get(1);
+        }
+        instructions.get(slot as usize)
+    }
+
+    /// retrieve each instruction in this bundle along with its address, if this bundle is at
+    /// `address`. the `LX` instruction of an `MLX` bundle is reported at slot 1.
+    pub fn instructions_at(&self, address: u64) -> impl Iterator<Item=(SlotAddress, &Instruction)> {
+        let bundle = address & !0xf;
+        self.instructions().iter().enumerate().map(move |(i, inst)| {
+            (SlotAddress::new(bundle, i as u8).expect("slot is in range"), inst)
+        })
+    }
 }
 impl yaxpeax_arch::LengthedInstruction for InstructionBundle {
     type Unit = yaxpeax_arch::AddressDiff<u64>;
@@ -1301,6 +1427,7 @@ impl yaxpeax_arch::LengthedInstruction for InstructionBundle {
     fn min_size() -> Self::Unit { AddressDiff::from_const(16) }
 }
 impl yaxpeax_arch::Instruction for InstructionBundle {
+    #[allow(clippy::collapsible_match)]
     fn well_defined(&self) -> bool {
         // Alloc has some special rules that need to be checked.
         let validate_alloc = |insn: &Instruction| {
@@ -1349,7 +1476,7 @@ impl yaxpeax_arch::Instruction for InstructionBundle {
         }
 
         // If alloc is in slot 1, there must be a stop before it.
-        let stop_before_slot1 = BUNDLE_TAGS[self.bundle_tag as usize].map_or(false, |(_, stops)| stops & 0b100 != 0);
+        let stop_before_slot1 = matches!(BUNDLE_TAGS[self.bundle_tag as usize], Some((_, stops)) if stops & 0b100 != 0);
         if self.instructions[1].opcode() == Opcode::Alloc && (!stop_before_slot1 || !validate_alloc(&self.instructions[1])) {
             return false;
         }
@@ -1357,8 +1484,11 @@ impl yaxpeax_arch::Instruction for InstructionBundle {
         true
     }
 }
-impl fmt::Display for InstructionBundle {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl InstructionBundle {
+    /// write this bundle as `Display` does, with each instruction written by `inst`, which is
+    /// given the instruction's index in `instructions()`.
+    pub(crate) fn fmt_with<F>(&self, f: &mut fmt::Formatter, mut inst: F) -> fmt::Result
+            where F: FnMut(&mut fmt::Formatter, usize, &Instruction) -> fmt::Result {
         let (stops, types) = if let Some((types, stops)) = BUNDLE_TAGS[self.bundle_tag as usize] {
             write!(f, "[{}{}{}]", types[0], types[1], types[2])?;
             ([(stops & 0b100) > 0, (stops & 0b010) > 0, (stops & 0b001) > 0], types)
@@ -1366,19 +1496,27 @@ impl fmt::Display for InstructionBundle {
             return write!(f, "tag: invalid ({})", self.bundle_tag);
         };
         if types[2] == InstructionType::X {
-            write!(f, " {}{}; {}{}",
-                &self.instructions[0], if stops[0] { ";" } else { "" },
-                &self.instructions[1], if stops[1] { ";;" } else { "" },
-            )
+            f.write_str(" ")?;
+            inst(f, 0, &self.instructions[0])?;
+            write!(f, "{}; ", if stops[0] { ";" } else { "" })?;
+            inst(f, 1, &self.instructions[1])?;
+            write!(f, "{}", if stops[1] { ";;" } else { "" })
         } else {
-            write!(f, " {}{}; {}{}; {}{}",
-                &self.instructions[0], if stops[0] { ";" } else { "" },
-                &self.instructions[1], if stops[1] { ";" } else { "" },
-                &self.instructions[2], if stops[2] { ";;" } else { "" },
-            )
+            f.write_str(" ")?;
+            inst(f, 0, &self.instructions[0])?;
+            write!(f, "{}; ", if stops[0] { ";" } else { "" })?;
+            inst(f, 1, &self.instructions[1])?;
+            write!(f, "{}; ", if stops[1] { ";" } else { "" })?;
+            inst(f, 2, &self.instructions[2])?;
+            write!(f, "{}", if stops[2] { ";;" } else { "" })
         }
     }
 }
+impl fmt::Display for InstructionBundle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with(f, |f, _, inst| write!(f, "{}", inst))
+    }
+}
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DecodeError {
     ExhaustedInput,
@@ -1424,35 +1562,55 @@ impl std::error::Error for DecodeError {
 #[derive(Default)]
 pub struct InstDecoder {}
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+impl InstDecoder {
+    /// decode the bundle containing `address`, where `bytes` holds code starting at the bundle
+    /// address `base`.
+    pub fn decode_bundle_at(&self, bytes: &[u8], base: u64, address: SlotAddress) -> Result<InstructionBundle, DecodeError> {
+        let start = address.bundle().wrapping_sub(base & !0xf);
+        if start >= bytes.len() as u64 {
+            return Err(DecodeError::ExhaustedInput);
+        }
+        let mut reader = yaxpeax_arch::U8Reader::new(&bytes[start as usize..]);
+        self.decode(&mut reader)
+    }
+
+    /// decode the instruction at `address`, where `bytes` holds code starting at the bundle
+    /// address `base`. slot 2 of an `MLX` bundle yields the `LX` instruction, same as slot 1.
+    pub fn decode_slot(&self, bytes: &[u8], base: u64, address: SlotAddress) -> Result<Instruction, DecodeError> {
+        let bundle = self.decode_bundle_at(bytes, base, address)?;
+        bundle.instruction_at(address.slot()).cloned().ok_or(DecodeError::BadBundle)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct GPRegister(pub u8); // 128 64-bit registers
 impl fmt::Display for GPRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "r{}", self.0)
     }
 }
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct FloatRegister(pub u8); // 128 82-bit registers
 impl fmt::Display for FloatRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "f{}", self.0)
     }
 }
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct PredicateRegister(pub u8); // 64 1-bit registers
 impl fmt::Display for PredicateRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "p{}", self.0)
     }
 }
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ControlRegister(pub u8);
 impl fmt::Display for ControlRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "cr{}", self.0)
     }
 }
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct BranchRegister(pub u8); // 8 64-bit registers
 impl fmt::Display for BranchRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -1489,7 +1647,7 @@ impl fmt::Display for IndirectionReg {
         }
     }
 }
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ApplicationRegister(pub u8); // 128 64-bit(?) registers
 impl fmt::Display for ApplicationRegister {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -1621,7 +1779,7 @@ impl fmt::Display for Operand {
             Operand::Memory(reg) => { write!(f, "[{}]", reg) },
             Operand::ImmU64(imm) => { write!(f, "{:#x}", imm) },
             Operand::ImmI64(imm) => { if imm < &0 {
-                write!(f, "-{:#x}", -imm)
+                write!(f, "-{:#x}", imm.wrapping_neg() as u64)
             } else {
                 write!(f, "{:#x}", imm)
             } },
@@ -1640,6 +1798,7 @@ impl fmt::Display for Operand {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Operand {
     fn default() -> Self {
         Operand::None
@@ -1771,6 +1930,7 @@ impl Decoder<IA64> for InstDecoder {
             }
         }
 
+        #[allow(clippy::manual_range_patterns)]
         fn decode_instruction(word: &BitSlice<Lsb0, u8>, ty: InstructionType) -> Instruction {
             let tag = word[37..41].load::<u8>();
 
@@ -2011,6 +2171,17 @@ fn four_op(dest: Option<u8>, op1: Operand, op2: Operand, op3: Operand, op4: Oper
     (dest, [op1, op2, op3, op4, Operand::None])
 }
 
+/// `brl` and `brl.call` displacements are `imm60`, made from `imm20b` and `i` in the `X` slot and
+/// `imm39` in the `L` slot, in bundles. like `B1`, this is scaled to a displacement in bytes.
+fn long_branch_displacement(word: &BitSlice<Lsb0, u8>, word2: &BitSlice<Lsb0, u8>) -> i64 {
+    let imm20b = word[13..33].load::<u64>();
+    let i = word[36] as u64;
+    let imm39 = word2[2..41].load::<u64>();
+    let imm60 = imm20b + (imm39 << 20) + (i << 59);
+    // `i` is the sign bit, and lands in bit 63 once scaled.
+    (imm60 << 4) as i64
+}
+
 fn read_l_operands(encoding: OperandEncodingX, word: &BitSlice<Lsb0, u8>, word2: &BitSlice<Lsb0, u8>) -> (Option<u8>, [Operand; 5]) {
     use OperandEncodingX::*;
     match encoding {
@@ -2021,9 +2192,9 @@ fn read_l_operands(encoding: OperandEncodingX, word: &BitSlice<Lsb0, u8>, word2:
             let imm20a = word[6..26].load::<u64>();
             let i = word[36];
             let imm41 = word2[0..41].load::<u64>();
-            let imm = (imm41 << 21) + ((i as u64) << 20) + imm20a;
-            // TODO: this is certainly assembled incorrectly
-            one_op(false, Operand::ImmU64(imm as u64))
+            // imm62 is `i:imm41:imm20a`
+            let imm = ((i as u64) << 61) | (imm41 << 20) | imm20a;
+            one_op(false, Operand::ImmU64(imm))
         }
         X2 => {
             let r1 = word[6..13].load::<u8>();
@@ -2045,47 +2216,48 @@ fn read_l_operands(encoding: OperandEncodingX, word: &BitSlice<Lsb0, u8>, word2:
             two_op(
                 Some(0),
                 Operand::GPRegister(GPRegister(r1)),
-                Operand::ImmU64(imm as u64)
+                Operand::ImmU64(imm)
             )
         }
         X3 => {
             let btype = word[6..9].load::<u8>();
             if btype != 0 {
-                // unclear what happens. invalid instruction?
+                // unclear what happens. invalid instruction? reported by `canonical::check`.
             }
-            let _p = word[12];
-            let imm20b = word[13..33].load::<u64>();
-            let _wh = word[33..35].load::<u64>();
-            let _d = word[35];
-            let i = word[36];
-            let imm39 = word2[2..41].load::<u64>();
-            // TODO: this is certainly assembled incorrectly
-            let imm = (imm39 << 21) + ((i as u64) << 20) + imm20b;
-            one_op(false, Operand::ImmU64(imm as u64))
+            let p = word[12];
+            let wh = word[33..35].load::<u8>();
+            let d = word[35];
+            four_op(
+                Option::None,
+                Operand::ImmI64(long_branch_displacement(word, word2)),
+                Operand::ImmU64(p as u64),
+                Operand::ImmU64(wh as u64),
+                Operand::ImmU64(d as u64),
+            )
         }
         X4 => {
             let b1 = word[6..9].load::<u8>();
-            let _p = word[12];
-            let imm20b = word[13..33].load::<u64>();
-            let _wh = word[33..35].load::<u64>();
-            let _d = word[35];
-            let i = word[36];
-            let imm39 = word2[2..41].load::<u64>();
-            // TODO: this is certainly assembled incorrectly
-            let imm = (imm39 << 21) + ((i as u64) << 20) + imm20b;
-            two_op(
-                Some(0),
-                Operand::BranchRegister(BranchRegister(b1)),
-                Operand::ImmU64(imm as u64)
+            let p = word[12];
+            let wh = word[33..35].load::<u8>();
+            let d = word[35];
+            (
+                Option::None,
+                [
+                    Operand::BranchRegister(BranchRegister(b1)),
+                    Operand::ImmI64(long_branch_displacement(word, word2)),
+                    Operand::ImmU64(p as u64),
+                    Operand::ImmU64(wh as u64),
+                    Operand::ImmU64(d as u64),
+                ]
             )
         }
         X5 => {
             let imm20 = word[6..26].load::<u64>();
             let i = word[36];
             let imm41 = word2[0..41].load::<u64>();
-            // TODO: this is certainly assembled incorrectly
-            let imm = (imm41 << 21) + ((i as u64) << 20) + imm20;
-            one_op(false, Operand::ImmU64(imm as u64))
+            // imm62 is `i:imm41:imm20`
+            let imm = ((i as u64) << 61) | (imm41 << 20) | imm20;
+            one_op(false, Operand::ImmU64(imm))
         }
     }
 }
@@ -2546,14 +2718,16 @@ fn read_i_operands(encoding: OperandEncodingI, word: &BitSlice<Lsb0, u8>) -> (Op
             )
         },
         I20 => {
-            let p1 = word[6..12].load::<u8>();
-            let r3 = word[20..27].load::<u8>();
-            let p2 = word[27..33].load::<u8>();
-            three_op(
+            // same layout as `M20`, for `chk.s.i`.
+            let imm7a = word[6..13].load::<u32>();
+            let r2 = word[13..20].load::<u8>();
+            let imm13c = word[20..33].load::<u32>();
+            let s = word[36] as u32;
+            let imm = (((imm7a + (imm13c << 7) + (s << 20)) as i32) << 11) >> 7;
+            two_op(
                 Option::None,
-                Operand::PredicateRegister(PredicateRegister(p1)),
-                Operand::PredicateRegister(PredicateRegister(p2)),
-                Operand::GPRegister(GPRegister(r3)),
+                Operand::GPRegister(GPRegister(r2)),
+                Operand::ImmI64(imm as i64),
             )
         }
         I21 => {
@@ -2902,7 +3076,8 @@ fn read_m_operands(encoding: OperandEncodingM, word: &BitSlice<Lsb0, u8>) -> (Op
             let r2 = word[13..20].load::<u8>();
             let imm13c = word[20..33].load::<u32>();
             let s = word[36] as u32;
-            let imm = (((imm7a + (imm13c << 7) + (s << 20)) as i32) << 11) >> 11;
+            // `target25` is a displacement in bundles. scale it to bytes, as for `B1`.
+            let imm = (((imm7a + (imm13c << 7) + (s << 20)) as i32) << 11) >> 7;
             two_op(
                 Option::None,
                 Operand::GPRegister(GPRegister(r2)),
@@ -2914,7 +3089,7 @@ fn read_m_operands(encoding: OperandEncodingM, word: &BitSlice<Lsb0, u8>) -> (Op
             let f2 = word[13..20].load::<u8>();
             let imm13c = word[20..33].load::<u32>();
             let s = word[36] as u32;
-            let imm = (((imm7a + (imm13c << 7) + (s << 20)) as i32) << 11) >> 11;
+            let imm = (((imm7a + (imm13c << 7) + (s << 20)) as i32) << 11) >> 7;
             two_op(
                 Option::None,
                 Operand::FloatRegister(FloatRegister(f2)),
@@ -2925,7 +3100,7 @@ fn read_m_operands(encoding: OperandEncodingM, word: &BitSlice<Lsb0, u8>) -> (Op
             let r1 = word[6..13].load::<u8>();
             let imm20b = word[13..33].load::<u32>();
             let s = word[36] as u32;
-            let imm = ((imm20b + (s << 20)) << 11) >> 11;
+            let imm = (((imm20b + (s << 20)) as i32) << 11) >> 7;
             two_op(
                 Option::None,
                 Operand::GPRegister(GPRegister(r1)),
@@ -2936,7 +3111,7 @@ fn read_m_operands(encoding: OperandEncodingM, word: &BitSlice<Lsb0, u8>) -> (Op
             let f1 = word[6..13].load::<u8>();
             let imm20b = word[13..33].load::<u32>();
             let s = word[36] as u32;
-            let imm = ((imm20b + (s << 20)) << 11) >> 11;
+            let imm = (((imm20b + (s << 20)) as i32) << 11) >> 7;
             two_op(
                 Option::None,
                 Operand::FloatRegister(FloatRegister(f1)),
@@ -3275,9 +3450,9 @@ fn read_a_operands(encoding: OperandEncodingA, word: &BitSlice<Lsb0, u8>) -> (Op
         },
         A7 => {
             let p1 = word[6..12].load::<u8>();
-            let r2 = word[13..20].load::<u8>();
-            // TODO: what happens if this isn't zero?
-            assert_eq!(r2, 0);
+            // `r2` is architecturally zero here, and the compare is against `r0` regardless of
+            // what's in the field. a non-zero field is reported by `canonical::check`.
+            let _r2 = word[13..20].load::<u8>();
             let r3 = word[20..27].load::<u8>();
             let p2 = word[27..33].load::<u8>();
             four_op(
@@ -3360,6 +3535,7 @@ fn get_l_opcode_and_encoding(tag: u8, word: &BitSlice<Lsb0, u8>) -> (Opcode, Ope
     }
 }
 
+#[allow(clippy::manual_range_patterns)]
 fn get_b_opcode_and_encoding(tag: u8, word: &BitSlice<Lsb0, u8>) -> (Opcode, OperandEncodingB) {
     use Opcode::*;
     use OperandEncodingB::*;
@@ -0,0 +1,1037 @@
+//! lifting instructions to a small IR.
+//!
+//! `lift` translates one decoded instruction into a list of `Statement`s. the IR is small on
+//! purpose, so an analysis can handle every statement without knowing anything about ia64
+//! opcodes:
+//!
+//! * every value is 64 bits wide. predicates and NaT bits are 0 or 1.
+//! * registers are `registers::Location`s, and each general register's NaT bit is a separate
+//!   `Var::Nat`. `r0`, `p0`, `f0`, and `f1` are never variables: reading them yields a constant
+//!   and writing them does nothing.
+//! * temporaries are numbered from 0 for each lifted instruction and are assigned exactly once,
+//!   so building SSA form only needs registers renamed.
+//! * predication is explicit. a statement with a guard does nothing unless the guard is 1, and a
+//!   guarded assignment leaves its destination unchanged when it does nothing. most statements
+//!   are guarded by the qualifying predicate; conditional effects (a `cmpxchg` store, a `chk.s`
+//!   branch, a parallel compare's writes) are guarded by temporaries.
+//! * statements run in order, and each reads what the statements before it wrote.
+//!
+//! integer (`A`), `I`-unit, memory (`M`), and branch (`B`) instructions are lifted to assignments,
+//! loads, stores, and branches, with NaT bits propagated as the manual describes. the rest -
+//! floating-point arithmetic, floating-point loads and stores, multimedia instructions, system
+//! instructions, and the register rotation of the modulo-scheduled loop branches - are lifted to
+//! `Operation::Intrinsic`s that name the registers they read and write. the ALAT and the register
+//! stack are not modeled, memory is little-endian, and faults are not represented.
+
+use core::fmt;
+
+use crate::{ApplicationRegister, ControlRegister, FloatRegister, GPRegister, Instruction, Opcode, Operand, PredicateRegister, SlotAddress};
+use crate::predicate::{CompareType, predicate_write};
+use crate::registers::{Location, accesses};
+
+/// a variable: a register, the NaT bit of a general register, or a temporary.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Var {
+    Location(Location),
+    /// the NaT bit of a general register.
+    Nat(GPRegister),
+    /// a temporary, assigned once and local to the lifted instruction.
+    Temp(u16),
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Var::Location(location) => match location {
+                Location::GPRegister(r) => write!(f, "{}", r),
+                Location::FloatRegister(r) => write!(f, "{}", r),
+                Location::PredicateRegister(r) => write!(f, "{}", r),
+                Location::BranchRegister(r) => write!(f, "{}", r),
+                Location::ApplicationRegister(r) => write!(f, "{}", r),
+                Location::ControlRegister(r) => write!(f, "{}", r),
+                Location::PR => write!(f, "pr"),
+                Location::PSR => write!(f, "psr"),
+                Location::IP => write!(f, "ip"),
+            },
+            Var::Nat(r) => write!(f, "{}.nat", r),
+            Var::Temp(t) => write!(f, "t{}", t),
+        }
+    }
+}
+
+/// an operand of an expression.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Var(Var),
+    Const(u64),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Var(var) => write!(f, "{}", var),
+            Value::Const(c) => write!(f, "{:#x}", c),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    /// bitwise complement.
+    Not,
+    /// number of bits set.
+    Popcount,
+    /// number of leading zero bits; 64 for zero.
+    LeadingZeros,
+}
+
+/// binary operators. comparisons yield 0 or 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    /// the low 64 bits of the product.
+    Mul,
+    And,
+    Or,
+    Xor,
+    /// shift left. the count is unsigned, and counts of 64 or more shift out every bit.
+    Shl,
+    /// logical shift right, with counts as for `Shl`.
+    Shr,
+    /// arithmetic shift right. counts of 64 or more fill the result with the sign bit.
+    Sar,
+    Eq,
+    Ne,
+    /// signed less than.
+    Lt,
+    /// signed less than or equal.
+    Le,
+    /// unsigned less than.
+    Ltu,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Value(Value),
+    Unary(UnaryOp, Value),
+    Binary(BinaryOp, Value, Value),
+    /// bits `pos` through `pos + len - 1` of `value`, zero- or sign-extended. `pos + len` is at
+    /// most 64.
+    Extract { value: Value, pos: u8, len: u8, signed: bool },
+    /// `value` with bits `pos` through `pos + len - 1` replaced by the low `len` bits of `field`.
+    /// `pos + len` is at most 64.
+    Deposit { value: Value, field: Value, pos: u8, len: u8 },
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Value(value) => write!(f, "{}", value),
+            Expr::Unary(op, value) => {
+                let name = match op {
+                    UnaryOp::Not => "not",
+                    UnaryOp::Popcount => "popcnt",
+                    UnaryOp::LeadingZeros => "clz",
+                };
+                write!(f, "{} {}", name, value)
+            }
+            Expr::Binary(op, a, b) => {
+                let name = match op {
+                    BinaryOp::Add => "add",
+                    BinaryOp::Sub => "sub",
+                    BinaryOp::Mul => "mul",
+                    BinaryOp::And => "and",
+                    BinaryOp::Or => "or",
+                    BinaryOp::Xor => "xor",
+                    BinaryOp::Shl => "shl",
+                    BinaryOp::Shr => "shr",
+                    BinaryOp::Sar => "sar",
+                    BinaryOp::Eq => "eq",
+                    BinaryOp::Ne => "ne",
+                    BinaryOp::Lt => "lt",
+                    BinaryOp::Le => "le",
+                    BinaryOp::Ltu => "ltu",
+                };
+                write!(f, "{} {}, {}", name, a, b)
+            }
+            Expr::Extract { value, pos, len, signed } => {
+                write!(f, "{} {}, {}, {}", if *signed { "extr" } else { "extr.u" }, value, pos, len)
+            }
+            Expr::Deposit { value, field, pos, len } => {
+                write!(f, "dep {}, {}, {}, {}", value, field, pos, len)
+            }
+        }
+    }
+}
+
+/// why a branch transfers control.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BranchKind {
+    /// an ordinary branch, including loop branches.
+    Jump,
+    /// a call. the return address has already been written to the link register.
+    Call,
+    Return,
+    /// to the recovery code of a `chk.s` or `chk.a`.
+    Recovery,
+    /// `rfi`, to `cr.iip`.
+    InterruptionReturn,
+    /// `br.ia`, to IA-32 code.
+    Ia32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Assign { dest: Var, expr: Expr },
+    /// `dest` gets a value this IR does not describe.
+    Undefined { dest: Var },
+    /// `dest` gets the `size`-byte value at `address`, zero-extended.
+    Load { dest: Var, address: Value, size: u8 },
+    /// the low `size` bytes of `value` are stored at `address`.
+    Store { address: Value, value: Value, size: u8 },
+    /// control goes to `target`, which is bundle-aligned in ia64 code.
+    Branch { kind: BranchKind, target: Value },
+    /// an effect of `opcode` this IR does not describe, reading and writing the listed variables.
+    /// it may also access memory or other machine state, as `opcode` implies.
+    Intrinsic { opcode: Opcode, reads: Vec<Var>, writes: Vec<Var> },
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Assign { dest, expr } => write!(f, "{} = {}", dest, expr),
+            Operation::Undefined { dest } => write!(f, "{} = undefined", dest),
+            Operation::Load { dest, address, size } => write!(f, "{} = load{} [{}]", dest, size, address),
+            Operation::Store { address, value, size } => write!(f, "store{} [{}] = {}", size, address, value),
+            Operation::Branch { kind, target } => {
+                let name = match kind {
+                    BranchKind::Jump => "br",
+                    BranchKind::Call => "br.call",
+                    BranchKind::Return => "br.ret",
+                    BranchKind::Recovery => "br.recover",
+                    BranchKind::InterruptionReturn => "rfi",
+                    BranchKind::Ia32 => "br.ia",
+                };
+                write!(f, "{} {}", name, target)
+            }
+            Operation::Intrinsic { opcode, reads, writes } => {
+                for (i, var) in writes.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { "" } else { ", " }, var)?;
+                }
+                if !writes.is_empty() {
+                    write!(f, " = ")?;
+                }
+                write!(f, "{}(", opcode)?;
+                for (i, var) in reads.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { "" } else { ", " }, var)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// one IR operation, executed only if `guard` is `None` or holds 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub guard: Option<Var>,
+    pub operation: Operation,
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(guard) = self.guard {
+            write!(f, "({}) ", guard)?;
+        }
+        write!(f, "{}", self.operation)
+    }
+}
+
+/// lift `inst`, at `address`, to IR statements. the address gives values to ip-relative branch
+/// targets, `mov r1=ip`, and the return address of calls.
+pub fn lift(inst: &Instruction, address: SlotAddress) -> Vec<Statement> {
+    let guard = if inst.predicate() == 0 {
+        None
+    } else {
+        Some(Var::Location(Location::PredicateRegister(PredicateRegister(inst.predicate()))))
+    };
+    let mut lifter = Lifter { inst, address, guard, statements: Vec::new(), temps: 0 };
+    lifter.lift();
+    lifter.statements
+}
+
+/// access size and kind of an integer load.
+fn load(opcode: Opcode) -> Option<(u8, LoadKind)> {
+    use Opcode::*;
+    Some(match opcode {
+        Ld1 | Ld1_a | Ld1_bias | Ld1_acq | Ld1_c_clr | Ld1_c_nc | Ld1_c_clr_acq => (1, LoadKind::Plain),
+        Ld2 | Ld2_a | Ld2_bias | Ld2_acq | Ld2_c_clr | Ld2_c_nc | Ld2_c_clr_acq => (2, LoadKind::Plain),
+        Ld4 | Ld4_a | Ld4_bias | Ld4_acq | Ld4_c_clr | Ld4_c_nc | Ld4_c_clr_acq => (4, LoadKind::Plain),
+        Ld8 | Ld8_a | Ld8_bias | Ld8_acq | Ld8_c_clr | Ld8_c_nc | Ld8_c_clr_acq => (8, LoadKind::Plain),
+        Ld1_s | Ld1_sa => (1, LoadKind::Speculative),
+        Ld2_s | Ld2_sa => (2, LoadKind::Speculative),
+        Ld4_s | Ld4_sa => (4, LoadKind::Speculative),
+        Ld8_s | Ld8_sa => (8, LoadKind::Speculative),
+        Ld8_fill => (8, LoadKind::Fill),
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LoadKind {
+    /// the NaT bit of the target is cleared. this includes advanced and check loads, whose ALAT
+    /// effects aren't modeled: when the program is correct, they produce the value in memory.
+    Plain,
+    /// a deferred fault sets the NaT bit of the target instead.
+    Speculative,
+    /// `ld8.fill`, which takes the NaT bit from `ar.unat`.
+    Fill,
+}
+
+fn store_size(opcode: Opcode) -> Option<u8> {
+    use Opcode::*;
+    Some(match opcode {
+        St1 | St1_rel => 1,
+        St2 | St2_rel => 2,
+        St4 | St4_rel => 4,
+        St8 | St8_rel | St8_spill => 8,
+        _ => return None,
+    })
+}
+
+fn semaphore_size(opcode: Opcode) -> Option<u8> {
+    use Opcode::*;
+    Some(match opcode {
+        Cmpxchg1_acq | Cmpxchg1_rel | Xchg1 => 1,
+        Cmpxchg2_acq | Cmpxchg2_rel | Xchg2 => 2,
+        Cmpxchg4_acq | Cmpxchg4_rel | Xchg4 | Fetchadd4_acq | Fetchadd4_rel => 4,
+        Cmpxchg8_acq | Cmpxchg8_rel | Xchg8 | Fetchadd8_acq | Fetchadd8_rel => 8,
+        _ => return None,
+    })
+}
+
+/// the relation an integer compare tests: the operator, whether its operands are swapped, and
+/// whether it compares only the low 32 bits (`cmp4`).
+fn relation(opcode: Opcode) -> Option<(BinaryOp, bool, bool)> {
+    use Opcode::*;
+    Some(match opcode {
+        Cmp_eq | Cmp_eq_unc | Cmp_eq_and | Cmp_eq_or | Cmp_eq_or_andcm => (BinaryOp::Eq, false, false),
+        Cmp_ne_and | Cmp_ne_or | Cmp_ne_or_andcm => (BinaryOp::Ne, false, false),
+        Cmp_lt | Cmp_lt_unc | Cmp_lt_and | Cmp_lt_or | Cmp_lt_or_andcm => (BinaryOp::Lt, false, false),
+        Cmp_le_and | Cmp_le_or | Cmp_le_or_andcm => (BinaryOp::Le, false, false),
+        Cmp_gt_and | Cmp_gt_or | Cmp_gt_or_andcm => (BinaryOp::Lt, true, false),
+        Cmp_ge_and | Cmp_ge_or | Cmp_ge_or_andcm => (BinaryOp::Le, true, false),
+        Cmp_ltu | Cmp_ltu_unc => (BinaryOp::Ltu, false, false),
+        Cmp4_eq | Cmp4_eq_unc | Cmp4_eq_and | Cmp4_eq_or | Cmp4_eq_or_andcm => (BinaryOp::Eq, false, true),
+        Cmp4_ne_and | Cmp4_ne_or | Cmp4_ne_or_andcm => (BinaryOp::Ne, false, true),
+        Cmp4_lt | Cmp4_lt_unc | Cmp4_lt_and | Cmp4_lt_or | Cmp4_lt_or_andcm => (BinaryOp::Lt, false, true),
+        Cmp4_le_and | Cmp4_le_or | Cmp4_le_or_andcm => (BinaryOp::Le, false, true),
+        Cmp4_gt_and | Cmp4_gt_or | Cmp4_gt_or_andcm => (BinaryOp::Lt, true, true),
+        Cmp4_ge_and | Cmp4_ge_or | Cmp4_ge_or_andcm => (BinaryOp::Le, true, true),
+        Cmp4_ltu | Cmp4_ltu_unc => (BinaryOp::Ltu, false, true),
+        _ => return None,
+    })
+}
+
+fn gr(r: GPRegister) -> Var {
+    Var::Location(Location::GPRegister(r))
+}
+
+fn ar(r: ApplicationRegister) -> Var {
+    Var::Location(Location::ApplicationRegister(r))
+}
+
+/// `cr.iip`, where `rfi` returns to.
+const IIP: ControlRegister = ControlRegister(19);
+
+struct Lifter<'a> {
+    inst: &'a Instruction,
+    address: SlotAddress,
+    /// the qualifying predicate, unless it is `p0`.
+    guard: Option<Var>,
+    statements: Vec<Statement>,
+    temps: u16,
+}
+
+impl<'a> Lifter<'a> {
+    fn emit(&mut self, guard: Option<Var>, operation: Operation) {
+        self.statements.push(Statement { guard, operation });
+    }
+
+    fn temp(&mut self) -> Var {
+        self.temps += 1;
+        Var::Temp(self.temps - 1)
+    }
+
+    /// is writing `var` a no-op?
+    fn is_constant(var: Var) -> bool {
+        matches!(var,
+            Var::Location(Location::GPRegister(GPRegister(0))) |
+            Var::Location(Location::PredicateRegister(PredicateRegister(0))) |
+            Var::Location(Location::FloatRegister(FloatRegister(0))) |
+            Var::Location(Location::FloatRegister(FloatRegister(1))) |
+            Var::Nat(GPRegister(0)))
+    }
+
+    /// `dest = expr`, under `guard`.
+    fn assign_if(&mut self, guard: Option<Var>, dest: Var, expr: Expr) {
+        if !Self::is_constant(dest) {
+            self.emit(guard, Operation::Assign { dest, expr });
+        }
+    }
+
+    /// `dest = expr`, under the qualifying predicate.
+    fn assign(&mut self, dest: Var, expr: Expr) {
+        self.assign_if(self.guard, dest, expr);
+    }
+
+    /// a new temporary holding `expr`. temporaries have no side effects, so they are computed
+    /// unconditionally.
+    fn compute(&mut self, expr: Expr) -> Value {
+        if let Expr::Value(value) = expr {
+            return value;
+        }
+        let temp = self.temp();
+        self.emit(None, Operation::Assign { dest: temp, expr });
+        Value::Var(temp)
+    }
+
+    fn binary(&mut self, op: BinaryOp, a: Value, b: Value) -> Value {
+        self.compute(Expr::Binary(op, a, b))
+    }
+
+    /// `a | b`, for NaT bits.
+    fn or(&mut self, a: Value, b: Value) -> Value {
+        match (a, b) {
+            (Value::Const(0), other) | (other, Value::Const(0)) => other,
+            (Value::Const(_), _) | (_, Value::Const(_)) => Value::Const(1),
+            _ if a == b => a,
+            _ => self.binary(BinaryOp::Or, a, b),
+        }
+    }
+
+    /// `!a`, for predicates and NaT bits.
+    fn not(&mut self, a: Value) -> Value {
+        match a {
+            Value::Const(c) => Value::Const(c ^ 1),
+            _ => self.binary(BinaryOp::Xor, a, Value::Const(1)),
+        }
+    }
+
+    /// `a & b`, for predicates and NaT bits.
+    fn and(&mut self, a: Value, b: Value) -> Value {
+        match (a, b) {
+            (Value::Const(0), _) | (_, Value::Const(0)) => Value::Const(0),
+            (Value::Const(_), other) | (other, Value::Const(_)) => other,
+            _ => self.binary(BinaryOp::And, a, b),
+        }
+    }
+
+    /// a guard for "the qualifying predicate is true and `condition` is 1". `Err` if that can
+    /// never happen.
+    fn guard_with(&mut self, condition: Value) -> Result<Option<Var>, ()> {
+        let guard = self.guard.map(Value::Var).unwrap_or(Value::Const(1));
+        match self.and(guard, condition) {
+            Value::Const(0) => Err(()),
+            Value::Const(_) => Ok(None),
+            Value::Var(var) => Ok(Some(var)),
+        }
+    }
+
+    fn operand(&self, index: usize) -> Operand {
+        self.inst.operands().get(index).cloned().unwrap_or(Operand::None)
+    }
+
+    /// the value of an operand that is read.
+    fn value(&self, operand: Operand) -> Value {
+        match operand {
+            Operand::GPRegister(GPRegister(0)) => Value::Const(0),
+            Operand::GPRegister(r) => Value::Var(gr(r)),
+            Operand::PredicateRegister(PredicateRegister(0)) => Value::Const(1),
+            Operand::ImmI64(imm) => Value::Const(imm as u64),
+            Operand::ImmU64(imm) => Value::Const(imm),
+            Operand::IP => Value::Const(self.address.bundle()),
+            Operand::Memory(r) => self.value(Operand::GPRegister(r)),
+            other => match crate::registers::location(&other) {
+                Some(location) => Value::Var(Var::Location(location)),
+                None => Value::Const(0),
+            },
+        }
+    }
+
+    /// the NaT bit of an operand that is read: 0 for anything but a general register.
+    fn nat(&self, operand: Operand) -> Value {
+        match operand {
+            Operand::GPRegister(GPRegister(0)) => Value::Const(0),
+            Operand::GPRegister(r) | Operand::Memory(r) => Value::Var(Var::Nat(r)),
+            _ => Value::Const(0),
+        }
+    }
+
+    /// write the destination operand: `expr`, and `nat` if it is a general register.
+    fn write(&mut self, dest: Operand, expr: Expr, nat: Value) {
+        match dest {
+            Operand::GPRegister(r) => {
+                self.assign(gr(r), expr);
+                if nat != Value::Var(Var::Nat(r)) {
+                    self.assign(Var::Nat(r), Expr::Value(nat));
+                }
+            }
+            other => {
+                if let Some(location) = crate::registers::location(&other) {
+                    self.assign(Var::Location(location), expr);
+                }
+            }
+        }
+    }
+
+    /// `op` applied to operands 1 and 2, into operand 0.
+    fn alu(&mut self, op: BinaryOp) {
+        let (a, b) = (self.operand(1), self.operand(2));
+        let nat = self.or(self.nat(a), self.nat(b));
+        self.write(self.operand(0), Expr::Binary(op, self.value(a), self.value(b)), nat);
+    }
+
+    /// `addp4`: a 32-bit sum, with bits 61 and 62 taken from bits 30 and 31 of `b`.
+    fn addp4(&mut self, sum: Value, b: Value, nat: Value) {
+        let low = self.compute(Expr::Extract { value: sum, pos: 0, len: 32, signed: false });
+        let region = self.compute(Expr::Extract { value: b, pos: 30, len: 2, signed: false });
+        self.write(self.operand(0), Expr::Deposit { value: low, field: region, pos: 61, len: 2 }, nat);
+    }
+
+    /// `r3 += increment`, for the post-incrementing memory operand at `index`.
+    fn post_increment(&mut self, index: usize, increment: Operand) {
+        if let Operand::Memory(base) = self.operand(index) {
+            if let Operand::None = increment {
+                return;
+            }
+            let nat = self.or(self.nat(Operand::GPRegister(base)), self.nat(increment));
+            let sum = Expr::Binary(BinaryOp::Add, self.value(Operand::GPRegister(base)), self.value(increment));
+            self.write(Operand::GPRegister(base), sum, nat);
+        }
+    }
+
+    /// the bit of `ar.unat` that `ld8.fill` and `st8.spill` use for `address`.
+    fn unat_bit(&mut self, address: Value) -> Value {
+        self.compute(Expr::Extract { value: address, pos: 3, len: 6, signed: false })
+    }
+
+    /// the low 32 bits of `value`, extended, for `cmp4`.
+    fn low32(&mut self, value: Value, signed: bool) -> Value {
+        match value {
+            Value::Const(c) if signed => Value::Const(c as u32 as i32 as i64 as u64),
+            Value::Const(c) => Value::Const(c as u32 as u64),
+            _ => self.compute(Expr::Extract { value, pos: 0, len: 32, signed }),
+        }
+    }
+
+    fn branch(&mut self, guard: Option<Var>, kind: BranchKind, target: Value) {
+        self.emit(guard, Operation::Branch { kind, target });
+    }
+
+    /// the target of a branch operand: ip-relative or through a branch register.
+    fn target(&self, operand: Operand) -> Value {
+        match operand {
+            Operand::ImmI64(disp) => Value::Const(self.address.bundle().wrapping_add(disp as u64)),
+            other => self.value(other),
+        }
+    }
+
+    /// an intrinsic for the whole instruction, reading and writing what `registers::accesses`
+    /// reports.
+    fn intrinsic(&mut self) {
+        let accesses = accesses(self.inst);
+        let qp = Location::PredicateRegister(PredicateRegister(self.inst.predicate()));
+        let mut reads = Vec::new();
+        for location in accesses.reads.into_iter().filter(|location| *location != qp) {
+            reads.push(Var::Location(location));
+            if let Location::GPRegister(r) = location {
+                reads.push(Var::Nat(r));
+            }
+        }
+        let mut writes = Vec::new();
+        for location in accesses.writes.into_iter() {
+            writes.push(Var::Location(location));
+            if let Location::GPRegister(r) = location {
+                writes.push(Var::Nat(r));
+            }
+        }
+        self.emit(self.guard, Operation::Intrinsic { opcode: self.inst.opcode(), reads, writes });
+    }
+
+    fn lift(&mut self) {
+        use Opcode::*;
+        let opcode = self.inst.opcode();
+
+        if predicate_write(self.inst).is_some() && self.compare() {
+            return;
+        }
+        if let Some((size, kind)) = load(opcode) {
+            self.load(size, kind);
+            return;
+        }
+        if let Some(size) = store_size(opcode) {
+            self.store(size);
+            return;
+        }
+        if let Some(size) = semaphore_size(opcode) {
+            self.semaphore(size);
+            return;
+        }
+
+        match opcode {
+            Nop_m | Nop_i | Nop_b | Nop_f | Nop_x | Hint_m | Hint_i | Hint_b | Hint_f | Hint_x |
+            Brp | Brp_ret | Brp_ipwh_ih | White => {}
+
+            Add | Adds | Addl => self.alu(BinaryOp::Add),
+            Sub => self.alu(BinaryOp::Sub),
+            And => self.alu(BinaryOp::And),
+            Or => self.alu(BinaryOp::Or),
+            Xor => self.alu(BinaryOp::Xor),
+            Andcm => {
+                let (a, b) = (self.operand(1), self.operand(2));
+                let nat = self.or(self.nat(a), self.nat(b));
+                let complement = self.compute(Expr::Unary(UnaryOp::Not, self.value(b)));
+                self.write(self.operand(0), Expr::Binary(BinaryOp::And, self.value(a), complement), nat);
+            }
+            AddPlusOne | SubMinusOne => {
+                let (a, b) = (self.operand(1), self.operand(2));
+                let nat = self.or(self.nat(a), self.nat(b));
+                let op = if opcode == AddPlusOne { BinaryOp::Add } else { BinaryOp::Sub };
+                let partial = self.binary(op, self.value(a), self.value(b));
+                self.write(self.operand(0), Expr::Binary(op, partial, Value::Const(1)), nat);
+            }
+            Addp4 => {
+                let (a, b) = (self.operand(1), self.operand(2));
+                let nat = self.or(self.nat(a), self.nat(b));
+                let sum = self.binary(BinaryOp::Add, self.value(a), self.value(b));
+                self.addp4(sum, self.value(b), nat);
+            }
+            Shladd | Shladdp4 => {
+                // `r1 = r2, count, r3`
+                let (a, count, b) = (self.operand(1), self.operand(2), self.operand(3));
+                let nat = self.or(self.nat(a), self.nat(b));
+                let shifted = self.binary(BinaryOp::Shl, self.value(a), self.value(count));
+                if opcode == Shladd {
+                    self.write(self.operand(0), Expr::Binary(BinaryOp::Add, shifted, self.value(b)), nat);
+                } else {
+                    let sum = self.binary(BinaryOp::Add, shifted, self.value(b));
+                    self.addp4(sum, self.value(b), nat);
+                }
+            }
+            Shl => self.alu(BinaryOp::Shl),
+            Shr => self.alu(BinaryOp::Sar),
+            Shr_u => self.alu(BinaryOp::Shr),
+            Shrp => {
+                // `r1 = r2, r3, count`: the low 64 bits of `r2:r3 >> count`.
+                let (a, b) = (self.operand(1), self.operand(2));
+                let nat = self.or(self.nat(a), self.nat(b));
+                let count = if let Operand::ImmU64(count) = self.operand(3) { count } else { 0 };
+                if count == 0 {
+                    self.write(self.operand(0), Expr::Value(self.value(b)), nat);
+                } else {
+                    let low = self.binary(BinaryOp::Shr, self.value(b), Value::Const(count));
+                    let high = self.binary(BinaryOp::Shl, self.value(a), Value::Const(64 - count));
+                    self.write(self.operand(0), Expr::Binary(BinaryOp::Or, low, high), nat);
+                }
+            }
+            Extr | Extr_u => {
+                // `r1 = r3, pos, len`
+                let source = self.operand(1);
+                if let (Operand::ImmU64(pos), Operand::ImmU64(len)) = (self.operand(2), self.operand(3)) {
+                    let len = len.min(64 - pos) as u8;
+                    let expr = Expr::Extract { value: self.value(source), pos: pos as u8, len, signed: opcode == Extr };
+                    self.write(self.operand(0), expr, self.nat(source));
+                }
+            }
+            Dep | Dep_z => self.deposit(),
+            Zxt1 | Zxt2 | Zxt4 | Sxt1 | Sxt2 | Sxt4 => {
+                let len = match opcode {
+                    Zxt1 | Sxt1 => 8,
+                    Zxt2 | Sxt2 => 16,
+                    _ => 32,
+                };
+                let signed = matches!(opcode, Sxt1 | Sxt2 | Sxt4);
+                let source = self.operand(1);
+                let expr = Expr::Extract { value: self.value(source), pos: 0, len, signed };
+                self.write(self.operand(0), expr, self.nat(source));
+            }
+            Popcnt | Clz => {
+                let op = if opcode == Popcnt { UnaryOp::Popcount } else { UnaryOp::LeadingZeros };
+                let source = self.operand(1);
+                self.write(self.operand(0), Expr::Unary(op, self.value(source)), self.nat(source));
+            }
+            Movl => {
+                self.write(self.operand(0), Expr::Value(self.value(self.operand(1))), Value::Const(0));
+            }
+            Mov_i | Mov_m | Mov_mwh_ih | Mov_ret_mwh_ih => {
+                // to or from an application register, or to a branch register. values moved
+                // out of general registers drop their NaT bit.
+                let source = self.operand(1);
+                self.write(self.operand(0), Expr::Value(self.value(source)), Value::Const(0));
+            }
+            Mov => self.mov(),
+
+            Alloc => {
+                // `r1 = ar.pfs, i, l, o, r`. the new frame is an intrinsic.
+                self.write(self.operand(0), Expr::Value(Value::Var(ar(ApplicationRegister::PFS))), Value::Const(0));
+                self.emit(self.guard, Operation::Intrinsic { opcode, reads: Vec::new(), writes: Vec::new() });
+            }
+            Lfetch | Lfetch_excl | Lfetch_fault | Lfetch_fault_excl => {
+                // a prefetch has no architectural effect but the increment.
+                self.post_increment(0, self.operand(1));
+            }
+            Ld16 | Ld16_acq => {
+                let address = self.value(self.operand(1));
+                let high = self.binary(BinaryOp::Add, address, Value::Const(8));
+                if let Operand::GPRegister(r) = self.operand(0) {
+                    let low = self.temp();
+                    self.emit(self.guard, Operation::Load { dest: low, address, size: 8 });
+                    self.emit(self.guard, Operation::Load { dest: ar(ApplicationRegister::CSD), address: high, size: 8 });
+                    self.write(Operand::GPRegister(r), Expr::Value(Value::Var(low)), Value::Const(0));
+                }
+            }
+            St16 | St16_rel => {
+                let address = self.value(self.operand(0));
+                let high = self.binary(BinaryOp::Add, address, Value::Const(8));
+                let value = self.value(self.operand(1));
+                self.emit(self.guard, Operation::Store { address, value, size: 8 });
+                self.emit(self.guard, Operation::Store { address: high, value: Value::Var(ar(ApplicationRegister::CSD)), size: 8 });
+            }
+
+            Chk_s_m_int | Chk_s_i_int => {
+                // `chk.s r2, target`
+                let nat = self.nat(self.operand(0));
+                if let Ok(guard) = self.guard_with(nat) {
+                    let target = self.target(self.operand(1));
+                    self.branch(guard, BranchKind::Recovery, target);
+                }
+            }
+            Chk_s_fp | Chk_a_nc_int | Chk_a_clr_int | Chk_a_nc_fp | Chk_a_clr_fp => {
+                // whether these branch depends on a NaTVal or the ALAT.
+                let failed = self.temp();
+                self.emit(None, Operation::Undefined { dest: failed });
+                if let Ok(guard) = self.guard_with(Value::Var(failed)) {
+                    let target = self.target(self.operand(1));
+                    self.branch(guard, BranchKind::Recovery, target);
+                }
+            }
+
+            Br_cond | Brl_cond_bwh_ph_dh => {
+                let target = self.target(self.operand(0));
+                self.branch(self.guard, BranchKind::Jump, target);
+            }
+            Br_ia => {
+                let target = self.target(self.operand(0));
+                self.branch(self.guard, BranchKind::Ia32, target);
+            }
+            Br_call | Brl_call_bwh_ph_dh => {
+                // `br.call b1=target`. read the target first if it is `b1`.
+                let target = match self.target(self.operand(1)) {
+                    Value::Var(var) if self.operand(1) == self.operand(0) => {
+                        let temp = self.temp();
+                        self.emit(None, Operation::Assign { dest: temp, expr: Expr::Value(Value::Var(var)) });
+                        Value::Var(temp)
+                    }
+                    other => other,
+                };
+                let next = Value::Const(self.address.bundle().wrapping_add(16));
+                self.write(self.operand(0), Expr::Value(next), Value::Const(0));
+                self.emit(self.guard, Operation::Undefined { dest: ar(ApplicationRegister::PFS) });
+                self.branch(self.guard, BranchKind::Call, target);
+            }
+            Br_ret => {
+                let target = self.target(self.operand(0));
+                self.branch(self.guard, BranchKind::Return, target);
+            }
+            Br_cloop => {
+                let lc = Value::Var(ar(ApplicationRegister::LC));
+                let more = self.binary(BinaryOp::Ne, lc, Value::Const(0));
+                if let Ok(guard) = self.guard_with(more) {
+                    self.assign_if(guard, ar(ApplicationRegister::LC), Expr::Binary(BinaryOp::Sub, lc, Value::Const(1)));
+                    let target = self.target(self.operand(0));
+                    self.branch(guard, BranchKind::Jump, target);
+                }
+            }
+            Br_ctop | Br_cexit | Br_wtop | Br_wexit => self.loop_branch(),
+            Rfi => {
+                self.branch(self.guard, BranchKind::InterruptionReturn, Value::Var(Var::Location(Location::ControlRegister(IIP))));
+            }
+
+            _ => self.intrinsic(),
+        }
+    }
+
+    fn load(&mut self, size: u8, kind: LoadKind) {
+        // `r1 = [r3]`, `r1 = [r3], r2`, or `r1 = [r3], imm`
+        let dest = self.operand(0);
+        let address = self.value(self.operand(1));
+        let bit = if kind == LoadKind::Fill { Some(self.unat_bit(address)) } else { None };
+        if let Operand::GPRegister(r) = dest {
+            if r.0 != 0 {
+                self.emit(self.guard, Operation::Load { dest: gr(r), address, size });
+            }
+            match kind {
+                LoadKind::Plain => self.assign(Var::Nat(r), Expr::Value(Value::Const(0))),
+                LoadKind::Speculative => {
+                    if r.0 != 0 {
+                        self.emit(self.guard, Operation::Undefined { dest: Var::Nat(r) });
+                    }
+                }
+                LoadKind::Fill => {
+                    let unat = Value::Var(ar(ApplicationRegister::UNAT));
+                    let shifted = self.binary(BinaryOp::Shr, unat, bit.expect("fill computes its bit"));
+                    self.assign(Var::Nat(r), Expr::Binary(BinaryOp::And, shifted, Value::Const(1)));
+                }
+            }
+        }
+        self.post_increment(1, self.operand(2));
+    }
+
+    fn store(&mut self, size: u8) {
+        // `[r3] = r2` or `[r3] = r2, imm`
+        let address = self.value(self.operand(0));
+        let source = self.operand(1);
+        self.emit(self.guard, Operation::Store { address, value: self.value(source), size });
+        if self.inst.opcode() == Opcode::St8_spill {
+            // the NaT bit goes to `ar.unat`.
+            let bit = self.unat_bit(address);
+            let unat = Value::Var(ar(ApplicationRegister::UNAT));
+            let mask = self.binary(BinaryOp::Shl, Value::Const(1), bit);
+            let keep = self.compute(Expr::Unary(UnaryOp::Not, mask));
+            let cleared = self.binary(BinaryOp::And, unat, keep);
+            let nat = self.nat(source);
+            let set = self.binary(BinaryOp::Shl, nat, bit);
+            self.assign(ar(ApplicationRegister::UNAT), Expr::Binary(BinaryOp::Or, cleared, set));
+        }
+        self.post_increment(0, self.operand(2));
+    }
+
+    fn semaphore(&mut self, size: u8) {
+        use Opcode::*;
+        // `r1 = [r3], r2` or, for `fetchadd`, `r1 = [r3], inc`
+        let address = self.value(self.operand(1));
+        let operand = self.value(self.operand(2));
+        let old = self.temp();
+        self.emit(self.guard, Operation::Load { dest: old, address, size });
+        match self.inst.opcode() {
+            Xchg1 | Xchg2 | Xchg4 | Xchg8 => {
+                self.emit(self.guard, Operation::Store { address, value: operand, size });
+            }
+            Fetchadd4_acq | Fetchadd4_rel | Fetchadd8_acq | Fetchadd8_rel => {
+                let new = self.binary(BinaryOp::Add, Value::Var(old), operand);
+                self.emit(self.guard, Operation::Store { address, value: new, size });
+            }
+            _ => {
+                let ccv = Value::Var(ar(ApplicationRegister::CCV));
+                let expected = if size == 8 {
+                    ccv
+                } else {
+                    self.compute(Expr::Extract { value: ccv, pos: 0, len: size * 8, signed: false })
+                };
+                let equal = self.binary(BinaryOp::Eq, Value::Var(old), expected);
+                if let Ok(guard) = self.guard_with(equal) {
+                    self.emit(guard, Operation::Store { address, value: operand, size });
+                }
+            }
+        }
+        self.write(self.operand(0), Expr::Value(Value::Var(old)), Value::Const(0));
+    }
+
+    fn deposit(&mut self) {
+        // `dep r1 = r2, r3, pos, len`, `dep r1 = imm1, r3, pos, len`, `dep.z r1 = r2, pos, len`,
+        // or `dep.z r1 = imm8, pos, len`
+        let ops = self.inst.operands();
+        let (field, target, pos, len) = if self.inst.opcode() == Opcode::Dep {
+            let field = match ops[1] {
+                // the one-bit immediate is sign-extended to fill the field.
+                Operand::ImmU64(imm) => Operand::ImmU64(if imm != 0 { u64::MAX } else { 0 }),
+                other => other,
+            };
+            (field, ops[2], ops[3], ops[4])
+        } else {
+            (ops[1], Operand::ImmU64(0), ops[2], ops[3])
+        };
+        if let (Operand::ImmU64(pos), Operand::ImmU64(len)) = (pos, len) {
+            let nat = self.or(self.nat(field), self.nat(target));
+            let len = len.min(64 - pos) as u8;
+            let expr = Expr::Deposit { value: self.value(target), field: self.value(field), pos: pos as u8, len };
+            self.write(ops[0], expr, nat);
+        }
+    }
+
+    fn mov(&mut self) {
+        let (dest, source) = (self.operand(0), self.operand(1));
+        let psr = Value::Var(Var::Location(Location::PSR));
+        match (dest, source) {
+            (Operand::PR, Operand::GPRegister(_)) => {
+                // `mov pr = r2, mask`. `p0` stays set.
+                let mask = if let Operand::ImmU64(mask) = self.operand(2) { mask & !1 } else { 0 };
+                let pr = Value::Var(Var::Location(Location::PR));
+                let kept = self.binary(BinaryOp::And, pr, Value::Const(!mask));
+                let new = self.binary(BinaryOp::And, self.value(source), Value::Const(mask));
+                self.assign(Var::Location(Location::PR), Expr::Binary(BinaryOp::Or, kept, new));
+            }
+            (Operand::PR, Operand::ImmU64(_)) => {
+                // `mov pr.rot = imm`
+                let pr = Value::Var(Var::Location(Location::PR));
+                self.assign(Var::Location(Location::PR), Expr::Deposit { value: pr, field: self.value(source), pos: 16, len: 48 });
+            }
+            (Operand::PSR_l, _) | (Operand::PSR_um, _) => {
+                let len = if dest == Operand::PSR_l { 32 } else { 6 };
+                self.assign(Var::Location(Location::PSR), Expr::Deposit { value: psr, field: self.value(source), pos: 0, len });
+            }
+            (Operand::GPRegister(_), Operand::PSR) => {
+                // only `psr{36:35}` and `psr{31:0}` are read.
+                let low = self.compute(Expr::Extract { value: psr, pos: 0, len: 32, signed: false });
+                let high = self.compute(Expr::Extract { value: psr, pos: 35, len: 2, signed: false });
+                self.write(dest, Expr::Deposit { value: low, field: high, pos: 35, len: 2 }, Value::Const(0));
+            }
+            (Operand::GPRegister(_), Operand::PSR_um) => {
+                self.write(dest, Expr::Extract { value: psr, pos: 0, len: 6, signed: false }, Value::Const(0));
+            }
+            (Operand::Indirection(_, _), _) | (_, Operand::Indirection(_, _)) => self.intrinsic(),
+            _ => {
+                // between general, branch, control, and application registers, and from `ip`
+                // and `pr`. only a general register source has a NaT bit to copy.
+                self.write(dest, Expr::Value(self.value(source)), self.nat(source));
+            }
+        }
+    }
+
+    /// the modulo-scheduled loop branches. the decision to branch, the updates of `ar.lc`,
+    /// `ar.ec`, and `p63`, and register rotation are one intrinsic, which also writes a
+    /// temporary that is 1 if the branch is taken.
+    fn loop_branch(&mut self) {
+        let opcode = self.inst.opcode();
+        let taken = self.temp();
+        let lc = ar(ApplicationRegister::LC);
+        let ec = ar(ApplicationRegister::EC);
+        let counted = matches!(opcode, Opcode::Br_ctop | Opcode::Br_cexit);
+        let (reads, guard) = if counted {
+            (vec![lc, ec], self.guard)
+        } else {
+            // `br.wtop` and `br.wexit` test their qualifying predicate rather than being
+            // predicated by it.
+            (vec![ec, Var::Location(Location::PredicateRegister(PredicateRegister(self.inst.predicate())))], None)
+        };
+        let mut writes = vec![ec, Var::Location(Location::PR), taken];
+        if counted {
+            writes.insert(0, lc);
+        }
+        self.emit(guard, Operation::Intrinsic { opcode, reads, writes });
+        let target = self.target(self.operand(0));
+        let guard = if counted {
+            match self.guard_with(Value::Var(taken)) {
+                Ok(guard) => guard,
+                Err(()) => return,
+            }
+        } else {
+            Some(taken)
+        };
+        self.branch(guard, BranchKind::Jump, target);
+    }
+
+    /// integer compares, `tbit`, `tnat`, and `tf`. `false` if this is a floating-point compare,
+    /// to be lifted as an intrinsic.
+    fn compare(&mut self) -> bool {
+        use Opcode::*;
+        let write = predicate_write(self.inst).expect("caller checked predicate_write");
+        let opcode = self.inst.opcode();
+        // the result of the relation, and whether a source is NaT.
+        let (result, nat) = if let Some((op, swap, four)) = relation(opcode) {
+            let (a, b) = (self.operand(2), self.operand(3));
+            let nat = self.or(self.nat(a), self.nat(b));
+            let (mut a, mut b) = (self.value(a), self.value(b));
+            if four {
+                let signed = op != BinaryOp::Ltu;
+                a = self.low32(a, signed);
+                b = self.low32(b, signed);
+            }
+            if swap {
+                core::mem::swap(&mut a, &mut b);
+            }
+            (self.binary(op, a, b), nat)
+        } else {
+            match opcode {
+                Tbit_z | Tbit_z_unc | Tbit_z_and | Tbit_nz_and | Tbit_z_or | Tbit_nz_or |
+                Tbit_z_or_andcm | Tbit_nz_or_andcm => {
+                    // `p1, p2 = r3, pos`
+                    let source = self.operand(2);
+                    let bit = match self.operand(3) {
+                        Operand::ImmU64(pos) => self.compute(Expr::Extract { value: self.value(source), pos: pos as u8, len: 1, signed: false }),
+                        _ => Value::Const(0),
+                    };
+                    let op = if matches!(opcode, Tbit_nz_and | Tbit_nz_or | Tbit_nz_or_andcm) { BinaryOp::Ne } else { BinaryOp::Eq };
+                    (self.binary(op, bit, Value::Const(0)), self.nat(source))
+                }
+                Tnat_z | Tnat_z_unc | Tnat_z_and | Tnat_nz_and | Tnat_z_or | Tnat_nz_or |
+                Tnat_z_or_andcm | Tnat_nz_or_andcm => {
+                    // `p1, p2 = r3`
+                    let nat = self.nat(self.operand(2));
+                    let op = if matches!(opcode, Tnat_nz_and | Tnat_nz_or | Tnat_nz_or_andcm) { BinaryOp::Ne } else { BinaryOp::Eq };
+                    (self.binary(op, nat, Value::Const(0)), Value::Const(0))
+                }
+                Tf_z | Tf_z_nc | Tf_z_and | Tf_nz_and | Tf_z_or | Tf_nz_or | Tf_z_or_andcm | Tf_nz_or_andcm => {
+                    // processor features are not known here.
+                    let feature = self.temp();
+                    self.emit(None, Operation::Undefined { dest: feature });
+                    (Value::Var(feature), Value::Const(0))
+                }
+                _ => return false,
+            }
+        };
+        let nat = if write.semantics.when_nat.is_some() { nat } else { Value::Const(0) };
+
+        let p1 = Var::Location(Location::PredicateRegister(write.p1));
+        let p2 = Var::Location(Location::PredicateRegister(write.p2));
+        match write.semantics.compare_type {
+            CompareType::Normal | CompareType::Unc => {
+                // both targets are cleared if a source is NaT.
+                let valid = self.not(nat);
+                let mut first = self.and(result, valid);
+                let complement = self.not(result);
+                let mut second = self.and(complement, valid);
+                if write.semantics.compare_type == CompareType::Normal {
+                    // the qualifying predicate may itself be a target, so both writes are
+                    // guarded by its value before either.
+                    let guard = self.guard.map(|qp| {
+                        let copy = self.temp();
+                        self.emit(None, Operation::Assign { dest: copy, expr: Expr::Value(Value::Var(qp)) });
+                        copy
+                    });
+                    self.assign_if(guard, p1, Expr::Value(first));
+                    self.assign_if(guard, p2, Expr::Value(second));
+                } else {
+                    // `.unc` writes both targets whatever the qualifying predicate, which may
+                    // itself be one of them.
+                    if let Some(qp) = self.guard {
+                        first = self.and(first, Value::Var(qp));
+                        second = self.and(second, Value::Var(qp));
+                    }
+                    self.assign_if(None, p1, Expr::Value(first));
+                    self.assign_if(None, p2, Expr::Value(second));
+                }
+            }
+            CompareType::And => {
+                let fails = self.not(result);
+                let clear = self.or(fails, nat);
+                if let Ok(guard) = self.guard_with(clear) {
+                    self.assign_if(guard, p1, Expr::Value(Value::Const(0)));
+                    self.assign_if(guard, p2, Expr::Value(Value::Const(0)));
+                }
+            }
+            CompareType::Or | CompareType::OrAndcm => {
+                let valid = self.not(nat);
+                let set = self.and(result, valid);
+                if let Ok(guard) = self.guard_with(set) {
+                    let second = if write.semantics.compare_type == CompareType::Or { 1 } else { 0 };
+                    self.assign_if(guard, p1, Expr::Value(Value::Const(1)));
+                    self.assign_if(guard, p2, Expr::Value(Value::Const(second)));
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,364 @@
+//! which general, floating-point, predicate, and branch registers are live at each point of a
+//! `Cfg`.
+//!
+//! a register is live if some path from the point reads it before writing it. a predicated write
+//! might not happen, so it doesn't end a register's liveness, except for the `.unc` compares,
+//! which always write their targets. the parallel compares (`.and`, `.or`, `.or.andcm`) read
+//! their targets as well as writing them.
+//!
+//! the register stack frame is taken from the function's `alloc`. if the graph has more than one
+//! `alloc` and they disagree, or has none, the frame is unknown. calls and returns follow the
+//! software conventions:
+//!
+//! * `br.call` reads `gp`, `sp`, `tp`, the floating-point argument registers `f8`-`f15`, and
+//!   the frame's output registers, which become the callee's inputs. it writes the scratch
+//!   registers, `gp`, and the output registers. with the frame unknown, every stacked register
+//!   is read and none is written.
+//! * `br.ret` reads the result registers `r8`-`r11` and `f8`-`f15`, unless told which the
+//!   function returns, and every preserved register. no stacked register is live after a
+//!   return: the callee's frame is gone.
+//! * `alloc` writes the stacked registers past its locals, which hold nothing until written.
+//!
+//! register rotation is handled conservatively: the loop branches that rotate (`br.ctop`,
+//! `br.cexit`, `br.wtop`, `br.wexit`) and `clrrrb` read every rotating register, so nothing
+//! written in a software-pipelined loop is ever dead. an edge that leaves the graph, and an
+//! indirect branch or `rfi` without edges, is assumed to make every register live.
+
+use core::ops::Range;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{BranchRegister, Bundles, FloatRegister, GPRegister, Instruction, Opcode, PredicateRegister, SlotAddress};
+use crate::cfg::{Block, Cfg};
+use crate::predicate::CompareType;
+use crate::registers::{self, Location};
+
+/// a set of general, floating-point, predicate, and branch registers. registers with fixed
+/// values, `r0`, `f0`, `f1`, and `p0`, are never members.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RegisterSet {
+    gr: u128,
+    fr: u128,
+    pr: u64,
+    br: u8,
+}
+
+fn bits128(range: Range<u8>) -> u128 {
+    range.fold(0, |bits, i| bits | 1 << i)
+}
+
+fn bits64(range: Range<u8>) -> u64 {
+    range.fold(0, |bits, i| bits | 1 << i)
+}
+
+impl RegisterSet {
+    /// the empty set.
+    pub fn new() -> RegisterSet {
+        RegisterSet::default()
+    }
+
+    /// every register.
+    pub fn all() -> RegisterSet {
+        RegisterSet { gr: !1, fr: !3, pr: !1, br: !0 }
+    }
+
+    fn grs(range: Range<u8>) -> RegisterSet {
+        RegisterSet { gr: bits128(range) & !1, ..RegisterSet::default() }
+    }
+
+    fn frs(range: Range<u8>) -> RegisterSet {
+        RegisterSet { fr: bits128(range) & !3, ..RegisterSet::default() }
+    }
+
+    fn prs(range: Range<u8>) -> RegisterSet {
+        RegisterSet { pr: bits64(range) & !1, ..RegisterSet::default() }
+    }
+
+    fn brs(range: Range<u8>) -> RegisterSet {
+        RegisterSet { br: bits64(range) as u8, ..RegisterSet::default() }
+    }
+
+    /// add `location`. `PR` adds every predicate register; locations other than registers of
+    /// the four kinds are ignored.
+    pub fn insert(&mut self, location: Location) {
+        *self = self.union(&RegisterSet::from(location));
+    }
+
+    /// remove `location`, as for `insert`.
+    pub fn remove(&mut self, location: Location) {
+        *self = self.difference(&RegisterSet::from(location));
+    }
+
+    /// is `location` a member? for `PR`, is any predicate register?
+    pub fn contains(&self, location: Location) -> bool {
+        !self.intersection(&RegisterSet::from(location)).is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == RegisterSet::default()
+    }
+
+    pub fn union(&self, other: &RegisterSet) -> RegisterSet {
+        RegisterSet { gr: self.gr | other.gr, fr: self.fr | other.fr, pr: self.pr | other.pr, br: self.br | other.br }
+    }
+
+    pub fn intersection(&self, other: &RegisterSet) -> RegisterSet {
+        RegisterSet { gr: self.gr & other.gr, fr: self.fr & other.fr, pr: self.pr & other.pr, br: self.br & other.br }
+    }
+
+    pub fn difference(&self, other: &RegisterSet) -> RegisterSet {
+        RegisterSet { gr: self.gr & !other.gr, fr: self.fr & !other.fr, pr: self.pr & !other.pr, br: self.br & !other.br }
+    }
+
+    /// every member, general registers first, then floating-point, predicate, and branch
+    /// registers, each in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item=Location> + '_ {
+        let grs = (0..128u8).filter(move |i| self.gr >> i & 1 != 0).map(|i| Location::GPRegister(GPRegister(i)));
+        let frs = (0..128u8).filter(move |i| self.fr >> i & 1 != 0).map(|i| Location::FloatRegister(FloatRegister(i)));
+        let prs = (0..64u8).filter(move |i| self.pr >> i & 1 != 0).map(|i| Location::PredicateRegister(PredicateRegister(i)));
+        let brs = (0..8u8).filter(move |i| self.br >> i & 1 != 0).map(|i| Location::BranchRegister(BranchRegister(i)));
+        grs.chain(frs).chain(prs).chain(brs)
+    }
+}
+
+impl From<Location> for RegisterSet {
+    fn from(location: Location) -> RegisterSet {
+        match location {
+            Location::GPRegister(GPRegister(r)) => RegisterSet::grs(r..r + 1),
+            Location::FloatRegister(FloatRegister(f)) => RegisterSet::frs(f..f + 1),
+            Location::PredicateRegister(PredicateRegister(p)) => RegisterSet::prs(p..p + 1),
+            Location::BranchRegister(BranchRegister(b)) => RegisterSet::brs(b..b + 1),
+            Location::PR => RegisterSet::prs(0..64),
+            _ => RegisterSet::new(),
+        }
+    }
+}
+
+/// a register stack frame, as allocated by `alloc`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Frame {
+    /// the number of stacked registers, `sof`.
+    pub size: u8,
+    /// the number of input and local registers, `sol`.
+    pub locals: u8,
+    /// the number of rotating registers, a multiple of 8.
+    pub rotating: u8,
+}
+
+impl Frame {
+    /// the frame `inst` allocates, if it is an `alloc`.
+    pub fn of(inst: &Instruction) -> Option<Frame> {
+        if inst.opcode() != Opcode::Alloc {
+            return None;
+        }
+        let operands = inst.operands();
+        Some(Frame {
+            size: operands[2].as_unsigned_imm() as u8,
+            locals: operands[3].as_unsigned_imm() as u8,
+            rotating: (operands[4].as_unsigned_imm() as u8) << 3,
+        })
+    }
+
+    /// the output registers.
+    fn outputs(&self) -> RegisterSet {
+        RegisterSet::grs(32 + self.locals.min(96)..32 + self.size.min(96))
+    }
+}
+
+fn stacked() -> RegisterSet {
+    RegisterSet::grs(32..128)
+}
+
+/// registers a call reads, other than its operands.
+fn call_reads(frame: Option<Frame>) -> RegisterSet {
+    let args = RegisterSet::grs(1..2).union(&RegisterSet::grs(12..14)).union(&RegisterSet::frs(8..16));
+    args.union(&frame.map(|frame| frame.outputs()).unwrap_or_else(stacked))
+}
+
+/// registers a call writes, other than its operands.
+fn call_writes(frame: Option<Frame>) -> RegisterSet {
+    let scratch = RegisterSet::grs(1..4).union(&RegisterSet::grs(8..12)).union(&RegisterSet::grs(14..32))
+        .union(&RegisterSet::frs(6..16)).union(&RegisterSet::frs(32..128))
+        .union(&RegisterSet::prs(6..16))
+        .union(&RegisterSet::brs(6..8));
+    scratch.union(&frame.map(|frame| frame.outputs()).unwrap_or_default())
+}
+
+/// the result registers, `r8`-`r11` and `f8`-`f15`.
+pub fn results() -> RegisterSet {
+    RegisterSet::grs(8..12).union(&RegisterSet::frs(8..16))
+}
+
+/// registers a return reads, other than its operands and results: the preserved registers.
+fn preserved() -> RegisterSet {
+    RegisterSet::grs(4..8).union(&RegisterSet::grs(12..14))
+        .union(&RegisterSet::frs(2..6)).union(&RegisterSet::frs(16..32))
+        .union(&RegisterSet::prs(1..6)).union(&RegisterSet::prs(16..64))
+        .union(&RegisterSet::brs(1..6))
+}
+
+fn rotating(frame: Option<Frame>) -> RegisterSet {
+    let grs = match frame {
+        Some(frame) => RegisterSet::grs(32..32 + frame.rotating.min(96)),
+        None => stacked(),
+    };
+    grs.union(&RegisterSet::frs(32..128)).union(&RegisterSet::prs(16..64))
+}
+
+/// the registers live before `inst`, given those live after it.
+fn step_back(inst: &Instruction, live: RegisterSet, frame: Option<Frame>, results: &RegisterSet) -> RegisterSet {
+    let accesses = registers::accesses(inst);
+    let mut reads = RegisterSet::new();
+    for location in accesses.reads {
+        reads.insert(location);
+    }
+    let mut writes = RegisterSet::new();
+    for location in accesses.writes {
+        writes.insert(location);
+    }
+    let compare = inst.opcode().compare_type();
+    let parallel = matches!(compare, Some(CompareType::And) | Some(CompareType::Or) | Some(CompareType::OrAndcm));
+    if parallel {
+        reads = reads.union(&writes);
+    }
+    let mut live = live;
+    match inst.opcode() {
+        Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+            reads = reads.union(&call_reads(frame));
+            writes = writes.union(&call_writes(frame));
+        }
+        Opcode::Br_ret => {
+            if inst.predicate() == 0 {
+                live = RegisterSet::new();
+            }
+            reads = reads.union(&preserved()).union(results);
+        }
+        Opcode::Alloc => {
+            if let Some(allocated) = Frame::of(inst) {
+                writes = writes.union(&RegisterSet::grs(32 + allocated.locals.min(96)..128));
+            }
+        }
+        Opcode::Br_ctop | Opcode::Br_cexit | Opcode::Br_wtop | Opcode::Br_wexit |
+        Opcode::Clrrb | Opcode::Clrrb_pr => {
+            reads = reads.union(&rotating(frame));
+        }
+        _ => {}
+    }
+    let kills = (inst.predicate() == 0 && !parallel) || compare == Some(CompareType::Unc);
+    if kills {
+        live = live.difference(&writes);
+    }
+    live.union(&reads)
+}
+
+/// live registers throughout a graph, from `Liveness::analyze`.
+pub struct Liveness<'a> {
+    bundles: &'a Bundles,
+    cfg: &'a Cfg,
+    frame: Option<Frame>,
+    /// the result registers a return reads.
+    results: RegisterSet,
+    /// the registers live after the last instruction of each block.
+    live_out: BTreeMap<SlotAddress, RegisterSet>,
+}
+
+impl<'a> Liveness<'a> {
+    /// find the live registers at every point in `cfg`, a graph of `bundles` that is one
+    /// function.
+    pub fn analyze(bundles: &'a Bundles, cfg: &'a Cfg) -> Liveness<'a> {
+        Liveness::with_results(bundles, cfg, results())
+    }
+
+    /// as `analyze`, for a function known to return only the result registers in `results`.
+    pub fn with_results(bundles: &'a Bundles, cfg: &'a Cfg, results: RegisterSet) -> Liveness<'a> {
+        let mut frames = cfg.blocks()
+            .flat_map(|block| block.instructions(bundles))
+            .filter_map(|(_, inst)| Frame::of(inst));
+        let frame = frames.next().filter(|first| frames.all(|frame| frame == *first));
+
+        let mut liveness = Liveness { bundles, cfg, frame, results, live_out: BTreeMap::new() };
+        let mut predecessors: BTreeMap<SlotAddress, Vec<SlotAddress>> = BTreeMap::new();
+        for block in cfg.blocks() {
+            for edge in block.edges.iter() {
+                predecessors.entry(edge.target).or_default().push(block.start);
+            }
+        }
+        let mut live_in: BTreeMap<SlotAddress, RegisterSet> = BTreeMap::new();
+        let mut worklist: BTreeSet<SlotAddress> = cfg.blocks().map(|block| block.start).collect();
+        while let Some(start) = worklist.iter().next_back().cloned() {
+            worklist.remove(&start);
+            let block = cfg.block(start).expect("worklist holds blocks");
+            let last = bundles.instruction_at(block.last).expect("block is in range");
+            let mut out = if block.edges.is_empty() && !(last.opcode() == Opcode::Br_ret && last.predicate() == 0) {
+                RegisterSet::all()
+            } else {
+                RegisterSet::new()
+            };
+            for edge in block.edges.iter() {
+                out = out.union(&match cfg.block(edge.target) {
+                    Some(_) => live_in.get(&edge.target).cloned().unwrap_or_default(),
+                    None => RegisterSet::all(),
+                });
+            }
+            liveness.live_out.insert(start, out);
+            let new_in = liveness.backward(block, out, None);
+            if live_in.get(&start) != Some(&new_in) {
+                live_in.insert(start, new_in);
+                worklist.extend(predecessors.get(&start).into_iter().flatten().cloned());
+            }
+        }
+        liveness
+    }
+
+    /// the frame allocated by the function's `alloc`, if there is exactly one.
+    pub fn frame(&self) -> Option<Frame> {
+        self.frame
+    }
+
+    /// the registers live before the instruction at `until`, or before the block if `until` is
+    /// `None`, given those live after the block.
+    fn backward(&self, block: &Block, out: RegisterSet, until: Option<SlotAddress>) -> RegisterSet {
+        let instructions: Vec<(SlotAddress, &Instruction)> = block.instructions(self.bundles).collect();
+        let mut live = out;
+        for (address, inst) in instructions.iter().rev() {
+            if Some(*address) < until {
+                break;
+            }
+            live = step_back(inst, live, self.frame, &self.results);
+        }
+        live
+    }
+
+    /// the registers live on entry to the block starting at `start`.
+    pub fn live_in(&self, start: SlotAddress) -> RegisterSet {
+        match self.cfg.block(start) {
+            Some(block) => self.backward(block, self.live_out(start), None),
+            None => RegisterSet::all(),
+        }
+    }
+
+    /// the registers live on exit from the block starting at `start`.
+    pub fn live_out(&self, start: SlotAddress) -> RegisterSet {
+        self.live_out.get(&start).cloned().unwrap_or_else(RegisterSet::all)
+    }
+
+    /// the registers live just before the instruction at `address`.
+    pub fn live_before(&self, address: SlotAddress) -> RegisterSet {
+        match self.cfg.block_containing(address) {
+            Some(block) => self.backward(block, self.live_out(block.start), Some(address)),
+            None => RegisterSet::all(),
+        }
+    }
+
+    /// the registers live just after the instruction at `address`: those a rewriter can't use as
+    /// scratch registers there.
+    pub fn live_after(&self, address: SlotAddress) -> RegisterSet {
+        match self.cfg.block_containing(address) {
+            Some(block) if address == block.last => self.live_out(block.start),
+            Some(block) => {
+                let next = block.instructions(self.bundles).map(|(at, _)| at).find(|at| *at > address);
+                self.backward(block, self.live_out(block.start), next)
+            }
+            None => RegisterSet::all(),
+        }
+    }
+}
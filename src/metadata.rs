@@ -0,0 +1,475 @@
+//! per-opcode classification: privilege, ordering, and speculation flags, and the unit and
+//! instruction class the manual assigns each opcode.
+//!
+//! a few opcodes cover instructions that differ in exactly these respects: `Mov` moves between
+//! general registers and branch, control, predicate, and indirect registers and the psr, and
+//! `Mov_m` and `Mov_i` move to and from application registers. for those, the methods on
+//! `Opcode` answer for the most demanding form (`Mov` may be privileged) or not at all (`Mov`
+//! has no single unit), and the methods on `Instruction` look at the operands to answer exactly.
+//! a `mov` between general registers or of an immediate is an `adds` or `addl`, and is answered
+//! for as one.
+
+use crate::{Instruction, Opcode, Operand, IndirectionReg};
+use crate::dispersal;
+
+/// the execution unit type of an instruction, as in the manual's table `3-10`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Unit {
+    /// integer ALU: executes on an `I` or `M` unit.
+    A,
+    I,
+    M,
+    F,
+    B,
+    /// the two-slot `movl`, `brl`, and `X`-unit `break`, `nop`, and `hint`.
+    LX,
+}
+
+/// properties of an opcode, or of an instruction.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Flags {
+    /// only executes at privilege level 0, and otherwise raises a privileged operation fault.
+    pub privileged: bool,
+    /// an explicit serialization: `srlz.i`, `srlz.d`, or `sync.i`.
+    pub serializing: bool,
+    /// a memory fence: `mf` or `mf.a`.
+    pub fence: bool,
+    /// a control-speculative load, which defers faults to the NaT bit or NaTVal of its target.
+    pub speculative: bool,
+    /// a data-speculative (advanced) load, which allocates an ALAT entry.
+    pub advanced: bool,
+    /// an atomic read-modify-write of memory.
+    pub atomic: bool,
+    /// may raise a fault. this does not count faults every instruction can raise, such as a
+    /// disabled floating-point register set or an illegal reserved-field encoding.
+    pub may_fault: bool,
+    /// has no architectural effect: a `nop`, a `hint`, a branch prediction hint, or an opcode in
+    /// the space reserved for hints.
+    pub hint: bool,
+}
+
+impl Opcode {
+    /// the flags for this opcode. for `Mov`, `Mov_m`, and `Mov_i` a flag is set if it is set for
+    /// any instruction with the opcode.
+    pub fn flags(&self) -> Flags {
+        use Opcode::*;
+        let privileged = matches!(self,
+            Ptc_l | Ptc_g | Ptc_ga | Ptc_e | Ptr_d | Ptr_i | Itr_d | Itr_i | Itc_d | Itc_i |
+            Tpa | Tak | Ssm | Rsm | Rfi | Bsw_0 | Bsw_1 | Vmsw_0 | Vmsw_1 | Mov_fom_pkr |
+            Mov | Mov_m | Mov_i);
+        let speculative = matches!(self,
+            Ld1_s | Ld2_s | Ld4_s | Ld8_s | Ld1_sa | Ld2_sa | Ld4_sa | Ld8_sa |
+            Ldfe_s | Ldf8_s | Ldfs_s | Ldfd_s | Ldfe_sa | Ldf8_sa | Ldfs_sa | Ldfd_sa |
+            Ldfp8_s | Ldfps_s | Ldfpd_s | Ldfp8_sa | Ldfps_sa | Ldfpd_sa | Ldfp_sa);
+        let advanced = matches!(self,
+            Ld1_a | Ld2_a | Ld4_a | Ld8_a | Ld1_sa | Ld2_sa | Ld4_sa | Ld8_sa |
+            Ldfe_a | Ldf8_a | Ldfs_a | Ldfd_a | Ldfe_sa | Ldf8_sa | Ldfs_sa | Ldfd_sa |
+            Ldfp8_a | Ldfps_a | Ldfpd_a | Ldfp8_sa | Ldfps_sa | Ldfpd_sa | Ldfp_a | Ldfp_sa);
+        let atomic = matches!(self,
+            Cmpxchg1_acq | Cmpxchg2_acq | Cmpxchg4_acq | Cmpxchg8_acq |
+            Cmpxchg1_rel | Cmpxchg2_rel | Cmpxchg4_rel | Cmpxchg8_rel | Cmp8xchg16_acq | Cmp8xchg16_rel |
+            Xchg1 | Xchg2 | Xchg4 | Xchg8 | Fetchadd4_acq | Fetchadd8_acq | Fetchadd4_rel | Fetchadd8_rel);
+        let hint = matches!(self,
+            White | Nop_m | Nop_i | Nop_b | Nop_f | Nop_x | Hint_m | Hint_i | Hint_b | Hint_f | Hint_x |
+            Brp | Brp_ret | Brp_ipwh_ih);
+
+        // memory accesses, other than the ones that defer or ignore their faults.
+        let accesses_memory = self.is_memory_access() && !speculative && !matches!(self, Lfetch | Lfetch_excl);
+        let may_fault = privileged || accesses_memory || matches!(self,
+            // reserved encodings, and `break`, which raises a break instruction fault.
+            Purple | Cyan | Brown | Break_m | Break_i | Break_b | Break_f | Break_x |
+            // register stack changes can raise illegal operation faults or fault on their stores.
+            Alloc | Loadrs | Flushrs |
+            Br_ia |
+            // floating-point arithmetic that can raise IEEE faults.
+            Frcpa | Frsqta | Fprcpa | Fprsqrta | Fmin | Fmax | Famin | Famax | Fpmin | Fpmax | Fpamin | Fpamax |
+            Fcvt_fx | Fcvt_fxu | Fcvt_fx_trunc | Fcvt_fxu_trunc |
+            Fpcvt_fx | Fpcvt_fxu | Fpcvt_fx_trunc | Fpcvt_fxu_trunc |
+            Fcmp_eq | Fcmp_lt | Fcmp_le | Fcmp_unord | Fcmp_eq_unc | Fcmp_lt_unc | Fcmp_le_unc | Fcmp_unord_unc |
+            Fnorm | Fnorm_d | Fnorm_s | Fmpy | Fmpy_d | Fmpy_s | Fma_s | Fma | Fpma | Fma_d |
+            Fms_s | Fms | Fpms | Fms_d | Fnma_s | Fnma | Fpnma | Fnma_d);
+
+        Flags {
+            privileged,
+            serializing: matches!(self, Srlz_i | Srlz_d | Sync_i),
+            fence: matches!(self, Mf | Mf_a),
+            speculative,
+            advanced,
+            atomic,
+            may_fault,
+            hint,
+        }
+    }
+
+    /// does this opcode load from, store to, or otherwise reference memory by address?
+    fn is_memory_access(&self) -> bool {
+        use Opcode::*;
+        self.latency_class() == crate::latency::LatencyClass::IntegerLoad ||
+            self.latency_class() == crate::latency::LatencyClass::FloatLoad ||
+            self.latency_class() == crate::latency::LatencyClass::Store ||
+            matches!(self, Probe_r | Probe_w | Probe_rw_fault | Probe_r_fault | Probe_w_fault | Fc)
+    }
+
+    /// the unit type that executes this opcode. `None` for `Mov`, which may be an `I` or `M`
+    /// instruction, and for the reserved and ignored opcodes, which occur in every unit.
+    pub fn unit(&self) -> Option<Unit> {
+        use Opcode::*;
+        Some(match self {
+            Purple | Cyan | Brown | White | Mov => return None,
+
+            Addp4 | Adds | Addl | Add | AddPlusOne | And | Andcm | Or | Sub | SubMinusOne | Xor |
+            Shladd | Shladdp4 |
+            Padd1 | Padd1_sss | Padd1_uuu | Padd1_uus | Psub1 | Psub1_sss | Psub1_uuu | Psub1_uus |
+            Pavg1 | Pavg1_raz | Pavgsub1 | Pcmp1_eq | Pcmp1_gt |
+            Padd2 | Padd2_sss | Padd2_uuu | Padd2_uus | Psub2 | Psub2_sss | Psub2_uuu | Psub2_uus |
+            Pavg2 | Pavg2_raz | Pavgsub2 | Pshladd2 | Pshradd2 | Pcmp2_eq | Pcmp2_gt |
+            Padd4 | Psub4 | Pcmp4_eq | Pcmp4_gt => Unit::A,
+            _ if self.compare_type().is_some() && !self.is_float_compare() && !self.is_i_unit_test() => Unit::A,
+
+            Break_i | Nop_i | Hint_i | Zxt1 | Zxt2 | Zxt4 | Sxt1 | Sxt2 | Sxt4 | Czx1_l | Czx2_l | Czx1_r | Czx2_r |
+            Chk_s_i_int | Mov_i | Mov_mwh_ih | Mov_ret_mwh_ih | Dep | Dep_z | Extr | Shrp | Extr_u |
+            Pmin1_u | Unpack1_h | Pmax1_u | Unpack1_l | Mix1_r | Mix1_l | Psad1 | Mux1 |
+            Pshr2_u | Pmpyshr2_u | Pshr2 | Pmpyshr2 | Pshl1 | Pshr2_u_fixed | Pshr2_fixed | Popcnt | Clz |
+            Pack2_uss | Pack2_sss | Pmin2 | Unpack2_h | Unpack2_l | Pmax2 | Mix2_r | Mix2_l |
+            Pmpy2_r | Pmpy2_l | Pshl2_fixed | Mux2 | Pshr4_u | Pshr4 | Pshl4 | Mpy4 | Mpyshl4 |
+            Pshr4_u_fixed | Pshr4_fixed | Pack4_sss | Unpack4_h | Unpack4_l | Mix4_r | Mix4_l | Pshl4_fixed |
+            Shr_u | Shr | Shl => Unit::I,
+            _ if self.is_i_unit_test() => Unit::I,
+
+            Break_b | Cover | Clrrb | Clrrb_pr | Rfi | Bsw_0 | Bsw_1 | Epc | Vmsw_0 | Vmsw_1 |
+            Br_cond | Br_ia | Br_ret | Br_call | Nop_b | Hint_b | Brp | Brp_ret | Brp_ipwh_ih |
+            Br_wexit | Br_wtop | Br_cloop | Br_cexit | Br_ctop => Unit::B,
+
+            Movl | Brl_cond_bwh_ph_dh | Brl_call_bwh_ph_dh | Break_x | Nop_x | Hint_x => Unit::LX,
+
+            Frcpa | Frsqta | Break_f | Fsetc | Fclrf | Fchkf | Fmerge_s | Fmerge_ns | Fmerge_se |
+            Fmin | Fmax | Famin | Famax | Fcvt_fx | Fcvt_fxu | Fcvt_fx_trunc | Fcvt_fxu_trunc | Fcvt_xf |
+            Fpack | Fand | Fandcm | For | Fxor | Fswap | Fswap_nl | Fswap_nr | Fmix_lr | Fmix_r | Fmix_l |
+            Fsxt_r | Fsxt_l | Hint_f | Nop_f | Fprcpa | Fprsqrta | Fpmerge_s | Fpmerge_ns | Fpmerge_se |
+            Fpmin | Fpmax | Fpamin | Fpamax | Fpcvt_fx | Fpcvt_fxu | Fpcvt_fx_trunc | Fpcvt_fxu_trunc |
+            Fnorm | Fnorm_d | Fnorm_s | Fmpy | Fmpy_d | Fmpy_s | Fma_s | Fma | Fpma | Fma_d |
+            Fms_s | Fms | Fpms | Fms_d | Fnma_s | Fnma | Fpnma | Fnma_d |
+            Xma_l | Xma_hu | Xma_h | Xmpy_l | Xmpy_hu | Xmpy_h | Fselect => Unit::F,
+            _ if self.is_float_compare() => Unit::F,
+
+            _ => Unit::M,
+        })
+    }
+
+    fn is_float_compare(&self) -> bool {
+        use Opcode::*;
+        matches!(self,
+            Fcmp_eq | Fcmp_lt | Fcmp_le | Fcmp_unord | Fcmp_eq_unc | Fcmp_lt_unc | Fcmp_le_unc | Fcmp_unord_unc |
+            Fclass_m | Fclass_m_unc)
+    }
+
+    /// `tbit`, `tnat`, and `tf`, which write predicates from the `I` unit.
+    fn is_i_unit_test(&self) -> bool {
+        use Opcode::*;
+        matches!(self,
+            Tbit_z | Tnat_z | Tbit_z_unc | Tnat_z_unc | Tbit_z_and | Tnat_z_and | Tbit_nz_and | Tnat_nz_and |
+            Tbit_z_or | Tnat_z_or | Tbit_nz_or | Tnat_nz_or | Tbit_z_or_andcm | Tnat_z_or_andcm |
+            Tbit_nz_or_andcm | Tnat_nz_or_andcm |
+            Tf_z | Tf_z_nc | Tf_z_and | Tf_nz_and | Tf_z_or | Tf_nz_or | Tf_z_or_andcm | Tf_nz_or_andcm)
+    }
+
+    /// the name of the instruction class the dependency tables in the manual's chapter `5`
+    /// (`Resource and Dependency Semantics`) list this opcode under: a class such as `ld-s` or
+    /// `mov-to-BR` where the tables group instructions, and the base mnemonic otherwise. `None`
+    /// for `Mov`, `Mov_m`, and `Mov_i`, whose class depends on the registers moved, and for
+    /// reserved and ignored opcodes.
+    pub fn instruction_class(&self) -> Option<&'static str> {
+        use Opcode::*;
+        if self.is_float_compare() {
+            return Some(if matches!(self, Fclass_m | Fclass_m_unc) { "fclass" } else { "fcmp" });
+        }
+        if self.is_i_unit_test() {
+            return Some(match self {
+                Tbit_z | Tbit_z_unc | Tbit_z_and | Tbit_nz_and | Tbit_z_or | Tbit_nz_or |
+                Tbit_z_or_andcm | Tbit_nz_or_andcm => "tbit",
+                Tnat_z | Tnat_z_unc | Tnat_z_and | Tnat_nz_and | Tnat_z_or | Tnat_nz_or |
+                Tnat_z_or_andcm | Tnat_nz_or_andcm => "tnat",
+                _ => "tf",
+            });
+        }
+        if self.compare_type().is_some() {
+            let cmp4 = matches!(self,
+                Cmp4_eq | Cmp4_eq_and | Cmp4_eq_or | Cmp4_eq_or_andcm | Cmp4_eq_unc |
+                Cmp4_ge_and | Cmp4_ge_or | Cmp4_ge_or_andcm | Cmp4_gt_and | Cmp4_gt_or | Cmp4_gt_or_andcm |
+                Cmp4_le_and | Cmp4_le_or | Cmp4_le_or_andcm | Cmp4_lt | Cmp4_lt_and | Cmp4_lt_or |
+                Cmp4_lt_or_andcm | Cmp4_lt_unc | Cmp4_ltu | Cmp4_ltu_unc |
+                Cmp4_ne_and | Cmp4_ne_or | Cmp4_ne_or_andcm);
+            return Some(if cmp4 { "cmp4" } else { "cmp" });
+        }
+        Some(match self {
+            Purple | Cyan | Brown | White | Mov | Mov_m | Mov_i => return None,
+
+            Addp4 => "addp4",
+            Adds => "adds",
+            Addl => "addl",
+            Add | AddPlusOne => "add",
+            Sub | SubMinusOne => "sub",
+            And => "and",
+            Andcm => "andcm",
+            Or => "or",
+            Xor => "xor",
+            Shladd => "shladd",
+            Shladdp4 => "shladdp4",
+            Padd1 | Padd1_sss | Padd1_uuu | Padd1_uus | Padd2 | Padd2_sss | Padd2_uuu | Padd2_uus | Padd4 => "padd",
+            Psub1 | Psub1_sss | Psub1_uuu | Psub1_uus | Psub2 | Psub2_sss | Psub2_uuu | Psub2_uus | Psub4 => "psub",
+            Pavg1 | Pavg1_raz | Pavg2 | Pavg2_raz => "pavg",
+            Pavgsub1 | Pavgsub2 => "pavgsub",
+            Pcmp1_eq | Pcmp1_gt | Pcmp2_eq | Pcmp2_gt | Pcmp4_eq | Pcmp4_gt => "pcmp",
+            Pshladd2 => "pshladd",
+            Pshradd2 => "pshradd",
+
+            Zxt1 | Zxt2 | Zxt4 => "zxt",
+            Sxt1 | Sxt2 | Sxt4 => "sxt",
+            Czx1_l | Czx2_l | Czx1_r | Czx2_r => "czx",
+            Dep | Dep_z => "dep",
+            Extr | Extr_u => "extr",
+            Shrp => "shrp",
+            Shr | Shr_u => "shr",
+            Shl => "shl",
+            Pmin1_u | Pmin2 => "pmin",
+            Pmax1_u | Pmax2 => "pmax",
+            Unpack1_h | Unpack1_l | Unpack2_h | Unpack2_l | Unpack4_h | Unpack4_l => "unpack",
+            Mix1_r | Mix1_l | Mix2_r | Mix2_l | Mix4_r | Mix4_l => "mix",
+            Psad1 => "psad",
+            Mux1 | Mux2 => "mux",
+            Pshr2_u | Pshr2 | Pshr2_u_fixed | Pshr2_fixed | Pshr4_u | Pshr4 | Pshr4_u_fixed | Pshr4_fixed => "pshr",
+            Pshl1 | Pshl2_fixed | Pshl4 | Pshl4_fixed => "pshl",
+            Pmpyshr2_u | Pmpyshr2 => "pmpyshr",
+            Pmpy2_r | Pmpy2_l => "pmpy",
+            Pack2_uss | Pack2_sss | Pack4_sss => "pack",
+            Popcnt => "popcnt",
+            Clz => "clz",
+            Mpy4 => "mpy4",
+            Mpyshl4 => "mpyshl4",
+            Mov_mwh_ih | Mov_ret_mwh_ih => "mov-to-BR",
+
+            Chk_s_i_int | Chk_s_m_int | Chk_s_fp => "chk.s",
+            Chk_a_nc_int | Chk_a_nc_fp => "chk.a.nc",
+            Chk_a_clr_int | Chk_a_clr_fp => "chk.a.clr",
+            Alloc => "alloc",
+            Ld1 | Ld2 | Ld4 | Ld8 | Ld1_bias | Ld2_bias | Ld4_bias | Ld8_bias |
+            Ld1_acq | Ld2_acq | Ld4_acq | Ld8_acq | Ld8_fill | Ld16 | Ld16_acq => "ld",
+            Ld1_s | Ld2_s | Ld4_s | Ld8_s => "ld-s",
+            Ld1_a | Ld2_a | Ld4_a | Ld8_a => "ld-a",
+            Ld1_sa | Ld2_sa | Ld4_sa | Ld8_sa => "ld-sa",
+            Ld1_c_nc | Ld2_c_nc | Ld4_c_nc | Ld8_c_nc => "ld-c-nc",
+            Ld1_c_clr | Ld2_c_clr | Ld4_c_clr | Ld8_c_clr => "ld-c-clr",
+            Ld1_c_clr_acq | Ld2_c_clr_acq | Ld4_c_clr_acq | Ld8_c_clr_acq => "ld-c-clr-acq",
+            Ldfe | Ldf8 | Ldfs | Ldfd | Ldf_fill => "ldf",
+            Ldfe_s | Ldf8_s | Ldfs_s | Ldfd_s => "ldf-s",
+            Ldfe_a | Ldf8_a | Ldfs_a | Ldfd_a => "ldf-a",
+            Ldfe_sa | Ldf8_sa | Ldfs_sa | Ldfd_sa => "ldf-sa",
+            Ldfe_c_nc | Ldf8_c_nc | Ldfs_c_nc | Ldfd_c_nc => "ldf-c-nc",
+            Ldfe_c_clr | Ldf8_c_clr | Ldfs_c_clr | Ldfd_c_clr => "ldf-c-clr",
+            Ldfp8 | Ldfps | Ldfpd => "ldfp",
+            Ldfp8_s | Ldfps_s | Ldfpd_s => "ldfp-s",
+            Ldfp8_a | Ldfps_a | Ldfpd_a | Ldfp_a => "ldfp-a",
+            Ldfp8_sa | Ldfps_sa | Ldfpd_sa | Ldfp_sa => "ldfp-sa",
+            Ldfp8_c_nc | Ldfps_c_nc | Ldfpd_c_nc => "ldfp-c-nc",
+            Ldfp8_c_clr | Ldfps_c_clr | Ldfpd_c_clr => "ldfp-c-clr",
+            St1 | St2 | St4 | St8 | St1_rel | St2_rel | St4_rel | St8_rel | St8_spill | St16 | St16_rel => "st",
+            Stfe | Stf8 | Stfs | Stfd | Stf_spill => "stf",
+            Cmpxchg1_acq | Cmpxchg2_acq | Cmpxchg4_acq | Cmpxchg8_acq |
+            Cmpxchg1_rel | Cmpxchg2_rel | Cmpxchg4_rel | Cmpxchg8_rel => "cmpxchg",
+            Cmp8xchg16_acq | Cmp8xchg16_rel => "cmp8xchg",
+            Xchg1 | Xchg2 | Xchg4 | Xchg8 => "xchg",
+            Fetchadd4_acq | Fetchadd8_acq | Fetchadd4_rel | Fetchadd8_rel => "fetchadd",
+            Getf_sig | Getf_exp | Getf_s | Getf_d => "getf",
+            Setf_sig | Setf_exp | Setf_s | Setf_d => "setf",
+            Lfetch | Lfetch_excl => "lfetch-nofault",
+            Lfetch_fault | Lfetch_fault_excl => "lfetch-fault",
+            Probe_r | Probe_w => "probe-nofault",
+            Probe_rw_fault | Probe_r_fault | Probe_w_fault => "probe-fault",
+            Fc => "fc",
+            Fwb => "fwb",
+            Invala => "invala",
+            Invala_e_int | Invala_e_fp => "invala.e",
+            Mf | Mf_a => "mf",
+            Srlz_d => "srlz.d",
+            Srlz_i => "srlz.i",
+            Sync_i => "sync.i",
+            Sum => "sum",
+            Rum => "rum",
+            Ssm => "ssm",
+            Rsm => "rsm",
+            Loadrs => "loadrs",
+            Flushrs => "flushrs",
+            Ptc_l => "ptc.l",
+            Ptc_g => "ptc.g",
+            Ptc_ga => "ptc.ga",
+            Ptc_e => "ptc.e",
+            Ptr_d => "ptr.d",
+            Ptr_i => "ptr.i",
+            Itr_d => "itr.d",
+            Itr_i => "itr.i",
+            Itc_d => "itc.d",
+            Itc_i => "itc.i",
+            Thash => "thash",
+            Ttag => "ttag",
+            Tpa => "tpa",
+            Tak => "tak",
+            Mov_fom_pkr => "mov-from-IND",
+
+            Br_cond | Brl_cond_bwh_ph_dh => "br.cond",
+            Br_call | Brl_call_bwh_ph_dh => "br.call",
+            Br_ret => "br.ret",
+            Br_ia => "br.ia",
+            Br_wexit => "br.wexit",
+            Br_wtop => "br.wtop",
+            Br_cloop => "br.cloop",
+            Br_cexit => "br.cexit",
+            Br_ctop => "br.ctop",
+            Brp | Brp_ret | Brp_ipwh_ih => "brp",
+            Cover => "cover",
+            Clrrb | Clrrb_pr => "clrrb",
+            Rfi => "rfi",
+            Bsw_0 | Bsw_1 => "bsw",
+            Epc => "epc",
+            Vmsw_0 | Vmsw_1 => "vmsw",
+            Movl => "movl",
+
+            Break_m | Break_i | Break_b | Break_f | Break_x => "break",
+            Nop_m | Nop_i | Nop_b | Nop_f | Nop_x => "nop",
+            Hint_m | Hint_i | Hint_b | Hint_f | Hint_x => "hint",
+
+            Frcpa => "frcpa",
+            Frsqta => "frsqrta",
+            Fprcpa => "fprcpa",
+            Fprsqrta => "fprsqrta",
+            Fsetc => "fsetc",
+            Fclrf => "fclrf",
+            Fchkf => "fchkf",
+            Fmerge_s | Fmerge_ns | Fmerge_se => "fmerge",
+            Fpmerge_s | Fpmerge_ns | Fpmerge_se => "fpmerge",
+            Fmin => "fmin",
+            Fmax => "fmax",
+            Famin => "famin",
+            Famax => "famax",
+            Fpmin => "fpmin",
+            Fpmax => "fpmax",
+            Fpamin => "fpamin",
+            Fpamax => "fpamax",
+            Fcvt_fx | Fcvt_fx_trunc => "fcvt.fx",
+            Fcvt_fxu | Fcvt_fxu_trunc => "fcvt.fxu",
+            Fcvt_xf => "fcvt.xf",
+            Fpcvt_fx | Fpcvt_fx_trunc => "fpcvt.fx",
+            Fpcvt_fxu | Fpcvt_fxu_trunc => "fpcvt.fxu",
+            Fpack => "fpack",
+            Fand => "fand",
+            Fandcm => "fandcm",
+            For => "for",
+            Fxor => "fxor",
+            Fswap | Fswap_nl | Fswap_nr => "fswap",
+            Fmix_lr | Fmix_r | Fmix_l => "fmix",
+            Fsxt_r | Fsxt_l => "fsxt",
+            // `fnorm` and `fmpy` are pseudo-ops of `fma`.
+            Fnorm | Fnorm_d | Fnorm_s | Fmpy | Fmpy_d | Fmpy_s | Fma_s | Fma | Fma_d => "fma",
+            Fpma => "fpma",
+            Fms_s | Fms | Fms_d => "fms",
+            Fpms => "fpms",
+            Fnma_s | Fnma | Fnma_d => "fnma",
+            Fpnma => "fpnma",
+            // `xmpy` is a pseudo-op of `xma`.
+            Xma_l | Xma_hu | Xma_h | Xmpy_l | Xmpy_hu | Xmpy_h => "xma",
+            Fselect => "fselect",
+
+            _ => return None,
+        })
+    }
+}
+
+impl Instruction {
+    /// the `A`-unit opcode this instruction is an alias of, if it is `mov r1=r3`, an `adds`, or
+    /// `mov r1=imm`, an `addl`.
+    fn register_move(&self) -> Option<Opcode> {
+        if !dispersal::is_register_move(self.opcode(), self.operands()) {
+            return None;
+        }
+        Some(if let Operand::GPRegister(_) = self.operands()[1] { Opcode::Adds } else { Opcode::Addl })
+    }
+
+    /// the flags for this instruction: those of its opcode, made exact for moves.
+    pub fn flags(&self) -> Flags {
+        if let Some(opcode) = self.register_move() {
+            return opcode.flags();
+        }
+        let mut flags = self.opcode().flags();
+        let operands = self.operands();
+        match self.opcode() {
+            Opcode::Mov => {
+                flags.privileged = match (operands[0], operands[1]) {
+                    (Operand::ControlRegister(_), _) | (_, Operand::ControlRegister(_)) => true,
+                    (Operand::PSR_l, _) | (_, Operand::PSR) => true,
+                    (Operand::Indirection(_, _), _) => true,
+                    // performance monitor data and cpuid can be read from any privilege level.
+                    (_, Operand::Indirection(IndirectionReg::Pmd, _)) |
+                    (_, Operand::Indirection(IndirectionReg::Cpuid, _)) => false,
+                    (_, Operand::Indirection(_, _)) => true,
+                    _ => false,
+                };
+                // a move out of a general register faults if the register is NaT.
+                flags.may_fault = flags.privileged || matches!(operands[1], Operand::GPRegister(_));
+            }
+            Opcode::Mov_m | Opcode::Mov_i => {
+                // only the kernel registers are always privileged to write.
+                flags.privileged = matches!(operands[0], Operand::ApplicationRegister(ar) if ar.0 < 8);
+                flags.may_fault = matches!(operands[0], Operand::ApplicationRegister(_));
+            }
+            _ => {}
+        }
+        flags
+    }
+
+    /// the unit type that executes this instruction. `None` only for reserved and ignored
+    /// opcodes, which occur in every unit.
+    pub fn unit(&self) -> Option<Unit> {
+        if let Some(opcode) = self.register_move() {
+            return opcode.unit();
+        }
+        if self.opcode() != Opcode::Mov {
+            return self.opcode().unit();
+        }
+        // moves to and from the branch and predicate registers and `ip` are `I`-unit
+        // instructions; the rest are `M`-unit ones.
+        Some(match (self.operands()[0], self.operands()[1]) {
+            (_, Operand::BranchRegister(_)) | (Operand::PR, _) | (_, Operand::PR) | (_, Operand::IP) => Unit::I,
+            _ => Unit::M,
+        })
+    }
+
+    /// the instruction class of this instruction, as for `Opcode::instruction_class`, with the
+    /// registers of a move taken into account.
+    pub fn instruction_class(&self) -> Option<&'static str> {
+        if let Some(opcode) = self.register_move() {
+            return opcode.instruction_class();
+        }
+        let (dest, source) = (self.operands()[0], self.operands()[1]);
+        let class = match self.opcode() {
+            Opcode::Mov_m => if let Operand::ApplicationRegister(_) = dest { "mov-to-AR-M" } else { "mov-from-AR-M" },
+            Opcode::Mov_i => if let Operand::ApplicationRegister(_) = dest { "mov-to-AR-I" } else { "mov-from-AR-I" },
+            Opcode::Mov => match (dest, source) {
+                (_, Operand::BranchRegister(_)) => "mov-from-BR",
+                (Operand::PR, Operand::ImmU64(_)) => "mov-to-PR-rotreg",
+                (Operand::PR, _) => "mov-to-PR-allreg",
+                (_, Operand::PR) => "mov-from-PR",
+                (_, Operand::IP) => "mov-ip",
+                (Operand::ControlRegister(_), _) => "mov-to-CR",
+                (_, Operand::ControlRegister(_)) => "mov-from-CR",
+                (Operand::PSR_l, _) => "mov-to-PSR-l",
+                (Operand::PSR_um, _) => "mov-to-PSR-um",
+                (_, Operand::PSR) => "mov-from-PSR",
+                (_, Operand::PSR_um) => "mov-from-PSR-um",
+                (Operand::Indirection(_, _), _) => "mov-to-IND",
+                (_, Operand::Indirection(_, _)) => "mov-from-IND",
+                _ => return None,
+            },
+            other => return other.instruction_class(),
+        };
+        Some(class)
+    }
+}
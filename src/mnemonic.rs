@@ -0,0 +1,258 @@
+//! mnemonics split into a base operation and typed completers.
+//!
+//! `Opcode`'s `Display` impl writes a whole mnemonic such as `ld8.c.clr.acq`; `mnemonic_parts`
+//! splits that into the base, `ld8`, and the completers `.c.clr` and `.acq`, so that "all loads
+//! with `.acq`" or "all 4-byte compares" can be found without matching on strings.
+//!
+//! sizes and floating-point formats that the assembler writes into the base (the `8` of `ld8`,
+//! the `s` of `ldfs`) are kept in the base, and are also reported as completers. completers
+//! that have no type here are reported as `Other`, in the order they are written.
+
+use crate::Opcode;
+use crate::metadata::Unit;
+use crate::predicate::CompareType;
+
+/// a mnemonic split into its parts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MnemonicParts {
+    /// the base operation, such as `cmp4`, `ldfps`, or `fetchadd4`.
+    pub base: &'static str,
+    pub completers: Vec<Completer>,
+}
+
+impl MnemonicParts {
+    /// true if `completer` is one of the completers.
+    pub fn has(&self, completer: Completer) -> bool {
+        self.completers.contains(&completer)
+    }
+
+    /// the access or operand size, in bytes, if the mnemonic names one.
+    pub fn size(&self) -> Option<u8> {
+        self.completers.iter().find_map(|c| match c {
+            Completer::Size(size) => Some(*size),
+            _ => None,
+        })
+    }
+}
+
+/// one completer of a mnemonic.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Completer {
+    /// the relation of a compare, `tbit`, `tnat`, `tf`, `fcmp`, or `fclass`.
+    Relation(Relation),
+    /// the comparison type of a compare. `CompareType::Normal` has no completer and is never
+    /// reported.
+    CompareType(CompareType),
+    /// the speculation or check completer of a load or `chk`.
+    Speculation(Speculation),
+    /// the ordering completer of a load, store, or semaphore.
+    Ordering(Ordering),
+    /// an access size in bytes, from `ld8` or `cmpxchg4`, or an element size from `padd2`.
+    Size(u8),
+    /// the precision of an arithmetic floating-point operation, or the format of a
+    /// floating-point load, store, `getf`, or `setf`.
+    Precision(Precision),
+    /// the unit of a `break`, `nop`, `hint`, or `mov`.
+    Unit(Unit),
+    Other(&'static str),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Ltu,
+    /// `fcmp.unord`.
+    Unord,
+    /// `.z` of `tbit`, `tnat`, and `tf`.
+    Z,
+    /// `.nz` of `tbit`, `tnat`, and `tf`.
+    Nz,
+    /// `fclass.m`.
+    M,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Speculation {
+    /// `.s`: control speculative.
+    Control,
+    /// `.a`: data speculative (advanced).
+    Advanced,
+    /// `.sa`: control and data speculative.
+    ControlAdvanced,
+    /// `.c.clr`: a check load that invalidates the matching ALAT entry.
+    CheckClear,
+    /// `.c.nc`: a check load that keeps the matching ALAT entry.
+    CheckNoClear,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ordering {
+    /// `.acq`.
+    Acquire,
+    /// `.rel`.
+    Release,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// `s`: single precision.
+    Single,
+    /// `d`: double precision.
+    Double,
+    /// `e`: double-extended, in memory.
+    Extended,
+    /// `8` or `sig`: the 64-bit significand.
+    Significand,
+    /// `exp`: the sign and exponent.
+    Exponent,
+}
+
+/// words in `Opcode` names that stand for operand fields rather than completers.
+const OPERAND_FIELDS: &[&str] = &["bwh", "ph", "dh", "ipwh", "ih"];
+
+impl Opcode {
+    /// this opcode's mnemonic, split into its base and completers.
+    pub fn mnemonic_parts(&self) -> MnemonicParts {
+        let name = match self {
+            // `add r1=r2,r3,1` and `sub r1=r2,r3,1`: the constant is an operand.
+            Opcode::AddPlusOne => "add",
+            Opcode::SubMinusOne => "sub",
+            _ => self.name(),
+        };
+        let mut words = name.split('.');
+        let base = words.next().unwrap_or(name);
+        let words: Vec<&'static str> = words.filter(|w| !OPERAND_FIELDS.contains(w)).collect();
+
+        let mut completers = Vec::new();
+        if let Some(precision) = memory_format(base) {
+            completers.push(Completer::Precision(precision));
+        } else if let Some(size) = size(base) {
+            completers.push(Completer::Size(size));
+        }
+
+        let compare = matches!(base, "cmp" | "cmp4" | "tbit" | "tnat" | "tf" | "fcmp" | "fclass");
+        let check = base == "chk";
+        let load = base.starts_with("ld");
+        let precision = matches!(base, "fma" | "fms" | "fnma" | "fnorm" | "fmpy" | "getf" | "setf");
+        let unit = matches!(base, "break" | "nop" | "hint" | "mov");
+
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i];
+            let next = words.get(i + 1).cloned();
+            let mut taken = 1;
+            let completer = if compare && i == 0 {
+                relation(word).map(Completer::Relation)
+            } else if compare {
+                match (word, next) {
+                    ("unc", _) => Some(Completer::CompareType(CompareType::Unc)),
+                    ("and", _) => Some(Completer::CompareType(CompareType::And)),
+                    ("or", Some("andcm")) => {
+                        taken = 2;
+                        Some(Completer::CompareType(CompareType::OrAndcm))
+                    }
+                    ("or", _) => Some(Completer::CompareType(CompareType::Or)),
+                    _ => None,
+                }
+            } else if load {
+                match (word, next) {
+                    ("s", _) => Some(Completer::Speculation(Speculation::Control)),
+                    ("a", _) => Some(Completer::Speculation(Speculation::Advanced)),
+                    ("sa", _) => Some(Completer::Speculation(Speculation::ControlAdvanced)),
+                    ("c", Some("clr")) => {
+                        taken = 2;
+                        Some(Completer::Speculation(Speculation::CheckClear))
+                    }
+                    ("c", Some("nc")) => {
+                        taken = 2;
+                        Some(Completer::Speculation(Speculation::CheckNoClear))
+                    }
+                    _ => None,
+                }
+            } else if check {
+                match word {
+                    "s" => Some(Completer::Speculation(Speculation::Control)),
+                    "a" => Some(Completer::Speculation(Speculation::Advanced)),
+                    _ => unit_completer(word),
+                }
+            } else if precision {
+                match word {
+                    "s" => Some(Completer::Precision(Precision::Single)),
+                    "d" => Some(Completer::Precision(Precision::Double)),
+                    "sig" => Some(Completer::Precision(Precision::Significand)),
+                    "exp" => Some(Completer::Precision(Precision::Exponent)),
+                    _ => None,
+                }
+            } else if unit {
+                unit_completer(word)
+            } else {
+                None
+            };
+            let completer = completer.or(match word {
+                "acq" => Some(Completer::Ordering(Ordering::Acquire)),
+                "rel" => Some(Completer::Ordering(Ordering::Release)),
+                _ => None,
+            });
+            completers.push(completer.unwrap_or(Completer::Other(word)));
+            i += taken;
+        }
+
+        MnemonicParts { base, completers }
+    }
+}
+
+fn relation(word: &str) -> Option<Relation> {
+    Some(match word {
+        "eq" => Relation::Eq,
+        "ne" => Relation::Ne,
+        "lt" => Relation::Lt,
+        "le" => Relation::Le,
+        "gt" => Relation::Gt,
+        "ge" => Relation::Ge,
+        "ltu" => Relation::Ltu,
+        "unord" => Relation::Unord,
+        "z" => Relation::Z,
+        "nz" => Relation::Nz,
+        "m" => Relation::M,
+        _ => { return None; }
+    })
+}
+
+fn unit_completer(word: &str) -> Option<Completer> {
+    Some(Completer::Unit(match word {
+        "m" => Unit::M,
+        "i" => Unit::I,
+        "b" => Unit::B,
+        "f" => Unit::F,
+        "x" => Unit::LX,
+        _ => { return None; }
+    }))
+}
+
+/// the format of a floating-point load or store, from the last letter of its base.
+fn memory_format(base: &str) -> Option<Precision> {
+    let format = base.strip_prefix("ldfp")
+        .or_else(|| base.strip_prefix("ldf"))
+        .or_else(|| base.strip_prefix("stf"))?;
+    match format {
+        "s" => Some(Precision::Single),
+        "d" => Some(Precision::Double),
+        "e" => Some(Precision::Extended),
+        "8" => Some(Precision::Significand),
+        _ => None,
+    }
+}
+
+/// the size at the end of a base like `ld8`, `cmp4`, or `cmp8xchg16`.
+fn size(base: &str) -> Option<u8> {
+    let prefix = base.trim_end_matches(|c: char| c.is_ascii_digit());
+    if prefix.len() == base.len() {
+        return None;
+    }
+    base[prefix.len()..].parse().ok()
+}
@@ -0,0 +1,228 @@
+//! parsers for the text `Display` writes for opcodes, registers, and operands.
+//!
+//! each parser accepts exactly what the matching `Display` impl writes, so `s.parse::<T>()`
+//! round-trips `t.to_string()`. a few values share their text with another: `Opcode::Mov_mwh_ih`
+//! is written `mov` like `Opcode::Mov`, and a non-negative `Operand::ImmI64` is written like an
+//! `Operand::ImmU64`. those parse as `Mov` and `ImmU64`.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{ApplicationRegister, BranchRegister, ControlRegister, FloatRegister, GPRegister};
+use crate::{IndirectionReg, Opcode, Operand, PredicateRegister};
+
+/// reasons a string is not an opcode, register, or operand.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// not a name `Display` writes for this type.
+    Unrecognized,
+    /// a register number or immediate is malformed: not decimal or hex as `Display` writes it,
+    /// or with leading zeros.
+    BadNumber,
+    /// a register number is past the end of its register file.
+    OutOfRange,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Unrecognized => f.write_str("unrecognized name"),
+            ParseError::BadNumber => f.write_str("bad number"),
+            ParseError::OutOfRange => f.write_str("register number out of range"),
+        }
+    }
+}
+
+/// a decimal number as `{}` writes it.
+fn decimal(s: &str) -> Result<u64, ParseError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0')) {
+        return Err(ParseError::BadNumber);
+    }
+    s.parse().map_err(|_| ParseError::BadNumber)
+}
+
+/// a hex number as `{:#x}` writes it.
+fn hex(s: &str) -> Result<u64, ParseError> {
+    let digits = s.strip_prefix("0x").ok_or(ParseError::BadNumber)?;
+    if digits.is_empty() ||
+        !digits.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) ||
+        (digits.len() > 1 && digits.starts_with('0')) {
+        return Err(ParseError::BadNumber);
+    }
+    u64::from_str_radix(digits, 16).map_err(|_| ParseError::BadNumber)
+}
+
+/// the register number after `prefix`, for a register file of `count` registers.
+fn register(s: &str, prefix: &str, count: u64) -> Result<u8, ParseError> {
+    let number = decimal(s.strip_prefix(prefix).ok_or(ParseError::Unrecognized)?)?;
+    if number >= count {
+        return Err(ParseError::OutOfRange);
+    }
+    Ok(number as u8)
+}
+
+impl FromStr for GPRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<GPRegister, ParseError> {
+        register(s, "r", 128).map(GPRegister)
+    }
+}
+
+impl FromStr for FloatRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<FloatRegister, ParseError> {
+        register(s, "f", 128).map(FloatRegister)
+    }
+}
+
+impl FromStr for PredicateRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<PredicateRegister, ParseError> {
+        register(s, "p", 64).map(PredicateRegister)
+    }
+}
+
+impl FromStr for BranchRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<BranchRegister, ParseError> {
+        register(s, "b", 8).map(BranchRegister)
+    }
+}
+
+impl FromStr for ControlRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<ControlRegister, ParseError> {
+        register(s, "cr", 128).map(ControlRegister)
+    }
+}
+
+const APPLICATION_REGISTER_NAMES: &[(&str, ApplicationRegister)] = &[
+    ("ar.kr0", ApplicationRegister::KR0),
+    ("ar.kr1", ApplicationRegister::KR1),
+    ("ar.kr2", ApplicationRegister::KR2),
+    ("ar.kr3", ApplicationRegister::KR3),
+    ("ar.kr4", ApplicationRegister::KR4),
+    ("ar.kr5", ApplicationRegister::KR5),
+    ("ar.kr6", ApplicationRegister::KR6),
+    ("ar.kr7", ApplicationRegister::KR7),
+    ("ar.rsc", ApplicationRegister::RSC),
+    ("ar.bsp", ApplicationRegister::BSP),
+    ("ar.bspstore", ApplicationRegister::BSPSTORE),
+    ("ar.rnat", ApplicationRegister::RNAT),
+    ("ar.fcr", ApplicationRegister(21)),
+    ("ar.eflag", ApplicationRegister(24)),
+    ("ar.csd", ApplicationRegister::CSD),
+    ("ar.ssd", ApplicationRegister(26)),
+    ("ar.cflg", ApplicationRegister(27)),
+    ("ar.fsr", ApplicationRegister(28)),
+    ("ar.fir", ApplicationRegister(29)),
+    ("ar.fdr", ApplicationRegister(30)),
+    ("ar.ccv", ApplicationRegister::CCV),
+    ("ar.unat", ApplicationRegister::UNAT),
+    ("ar.fpsr", ApplicationRegister::FPSR),
+    ("ar.itc", ApplicationRegister::ITC),
+    ("ar.ruc", ApplicationRegister::RUC),
+    ("ar.pfs", ApplicationRegister::PFS),
+    ("ar.lc", ApplicationRegister::LC),
+    ("ar.ec", ApplicationRegister::EC),
+];
+
+/// `ar.bspstore` for a named register, `ar48` for any other.
+impl FromStr for ApplicationRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<ApplicationRegister, ParseError> {
+        if let Some((_, reg)) = APPLICATION_REGISTER_NAMES.iter().find(|(name, _)| *name == s) {
+            return Ok(*reg);
+        }
+        let reg = ApplicationRegister(register(s, "ar", 128)?);
+        if APPLICATION_REGISTER_NAMES.iter().any(|(_, named)| *named == reg) {
+            // `Display` writes this one by name.
+            return Err(ParseError::Unrecognized);
+        }
+        Ok(reg)
+    }
+}
+
+impl FromStr for IndirectionReg {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<IndirectionReg, ParseError> {
+        use IndirectionReg::*;
+        Ok(match s {
+            "cpuid" => Cpuid,
+            "dbr" => Dbr,
+            "dtr" => Dtr,
+            "ibr" => Ibr,
+            "itr" => Itr,
+            "msr" => Msr,
+            "pkr" => Pkr,
+            "pmc" => Pmc,
+            "pmd" => Pmd,
+            "rr" => Rr,
+            _ => { return Err(ParseError::Unrecognized); }
+        })
+    }
+}
+
+/// any operand but `Operand::None`, which has no text.
+impl FromStr for Operand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Operand, ParseError> {
+        match s {
+            "psr" => return Ok(Operand::PSR),
+            "psr.l" => return Ok(Operand::PSR_l),
+            "psr.um" => return Ok(Operand::PSR_um),
+            "pr" => return Ok(Operand::PR),
+            "ip" => return Ok(Operand::IP),
+            _ => {}
+        }
+        if let Some(magnitude) = s.strip_prefix('-') {
+            let magnitude = hex(magnitude)?;
+            if magnitude == 0 || magnitude > 1 << 63 {
+                return Err(ParseError::BadNumber);
+            }
+            return Ok(Operand::ImmI64(magnitude.wrapping_neg() as i64));
+        }
+        if s.starts_with(|c: char| c.is_ascii_digit()) {
+            return hex(s).map(Operand::ImmU64);
+        }
+        if let Some(inner) = s.strip_suffix(']') {
+            let open = inner.find('[').ok_or(ParseError::Unrecognized)?;
+            let reg = inner[open + 1..].parse()?;
+            if open == 0 {
+                return Ok(Operand::Memory(reg));
+            }
+            return Ok(Operand::Indirection(inner[..open].parse()?, reg));
+        }
+        if s.starts_with("ar") {
+            s.parse().map(Operand::ApplicationRegister)
+        } else if s.starts_with("cr") {
+            s.parse().map(Operand::ControlRegister)
+        } else if s.starts_with('r') {
+            s.parse().map(Operand::GPRegister)
+        } else if s.starts_with('f') {
+            s.parse().map(Operand::FloatRegister)
+        } else if s.starts_with('p') {
+            s.parse().map(Operand::PredicateRegister)
+        } else if s.starts_with('b') {
+            s.parse().map(Operand::BranchRegister)
+        } else {
+            Err(ParseError::Unrecognized)
+        }
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Opcode, ParseError> {
+        Opcode::from_name(s).ok_or(ParseError::Unrecognized)
+    }
+}
@@ -0,0 +1,273 @@
+//! recognizing procedure linkage table entries and import stubs.
+//!
+//! the linker writes these as fixed bundle sequences with a few immediates filled in, from
+//! `elfxx-ia64.c` in binutils; OpenVMS images use the same full entry as ELF. a stub is
+//! recognized by decoding its bundles, copying the template's immediates over the candidate's,
+//! and comparing bundles; the candidate's immediates say which slot or relocation the stub is
+//! for. HP-UX import stubs are the full entry with a plain `ld8` of the function's entry point.
+//!
+//! `instruction` and `bundle` display code with branches to named stubs written as the stub's
+//! label, `br.call.sptk.many b0=printf@plt`.
+
+use core::fmt;
+
+use crate::{Bundles, InstDecoder, Instruction, InstructionBundle, Operand, SlotAddress};
+use crate::debug::{self, BranchState, SuccessorKind};
+use crate::image::Image;
+
+use yaxpeax_arch::{Decoder, U8Reader};
+
+/// what a stub does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StubKind {
+    /// the PLT header, which calls the dynamic linker to bind a lazy entry.
+    Header,
+    /// a lazy-binding entry, which passes `index`, the index of its `R_IA64_IPLTLSB` relocation,
+    /// to the PLT header at `header`.
+    Lazy { index: u64, header: SlotAddress },
+    /// a full entry, which calls the function whose descriptor is at `descriptor`, a linkage
+    /// table slot addressed from `gp`.
+    Full { descriptor: u64 },
+    /// an HP-UX import stub, which calls the function whose descriptor is at `descriptor`, a
+    /// linkage table slot addressed from `gp`.
+    Import { descriptor: u64 },
+}
+
+/// a recognized stub.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stub {
+    /// the first bundle of the stub.
+    pub address: u64,
+    /// the stub's length in bytes.
+    pub size: u64,
+    pub kind: StubKind,
+    /// the function a full entry or import stub calls: the symbol named by the relocation of its
+    /// descriptor, or failing that the symbol at the descriptor.
+    pub name: Option<String>,
+}
+
+impl Stub {
+    /// `printf@plt`, for a full entry or import stub with a name.
+    pub fn label(&self) -> Option<String> {
+        self.name.as_ref().map(|name| format!("{}@plt", name))
+    }
+}
+
+/// a stub's bundles, with the operands that vary between stubs.
+struct Template {
+    bundles: &'static [[u8; 16]],
+    /// the `(bundle, slot, operand)` of each varying operand.
+    variable: &'static [(usize, usize, usize)],
+}
+
+// [MMI] mov r2=r14;; addl r14=0x0,r2; nop.i 0x0;;
+// [MMI] ld8 r16=[r14],0x8;; ld8 r17=[r14],0x8; nop.i 0x0;;
+// [MIB] ld8 r1=[r14]; mov b6=r17; br.few b6;;
+const HEADER: Template = Template {
+    bundles: &[
+        [0x0b, 0x10, 0x00, 0x1c, 0x00, 0x21, 0xe0, 0x00, 0x08, 0x00, 0x48, 0x00, 0x00, 0x00, 0x04, 0x00],
+        [0x0b, 0x80, 0x20, 0x1c, 0x18, 0x14, 0x10, 0x41, 0x38, 0x30, 0x28, 0x00, 0x00, 0x00, 0x04, 0x00],
+        [0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x60, 0x88, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00],
+    ],
+    variable: &[(0, 1, 1)],
+};
+
+// [MIB] mov r15=0x0; nop.i 0x0; br.few $+0x0;;
+const LAZY: Template = Template {
+    bundles: &[
+        [0x11, 0x78, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40],
+    ],
+    variable: &[(0, 0, 1), (0, 2, 0)],
+};
+
+// [MMI] addl r15=0x0,r1;; ld8.acq r16=[r15],0x8; mov r14=r1;;
+// [MIB] ld8 r1=[r15]; mov b6=r16; br.few b6;;
+const FULL: Template = Template {
+    bundles: &[
+        [0x0b, 0x78, 0x00, 0x02, 0x00, 0x24, 0x00, 0x41, 0x3c, 0x70, 0x29, 0xc0, 0x01, 0x08, 0x00, 0x84],
+        [0x11, 0x08, 0x00, 0x1e, 0x18, 0x10, 0x60, 0x80, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00],
+    ],
+    variable: &[(0, 0, 1)],
+};
+
+// [MMI] addl r15=0x0,r1;; ld8 r16=[r15],0x8; mov r14=r1;;
+// [MIB] ld8 r1=[r15]; mov b6=r16; br.few b6;;
+const IMPORT: Template = Template {
+    bundles: &[
+        [0x0b, 0x78, 0x00, 0x02, 0x00, 0x24, 0x00, 0x41, 0x3c, 0x30, 0x28, 0xc0, 0x01, 0x08, 0x00, 0x84],
+        [0x11, 0x08, 0x00, 0x1e, 0x18, 0x10, 0x60, 0x80, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00],
+    ],
+    variable: &[(0, 0, 1)],
+};
+
+impl Template {
+    fn decode(&'static self) -> Decoded {
+        let decoder = InstDecoder::default();
+        let bundles = self.bundles.iter()
+            .map(|bytes| decoder.decode(&mut U8Reader::new(&bytes[..])).expect("template decodes"))
+            .collect();
+        Decoded { template: self, bundles }
+    }
+}
+
+/// a template with its bundles decoded.
+struct Decoded {
+    template: &'static Template,
+    bundles: Vec<InstructionBundle>,
+}
+
+impl Decoded {
+    /// if `candidate` starts with this template's bundles, the varying operands.
+    fn matches(&self, candidate: &[InstructionBundle]) -> Option<Vec<Operand>> {
+        if candidate.len() < self.bundles.len() || candidate[0].bundle_tag != self.bundles[0].bundle_tag {
+            return None;
+        }
+        let mut candidate = candidate[..self.bundles.len()].to_vec();
+        let mut operands = Vec::new();
+        for (bundle, slot, operand) in self.template.variable.iter().cloned() {
+            let inst = &mut candidate[bundle].instructions[slot];
+            operands.push(inst.operands[operand]);
+            inst.operands[operand] = self.bundles[bundle].instructions[slot].operands[operand];
+        }
+        if candidate == self.bundles {
+            Some(operands)
+        } else {
+            None
+        }
+    }
+
+    /// the stub's length in bytes.
+    fn size(&self) -> u64 {
+        self.bundles.len() as u64 * 16
+    }
+}
+
+/// every template, decoded once for a search.
+struct Templates {
+    header: Decoded,
+    lazy: Decoded,
+    full: Decoded,
+    import: Decoded,
+}
+
+impl Templates {
+    fn new() -> Templates {
+        Templates { header: HEADER.decode(), lazy: LAZY.decode(), full: FULL.decode(), import: IMPORT.decode() }
+    }
+
+    fn recognize<I: Image + ?Sized>(&self, bundles: &[InstructionBundle], address: u64, gp: u64, image: &I) -> Option<Stub> {
+        if self.header.matches(bundles).is_some() {
+            return Some(Stub { address, size: self.header.size(), kind: StubKind::Header, name: None });
+        }
+        if let Some(operands) = self.lazy.matches(bundles) {
+            let header = SlotAddress::new(address.wrapping_add(imm(&operands[1])), 0)?;
+            let kind = StubKind::Lazy { index: imm(&operands[0]), header };
+            return Some(Stub { address, size: self.lazy.size(), kind, name: None });
+        }
+        for (template, full) in [(&self.full, true), (&self.import, false)].iter() {
+            if let Some(operands) = template.matches(bundles) {
+                let descriptor = gp.wrapping_add(imm(&operands[0]));
+                let name = image.relocation(descriptor).or_else(|| image.symbol(descriptor)).map(|name| name.to_string());
+                let kind = if *full { StubKind::Full { descriptor } } else { StubKind::Import { descriptor } };
+                return Some(Stub { address, size: template.size(), kind, name });
+            }
+        }
+        None
+    }
+}
+
+fn imm(op: &Operand) -> u64 {
+    match op {
+        Operand::ImmI64(imm) => *imm as u64,
+        Operand::ImmU64(imm) => *imm,
+        _ => 0,
+    }
+}
+
+/// the stub starting at `bundles[0]`, which is at `address`, if it is one. `gp` is the global
+/// pointer of the module the stub is in.
+pub fn recognize<I: Image + ?Sized>(bundles: &[InstructionBundle], address: u64, gp: u64, image: &I) -> Option<Stub> {
+    Templates::new().recognize(bundles, address, gp, image)
+}
+
+/// every stub in `bundles`, in address order.
+pub fn find_stubs<I: Image + ?Sized>(bundles: &Bundles, gp: u64, image: &I) -> Vec<Stub> {
+    let templates = Templates::new();
+    let mut stubs = Vec::new();
+    let mut i = 0;
+    while i < bundles.bundles().len() {
+        let address = bundles.base().wrapping_add(i as u64 * 16);
+        match templates.recognize(&bundles.bundles()[i..], address, gp, image) {
+            Some(stub) => {
+                i += (stub.size / 16) as usize;
+                stubs.push(stub);
+            }
+            None => {
+                i += 1;
+            }
+        }
+    }
+    stubs
+}
+
+/// the label of the stub in `stubs`, as from `find_stubs`, that the branch at `address` in
+/// `bundle` goes to, if it has one.
+fn target_label(stubs: &[Stub], bundle: &InstructionBundle, address: SlotAddress) -> Option<String> {
+    let target = debug::successors(bundle, address, &BranchState::default()).into_iter()
+        .filter(|successor| successor.kind == SuccessorKind::Call || successor.kind == SuccessorKind::Branch)
+        .find_map(|successor| successor.target)?;
+    if target.slot() != 0 {
+        return None;
+    }
+    let i = stubs.binary_search_by_key(&target.bundle(), |stub| stub.address).ok()?;
+    stubs[i].label()
+}
+
+/// `inst`, at `address` in `bundle`, displayed with an ip-relative branch to a named stub in
+/// `stubs` written as the stub's label.
+pub fn instruction<'a>(stubs: &'a [Stub], bundle: &'a InstructionBundle, address: SlotAddress, inst: &'a Instruction) -> Labelled<'a> {
+    let label = if inst.opcode().is_branch() { target_label(stubs, bundle, address) } else { None };
+    Labelled { inst, label }
+}
+
+/// `bundle`, at `address`, displayed with ip-relative branches to named stubs in `stubs` written
+/// as the stub's label.
+pub fn bundle<'a>(stubs: &'a [Stub], bundle: &'a InstructionBundle, address: u64) -> LabelledBundle<'a> {
+    LabelledBundle { stubs, bundle, address: address & !0xf }
+}
+
+/// an instruction displayed with the label of the stub it branches to, if any.
+pub struct Labelled<'a> {
+    inst: &'a Instruction,
+    label: Option<String>,
+}
+
+impl<'a> fmt::Display for Labelled<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = self.inst.to_string();
+        // the target is written `$+0x40` or `$-0x40`, the last operand.
+        match (&self.label, text.find('$')) {
+            (Some(label), Some(start)) => {
+                let end = text[start..].find(&[',', ' ', ';'][..]).map(|end| start + end).unwrap_or(text.len());
+                write!(f, "{}{}{}", &text[..start], label, &text[end..])
+            }
+            _ => f.write_str(&text),
+        }
+    }
+}
+
+/// a bundle displayed with branches to named stubs written as the stub's label.
+pub struct LabelledBundle<'a> {
+    stubs: &'a [Stub],
+    bundle: &'a InstructionBundle,
+    address: u64,
+}
+
+impl<'a> fmt::Display for LabelledBundle<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.bundle.fmt_with(f, |f, i, inst| {
+            let address = SlotAddress::new(self.address, i as u8).expect("slot is in range");
+            write!(f, "{}", instruction(self.stubs, self.bundle, address, inst))
+        })
+    }
+}
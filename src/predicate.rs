@@ -0,0 +1,442 @@
+//! what compare instructions do to their target predicates.
+//!
+//! every instruction that writes a pair of predicates - `cmp`, `cmp4`, `tbit`, `tnat`, `tf`,
+//! `fcmp`, and `fclass` - does so according to its comparison type, from the manual's table
+//! `4-11 Comparison Types`. the type decides what `p1` and `p2` receive when the qualifying
+//! predicate is true, when it is false, and when a source register is NaT.
+//!
+//! the assembler's `andcm`, `orcm`, and `and.orcm` types are pseudo-ops for `and`, `or`, and
+//! `or.andcm` with the relation complemented or the targets swapped, and decode as those.
+//! similarly `cmp.ne` is `cmp.eq` with `p1` and `p2` swapped, and so on; the "result" below is
+//! the result of the relation as decoded.
+
+use crate::{Instruction, Opcode, Operand, PredicateRegister};
+
+/// a comparison type, as in the manual's table `4-11`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompareType {
+    /// no completer: `p1` gets the result and `p2` its complement.
+    Normal,
+    /// `.unc`: like `Normal`, but both targets are cleared if the qualifying predicate is false.
+    Unc,
+    /// `.and`: both targets are cleared if the result is false.
+    And,
+    /// `.or`: both targets are set if the result is true.
+    Or,
+    /// `.or.andcm`: `p1` is set and `p2` cleared if the result is true.
+    OrAndcm,
+}
+
+/// the new value of one target predicate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PredicateEffect {
+    /// not written.
+    Unchanged,
+    /// written with 0.
+    Clear,
+    /// written with the result of the comparison.
+    Result,
+    /// written with the complement of the result.
+    Complement,
+    /// written with 1 if the result is true, and otherwise not written.
+    SetIfResult,
+    /// written with 0 if the result is false, and otherwise not written.
+    ClearIfNotResult,
+    /// written with 0 if the result is true, and otherwise not written.
+    ClearIfResult,
+}
+
+impl PredicateEffect {
+    /// the value after applying this effect to a predicate holding `old`, for a comparison whose
+    /// result is `result`.
+    pub fn apply(&self, old: bool, result: bool) -> bool {
+        match self {
+            PredicateEffect::Unchanged => old,
+            PredicateEffect::Clear => false,
+            PredicateEffect::Result => result,
+            PredicateEffect::Complement => !result,
+            PredicateEffect::SetIfResult => old || result,
+            PredicateEffect::ClearIfNotResult => old && result,
+            PredicateEffect::ClearIfResult => old && !result,
+        }
+    }
+
+    /// does this effect ever write the predicate?
+    pub fn writes(&self) -> bool {
+        *self != PredicateEffect::Unchanged
+    }
+}
+
+/// how a compare's two targets relate after it executes with a true qualifying predicate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TargetRelation {
+    /// `p2` is the complement of `p1`, whatever they held before.
+    Complementary,
+    /// both targets get the same update, so targets that were equal before are equal after.
+    SameUpdate,
+    /// the targets get opposite updates, so targets that were complementary before are
+    /// complementary after.
+    OppositeUpdate,
+}
+
+/// the effect of a compare on its targets, `p1` and `p2`, in each case.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CompareSemantics {
+    pub compare_type: CompareType,
+    /// `(p1, p2)` when the qualifying predicate is true and no source is NaT.
+    pub when_true: (PredicateEffect, PredicateEffect),
+    /// `(p1, p2)` when the qualifying predicate is false.
+    pub when_false: (PredicateEffect, PredicateEffect),
+    /// `(p1, p2)` when the qualifying predicate is true and a source is NaT (NaTVal, for
+    /// `fcmp`). `None` for `tnat`, `tf`, and `fclass`, where a NaT source is not special.
+    pub when_nat: Option<(PredicateEffect, PredicateEffect)>,
+    pub relation: TargetRelation,
+}
+
+impl CompareType {
+    /// the semantics of this comparison type. `nat_sensitive` is whether a NaT source forces a
+    /// particular outcome, as it does for `cmp`, `tbit`, and `fcmp`.
+    pub fn semantics(&self, nat_sensitive: bool) -> CompareSemantics {
+        use PredicateEffect::*;
+        let (when_true, when_false, when_nat, relation) = match self {
+            CompareType::Normal => ((Result, Complement), (Unchanged, Unchanged), (Clear, Clear), TargetRelation::Complementary),
+            CompareType::Unc => ((Result, Complement), (Clear, Clear), (Clear, Clear), TargetRelation::Complementary),
+            CompareType::And => ((ClearIfNotResult, ClearIfNotResult), (Unchanged, Unchanged), (Clear, Clear), TargetRelation::SameUpdate),
+            CompareType::Or => ((SetIfResult, SetIfResult), (Unchanged, Unchanged), (Unchanged, Unchanged), TargetRelation::SameUpdate),
+            CompareType::OrAndcm => ((SetIfResult, ClearIfResult), (Unchanged, Unchanged), (Unchanged, Unchanged), TargetRelation::OppositeUpdate),
+        };
+        CompareSemantics {
+            compare_type: *self,
+            when_true,
+            when_false,
+            when_nat: if nat_sensitive { Some(when_nat) } else { None },
+            relation,
+        }
+    }
+}
+
+impl Opcode {
+    /// the comparison type of this opcode, if it writes a predicate pair.
+    pub fn compare_type(&self) -> Option<CompareType> {
+        use Opcode::*;
+        Some(match self {
+            Cmp_eq | Cmp_lt | Cmp_ltu | Cmp4_eq | Cmp4_lt | Cmp4_ltu |
+            Tbit_z | Tnat_z | Tf_z |
+            Fcmp_eq | Fcmp_lt | Fcmp_le | Fcmp_unord | Fclass_m => CompareType::Normal,
+
+            Cmp_eq_unc | Cmp_lt_unc | Cmp_ltu_unc | Cmp4_eq_unc | Cmp4_lt_unc | Cmp4_ltu_unc |
+            Tbit_z_unc | Tnat_z_unc | Tf_z_nc |
+            Fcmp_eq_unc | Fcmp_lt_unc | Fcmp_le_unc | Fcmp_unord_unc | Fclass_m_unc => CompareType::Unc,
+
+            Cmp_eq_and | Cmp_ne_and | Cmp_lt_and | Cmp_le_and | Cmp_gt_and | Cmp_ge_and |
+            Cmp4_eq_and | Cmp4_ne_and | Cmp4_lt_and | Cmp4_le_and | Cmp4_gt_and | Cmp4_ge_and |
+            Tbit_z_and | Tbit_nz_and | Tnat_z_and | Tnat_nz_and | Tf_z_and | Tf_nz_and => CompareType::And,
+
+            Cmp_eq_or | Cmp_ne_or | Cmp_lt_or | Cmp_le_or | Cmp_gt_or | Cmp_ge_or |
+            Cmp4_eq_or | Cmp4_ne_or | Cmp4_lt_or | Cmp4_le_or | Cmp4_gt_or | Cmp4_ge_or |
+            Tbit_z_or | Tbit_nz_or | Tnat_z_or | Tnat_nz_or | Tf_z_or | Tf_nz_or => CompareType::Or,
+
+            Cmp_eq_or_andcm | Cmp_ne_or_andcm | Cmp_lt_or_andcm | Cmp_le_or_andcm |
+            Cmp_gt_or_andcm | Cmp_ge_or_andcm |
+            Cmp4_eq_or_andcm | Cmp4_ne_or_andcm | Cmp4_lt_or_andcm | Cmp4_le_or_andcm |
+            Cmp4_gt_or_andcm | Cmp4_ge_or_andcm |
+            Tbit_z_or_andcm | Tbit_nz_or_andcm | Tnat_z_or_andcm | Tnat_nz_or_andcm |
+            Tf_z_or_andcm | Tf_nz_or_andcm => CompareType::OrAndcm,
+
+            _ => return None,
+        })
+    }
+
+    /// the effect of this opcode on its target predicates, if it writes a predicate pair.
+    pub fn compare_semantics(&self) -> Option<CompareSemantics> {
+        use Opcode::*;
+        let nat_sensitive = !matches!(self,
+            Tnat_z | Tnat_z_unc | Tnat_z_and | Tnat_nz_and | Tnat_z_or | Tnat_nz_or |
+            Tnat_z_or_andcm | Tnat_nz_or_andcm |
+            Tf_z | Tf_z_nc | Tf_z_and | Tf_nz_and | Tf_z_or | Tf_nz_or | Tf_z_or_andcm | Tf_nz_or_andcm |
+            Fclass_m | Fclass_m_unc);
+        self.compare_type().map(|ty| ty.semantics(nat_sensitive))
+    }
+}
+
+/// the predicate writes of one compare instruction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PredicateWrite {
+    /// the qualifying predicate.
+    pub qp: PredicateRegister,
+    pub p1: PredicateRegister,
+    pub p2: PredicateRegister,
+    pub semantics: CompareSemantics,
+}
+
+/// the predicate writes of `inst`, if it is a compare.
+pub fn predicate_write(inst: &Instruction) -> Option<PredicateWrite> {
+    let semantics = inst.opcode().compare_semantics()?;
+    match inst.operands() {
+        [Operand::PredicateRegister(p1), Operand::PredicateRegister(p2), ..] => Some(PredicateWrite {
+            qp: PredicateRegister(inst.predicate()),
+            p1: *p1,
+            p2: *p2,
+            semantics,
+        }),
+        _ => None,
+    }
+}
+
+/// one known relation between two predicates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Fact {
+    /// exactly one of the two is true.
+    Complementary(u8, u8),
+    /// at most one of the two is true.
+    Disjoint(u8, u8),
+    /// if the first is true, so is the second.
+    Implies(u8, u8),
+}
+
+impl Fact {
+    fn mentions(&self, p: u8) -> bool {
+        match *self {
+            Fact::Complementary(a, b) | Fact::Disjoint(a, b) | Fact::Implies(a, b) => a == p || b == p,
+        }
+    }
+}
+
+/// what is known about the relations between predicate registers at one point in a program.
+///
+/// facts come from compares: a compare with no qualifying predicate leaves its targets
+/// complementary, an `.unc` compare leaves them disjoint and each implying the qualifying
+/// predicate, and so on. writing a predicate by any other means forgets what was known about it.
+/// `p0` is always true, so every predicate implies it. like a compiler, this assumes compare
+/// sources are not NaT; a NaT source clears both targets of a normal compare.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PredicateRelations {
+    facts: Vec<Fact>,
+}
+
+impl PredicateRelations {
+    /// nothing known.
+    pub fn new() -> PredicateRelations {
+        PredicateRelations::default()
+    }
+
+    fn add(&mut self, fact: Fact) {
+        if !self.facts.contains(&fact) {
+            self.facts.push(fact);
+        }
+    }
+
+    /// forget everything about `p`.
+    fn forget(&mut self, p: u8) {
+        self.facts.retain(|fact| !fact.mentions(p));
+    }
+
+    /// `p` may be cleared but never set, so anything implied by `p` being true still holds.
+    fn only_clears(&mut self, p: u8) {
+        for fact in self.facts.iter_mut() {
+            match *fact {
+                Fact::Complementary(a, b) if a == p || b == p => { *fact = Fact::Disjoint(a, b); }
+                _ => {}
+            }
+        }
+        self.facts.retain(|fact| !matches!(*fact, Fact::Implies(_, b) if b == p));
+    }
+
+    /// `p` may be set but never cleared, so anything that implied `p` still does.
+    fn only_sets(&mut self, p: u8) {
+        self.facts.retain(|fact| match *fact {
+            Fact::Implies(a, _) => a != p,
+            _ => !fact.mentions(p),
+        });
+    }
+
+    /// update the known relations for the execution of `inst`.
+    pub fn update(&mut self, inst: &Instruction) {
+        if let Some(write) = predicate_write(inst) {
+            self.update_compare(&write);
+            return;
+        }
+
+        for location in crate::registers::accesses(inst).writes.iter() {
+            match location {
+                crate::registers::Location::PredicateRegister(p) => self.forget(p.0),
+                crate::registers::Location::PR => self.facts.clear(),
+                _ => {}
+            }
+        }
+        match inst.opcode() {
+            // the modulo-scheduled loop branches rotate `p16` through `p63`.
+            Opcode::Br_ctop | Opcode::Br_cexit | Opcode::Br_wtop | Opcode::Br_wexit => {
+                for p in 16..64 {
+                    self.forget(p);
+                }
+            }
+            // `p6` through `p15` are scratch across calls.
+            Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+                for p in 6..16 {
+                    self.forget(p);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_compare(&mut self, write: &PredicateWrite) {
+        let (qp, p1, p2) = (write.qp.0, write.p1.0, write.p2.0);
+        let were_complementary = self.complementary(write.p1, write.p2);
+        match write.semantics.compare_type {
+            CompareType::Normal => {
+                self.forget(p1);
+                self.forget(p2);
+                // with a false qualifying predicate the targets are unchanged, so they are
+                // complementary afterward only if they were before.
+                if qp == 0 || were_complementary {
+                    self.add(Fact::Complementary(p1, p2));
+                }
+            }
+            CompareType::Unc => {
+                self.forget(p1);
+                self.forget(p2);
+                if qp == 0 {
+                    self.add(Fact::Complementary(p1, p2));
+                } else {
+                    self.add(Fact::Disjoint(p1, p2));
+                    if qp != p1 && qp != p2 {
+                        self.add(Fact::Implies(p1, qp));
+                        self.add(Fact::Implies(p2, qp));
+                    }
+                }
+            }
+            CompareType::And => {
+                self.only_clears(p1);
+                self.only_clears(p2);
+            }
+            CompareType::Or => {
+                self.only_sets(p1);
+                self.only_sets(p2);
+            }
+            CompareType::OrAndcm => {
+                self.only_sets(p1);
+                self.only_clears(p2);
+                if were_complementary {
+                    self.add(Fact::Complementary(p1, p2));
+                }
+            }
+        }
+        // writes to `p0` are discarded, and it stays true.
+        self.forget(0);
+    }
+
+    /// every predicate `p` is known to imply, `p` and `p0` included.
+    fn implied_by(&self, p: u8) -> Vec<u8> {
+        let mut implied = vec![p, 0];
+        let mut i = 0;
+        while i < implied.len() {
+            let from = implied[i];
+            for fact in self.facts.iter() {
+                if let Fact::Implies(a, b) = *fact {
+                    if a == from && !implied.contains(&b) {
+                        implied.push(b);
+                    }
+                }
+            }
+            i += 1;
+        }
+        implied
+    }
+
+    /// is exactly one of `a` and `b` known to be true?
+    pub fn complementary(&self, a: PredicateRegister, b: PredicateRegister) -> bool {
+        self.facts.iter().any(|fact| {
+            *fact == Fact::Complementary(a.0, b.0) || *fact == Fact::Complementary(b.0, a.0)
+        })
+    }
+
+    /// are `a` and `b` known never to both be true?
+    pub fn disjoint(&self, a: PredicateRegister, b: PredicateRegister) -> bool {
+        let from_a = self.implied_by(a.0);
+        let from_b = self.implied_by(b.0);
+        self.facts.iter().any(|fact| match *fact {
+            Fact::Complementary(x, y) | Fact::Disjoint(x, y) => {
+                (from_a.contains(&x) && from_b.contains(&y)) || (from_a.contains(&y) && from_b.contains(&x))
+            }
+            Fact::Implies(_, _) => false,
+        })
+    }
+
+    /// is `b` known to be true whenever `a` is?
+    pub fn implies(&self, a: PredicateRegister, b: PredicateRegister) -> bool {
+        self.implied_by(a.0).contains(&b.0)
+    }
+}
+
+/// predicate relations at every instruction of a straight-line sequence, from `analyze`.
+#[derive(Debug, Clone)]
+pub struct BlockPredicates {
+    /// relations before each instruction.
+    before: Vec<PredicateRelations>,
+    /// the qualifying predicate of each instruction.
+    qps: Vec<u8>,
+    /// the predicates each instruction may write; `None` for all of them.
+    writes: Vec<Option<Vec<u8>>>,
+}
+
+impl BlockPredicates {
+    /// the relations that hold just before instruction `index` executes.
+    pub fn before(&self, index: usize) -> Option<&PredicateRelations> {
+        self.before.get(index)
+    }
+
+    /// could instructions `i` and `j` both execute, as far as their qualifying predicates go?
+    /// this is `false` only if their predicates are disjoint and neither was rewritten between
+    /// the two instructions, so for two predicated writes of one register it answers whether
+    /// both writes can happen.
+    pub fn may_both_execute(&self, i: usize, j: usize) -> bool {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        if j >= self.qps.len() || i == j {
+            return true;
+        }
+        let (qp_i, qp_j) = (self.qps[i], self.qps[j]);
+        let rewritten = self.writes[i..j].iter().any(|writes| match writes {
+            Some(writes) => writes.contains(&qp_i),
+            None => true,
+        });
+        if rewritten {
+            return true;
+        }
+        !self.before[j].disjoint(PredicateRegister(qp_i), PredicateRegister(qp_j))
+    }
+}
+
+/// track predicate relations through `instructions`, which are executed in order with no
+/// branches in.
+pub fn analyze<'a, I: IntoIterator<Item=&'a Instruction>>(instructions: I) -> BlockPredicates {
+    let mut relations = PredicateRelations::new();
+    let mut block = BlockPredicates { before: Vec::new(), qps: Vec::new(), writes: Vec::new() };
+    for inst in instructions {
+        block.before.push(relations.clone());
+        block.qps.push(inst.predicate());
+        let mut writes = Some(Vec::new());
+        for location in crate::registers::accesses(inst).writes.iter() {
+            match location {
+                crate::registers::Location::PredicateRegister(p) => {
+                    if let Some(writes) = writes.as_mut() {
+                        writes.push(p.0);
+                    }
+                }
+                crate::registers::Location::PR => { writes = None; }
+                _ => {}
+            }
+        }
+        if let Some(writes) = writes.as_mut() {
+            match inst.opcode() {
+                Opcode::Br_ctop | Opcode::Br_cexit | Opcode::Br_wtop | Opcode::Br_wexit => writes.extend(16..64),
+                Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => writes.extend(6..16),
+                _ => {}
+            }
+        }
+        block.writes.push(writes);
+        relations.update(inst);
+    }
+    block
+}
@@ -0,0 +1,178 @@
+//! which registers an instruction reads and writes.
+//!
+//! explicit operands are sorted by `Instruction::last_write_index`, with a few corrections the
+//! operand list alone can't express: the base register of a memory operand is read even when the
+//! memory operand is on the left of the `=`, post-incrementing loads and stores write their base
+//! register, and some instructions touch registers that aren't operands at all (`br.call` writes
+//! `ar.pfs`, `cmpxchg` reads `ar.ccv`, ...).
+//!
+//! registers with fixed values - `r0`, `f0`, `f1`, and `p0` - are never reported: reading them
+//! depends on nothing and writing them does nothing. memory, the floating-point status fields of
+//! `ar.fpsr`, and NaT bits (which travel with their register) are not tracked separately.
+
+use crate::{ApplicationRegister, BranchRegister, ControlRegister, FloatRegister, GPRegister, Instruction, Opcode, Operand, PredicateRegister};
+
+/// a register, or register-like piece of state, that an instruction can read or write.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Location {
+    GPRegister(GPRegister),
+    FloatRegister(FloatRegister),
+    PredicateRegister(PredicateRegister),
+    BranchRegister(BranchRegister),
+    ApplicationRegister(ApplicationRegister),
+    ControlRegister(ControlRegister),
+    /// all 64 predicate registers at once, as `mov pr=r2,mask` writes and `mov r1=pr` reads them.
+    PR,
+    /// the processor status register, including the `psr.l` and `psr.um` views of it.
+    PSR,
+    IP,
+}
+
+impl Location {
+    /// do `self` and `other` name any of the same state? this is only different from `==` for
+    /// `PR`, which overlaps every predicate register.
+    pub fn overlaps(&self, other: &Location) -> bool {
+        match (self, other) {
+            (Location::PR, Location::PredicateRegister(_)) |
+            (Location::PredicateRegister(_), Location::PR) => true,
+            _ => self == other,
+        }
+    }
+}
+
+/// the registers an instruction reads and writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Accesses {
+    /// registers read, including the qualifying predicate.
+    pub reads: Vec<Location>,
+    /// registers written. a predicated instruction only writes these if its predicate is true,
+    /// except for the `.unc` compares, which always write both targets.
+    pub writes: Vec<Location>,
+}
+
+impl Accesses {
+    fn read(&mut self, location: Location) {
+        if !is_constant(&location) && !self.reads.contains(&location) {
+            self.reads.push(location);
+        }
+    }
+
+    fn write(&mut self, location: Location) {
+        if !is_constant(&location) && !self.writes.contains(&location) {
+            self.writes.push(location);
+        }
+    }
+}
+
+fn is_constant(location: &Location) -> bool {
+    matches!(location,
+        Location::GPRegister(GPRegister(0)) |
+        Location::FloatRegister(FloatRegister(0)) | Location::FloatRegister(FloatRegister(1)) |
+        Location::PredicateRegister(PredicateRegister(0)))
+}
+
+pub(crate) fn location(operand: &Operand) -> Option<Location> {
+    match operand {
+        Operand::GPRegister(r) => Some(Location::GPRegister(*r)),
+        Operand::FloatRegister(f) => Some(Location::FloatRegister(*f)),
+        Operand::PredicateRegister(p) => Some(Location::PredicateRegister(*p)),
+        Operand::BranchRegister(b) => Some(Location::BranchRegister(*b)),
+        Operand::ApplicationRegister(ar) => Some(Location::ApplicationRegister(*ar)),
+        Operand::ControlRegister(cr) => Some(Location::ControlRegister(*cr)),
+        Operand::PR => Some(Location::PR),
+        Operand::PSR | Operand::PSR_l | Operand::PSR_um => Some(Location::PSR),
+        Operand::IP => Some(Location::IP),
+        Operand::None | Operand::ImmI64(_) | Operand::ImmU64(_) |
+        Operand::Memory(_) | Operand::Indirection(_, _) => None,
+    }
+}
+
+/// semaphore instructions have a memory operand followed by a value, rather than an increment.
+fn is_semaphore(opcode: Opcode) -> bool {
+    use Opcode::*;
+    matches!(opcode,
+        Cmpxchg1_acq | Cmpxchg2_acq | Cmpxchg4_acq | Cmpxchg8_acq |
+        Cmpxchg1_rel | Cmpxchg2_rel | Cmpxchg4_rel | Cmpxchg8_rel | Cmp8xchg16_acq | Cmp8xchg16_rel |
+        Xchg1 | Xchg2 | Xchg4 | Xchg8 | Fetchadd4_acq | Fetchadd8_acq | Fetchadd4_rel | Fetchadd8_rel)
+}
+
+/// the registers `inst` reads and writes.
+pub fn accesses(inst: &Instruction) -> Accesses {
+    let mut accesses = Accesses::default();
+    accesses.read(Location::PredicateRegister(PredicateRegister(inst.predicate())));
+
+    let operands = inst.operands();
+    let writes = inst.last_write_index().map(|i| i as usize + 1).unwrap_or(0);
+    for (i, operand) in operands.iter().enumerate() {
+        match operand {
+            Operand::Memory(base) | Operand::Indirection(_, base) => {
+                accesses.read(Location::GPRegister(*base));
+                // a load is `r1=[r3],inc`, a store `[r3]=r2,inc`, and `lfetch` is `[r3],inc`. in
+                // each case an operand past the ones the instruction needs is the increment.
+                let needed = if i < writes { i + 2 } else { i + 1 };
+                if let Operand::Memory(_) = operand {
+                    if operands.len() > needed && !is_semaphore(inst.opcode()) {
+                        accesses.write(Location::GPRegister(*base));
+                    }
+                }
+            }
+            // a store's value is right of the `=`, but its address is left of it.
+            _ if i < writes => {
+                if let Some(location) = location(operand) {
+                    accesses.write(location);
+                }
+            }
+            _ => {
+                if let Some(location) = location(operand) {
+                    accesses.read(location);
+                }
+            }
+        }
+    }
+
+    let pfs = Location::ApplicationRegister(ApplicationRegister::PFS);
+    let lc = Location::ApplicationRegister(ApplicationRegister::LC);
+    let ec = Location::ApplicationRegister(ApplicationRegister::EC);
+    match inst.opcode() {
+        Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+            accesses.write(pfs);
+        }
+        Opcode::Br_ret => {
+            accesses.read(pfs);
+        }
+        Opcode::Br_cloop => {
+            accesses.read(lc);
+            accesses.write(lc);
+        }
+        // the modulo-scheduled loop branches also rotate registers and write `p63`.
+        Opcode::Br_ctop | Opcode::Br_cexit => {
+            accesses.read(lc);
+            accesses.read(ec);
+            accesses.write(lc);
+            accesses.write(ec);
+            accesses.write(Location::PredicateRegister(PredicateRegister(63)));
+        }
+        Opcode::Br_wtop | Opcode::Br_wexit => {
+            accesses.read(ec);
+            accesses.write(ec);
+            accesses.write(Location::PredicateRegister(PredicateRegister(63)));
+        }
+        Opcode::Cmpxchg1_acq | Opcode::Cmpxchg2_acq | Opcode::Cmpxchg4_acq | Opcode::Cmpxchg8_acq |
+        Opcode::Cmpxchg1_rel | Opcode::Cmpxchg2_rel | Opcode::Cmpxchg4_rel | Opcode::Cmpxchg8_rel => {
+            accesses.read(Location::ApplicationRegister(ApplicationRegister::CCV));
+        }
+        Opcode::Cmp8xchg16_acq | Opcode::Cmp8xchg16_rel => {
+            accesses.read(Location::ApplicationRegister(ApplicationRegister::CCV));
+            accesses.read(Location::ApplicationRegister(ApplicationRegister::CSD));
+        }
+        Opcode::Ld8_fill => {
+            accesses.read(Location::ApplicationRegister(ApplicationRegister::UNAT));
+        }
+        Opcode::St8_spill => {
+            accesses.write(Location::ApplicationRegister(ApplicationRegister::UNAT));
+        }
+        _ => {}
+    }
+
+    accesses
+}
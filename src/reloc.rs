@@ -0,0 +1,265 @@
+//! showing relocations in disassembly of relocatable objects.
+//!
+//! in a `.o` file, an immediate that refers to a symbol is left as zero and described by a
+//! relocation instead: `addl r14=0x0,r1` with an `R_IA64_LTOFF22` relocation against `var` is
+//! `addl r14=@ltoff(var),r1` in the source. relocations against instructions use the usual
+//! slot-in-low-bits form for `r_offset`, so an `ld8` in slot 2 of the bundle at `0x40` is
+//! relocated at `0x42`. `Relocations` collects them, and displays instructions and bundles with
+//! each relocated immediate shown as the expression the assembler was given.
+//!
+//! only relocations of instruction immediates are displayed. data relocations (`R_IA64_DIR64LSB`
+//! and the like) and `R_IA64_LDXMOV`, which marks an `ld8` without naming an operand, are not
+//! recognized by `RelocationType::from_raw`.
+
+use core::fmt;
+use std::collections::BTreeMap;
+
+use crate::{Instruction, InstructionBundle, Operand, SlotAddress};
+use crate::address::is_long_bundle;
+
+/// the type of a relocation against an instruction immediate, from the ia64 ELF ABI.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RelocationType {
+    IMM14,
+    IMM22,
+    IMM64,
+    GPREL22,
+    GPREL64I,
+    LTOFF22,
+    LTOFF64I,
+    LTOFF22X,
+    PLTOFF22,
+    PLTOFF64I,
+    FPTR64I,
+    PCREL60B,
+    PCREL21B,
+    PCREL21M,
+    PCREL21F,
+    PCREL21BI,
+    PCREL22,
+    PCREL64I,
+    LTOFF_FPTR22,
+    LTOFF_FPTR64I,
+    TPREL14,
+    TPREL22,
+    TPREL64I,
+    LTOFF_TPREL22,
+    LTOFF_DTPMOD22,
+    DTPREL14,
+    DTPREL22,
+    DTPREL64I,
+    LTOFF_DTPREL22,
+}
+
+impl RelocationType {
+    /// the relocation type numbered `r_type`, if it relocates an instruction immediate.
+    pub fn from_raw(r_type: u32) -> Option<RelocationType> {
+        use RelocationType::*;
+        Some(match r_type {
+            0x21 => IMM14,
+            0x22 => IMM22,
+            0x23 => IMM64,
+            0x2a => GPREL22,
+            0x2b => GPREL64I,
+            0x32 => LTOFF22,
+            0x33 => LTOFF64I,
+            0x86 => LTOFF22X,
+            0x3a => PLTOFF22,
+            0x3b => PLTOFF64I,
+            0x43 => FPTR64I,
+            0x48 => PCREL60B,
+            0x49 => PCREL21B,
+            0x4a => PCREL21M,
+            0x4b => PCREL21F,
+            0x79 => PCREL21BI,
+            0x7a => PCREL22,
+            0x7b => PCREL64I,
+            0x52 => LTOFF_FPTR22,
+            0x53 => LTOFF_FPTR64I,
+            0x91 => TPREL14,
+            0x92 => TPREL22,
+            0x93 => TPREL64I,
+            0x9a => LTOFF_TPREL22,
+            0xaa => LTOFF_DTPMOD22,
+            0xb1 => DTPREL14,
+            0xb2 => DTPREL22,
+            0xb3 => DTPREL64I,
+            0xba => LTOFF_DTPREL22,
+            _ => { return None; }
+        })
+    }
+
+    /// the assembler's operators for this relocation, outermost first: `["ltoff", "fptr"]` for
+    /// `@ltoff(@fptr(sym))`. empty for plain symbols, including branch targets.
+    pub fn operators(&self) -> &'static [&'static str] {
+        use RelocationType::*;
+        match self {
+            IMM14 | IMM22 | IMM64 |
+            PCREL60B | PCREL21B | PCREL21M | PCREL21F | PCREL21BI => &[],
+            GPREL22 | GPREL64I => &["gprel"],
+            LTOFF22 | LTOFF64I => &["ltoff"],
+            LTOFF22X => &["ltoffx"],
+            PLTOFF22 | PLTOFF64I => &["pltoff"],
+            FPTR64I => &["fptr"],
+            PCREL22 | PCREL64I => &["pcrel"],
+            LTOFF_FPTR22 | LTOFF_FPTR64I => &["ltoff", "fptr"],
+            TPREL14 | TPREL22 | TPREL64I => &["tprel"],
+            LTOFF_TPREL22 => &["ltoff", "tprel"],
+            LTOFF_DTPMOD22 => &["ltoff", "dtpmod"],
+            DTPREL14 | DTPREL22 | DTPREL64I => &["dtprel"],
+            LTOFF_DTPREL22 => &["ltoff", "dtprel"],
+        }
+    }
+}
+
+/// a relocation against an instruction immediate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Relocation {
+    pub kind: RelocationType,
+    pub symbol: String,
+    pub addend: i64,
+}
+
+impl Relocation {
+    /// the relocation described by an `Elf64_Rela`'s `r_info` and `r_addend`, against `symbol`,
+    /// if its type relocates an instruction immediate.
+    pub fn from_rela(r_info: u64, r_addend: i64, symbol: &str) -> Option<Relocation> {
+        Some(Relocation {
+            kind: RelocationType::from_raw(r_info as u32)?,
+            symbol: symbol.to_string(),
+            addend: r_addend,
+        })
+    }
+}
+
+/// `@ltoff(var+0x8)`: the expression the relocated immediate was written as.
+impl fmt::Display for Relocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for op in self.kind.operators() {
+            write!(f, "@{}(", op)?;
+        }
+        f.write_str(&self.symbol)?;
+        if self.addend > 0 {
+            write!(f, "+{:#x}", self.addend)?;
+        } else if self.addend < 0 {
+            write!(f, "-{:#x}", self.addend.wrapping_neg() as u64)?;
+        }
+        for _ in self.kind.operators() {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// the relocations against instructions in an object, by the slot they apply to.
+#[derive(Debug, Clone, Default)]
+pub struct Relocations {
+    entries: BTreeMap<SlotAddress, Relocation>,
+}
+
+impl Relocations {
+    pub fn new() -> Relocations {
+        Relocations::default()
+    }
+
+    /// record `relocation` at `r_offset`, a slot address in slot-in-low-bits form. `false`, and
+    /// nothing is recorded, if `r_offset` does not name a slot.
+    pub fn add(&mut self, r_offset: u64, relocation: Relocation) -> bool {
+        match SlotAddress::from_linear(r_offset) {
+            Some(address) => {
+                self.entries.insert(address, relocation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// the relocation against the instruction at `address`, in `bundle`. a relocation of the
+    /// `LX` instruction of an `MLX` bundle may be recorded at slot 1 or slot 2.
+    pub fn get(&self, bundle: &InstructionBundle, address: SlotAddress) -> Option<&Relocation> {
+        let address = address.canonical(bundle.bundle_tag());
+        self.entries.get(&address).or_else(|| {
+            if address.slot() == 1 && is_long_bundle(bundle.bundle_tag()) {
+                self.entries.get(&address.next_slot())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// `inst`, at `address` in `bundle`, displayed with its relocation.
+    pub fn instruction<'a>(&'a self, bundle: &'a InstructionBundle, address: SlotAddress, inst: &'a Instruction) -> Relocated<'a> {
+        Relocated { inst, relocation: self.get(bundle, address) }
+    }
+
+    /// `bundle`, at `address`, displayed with the relocations of its instructions.
+    pub fn bundle<'a>(&'a self, bundle: &'a InstructionBundle, address: u64) -> RelocatedBundle<'a> {
+        RelocatedBundle { relocations: self, bundle, address: address & !0xf }
+    }
+}
+
+/// an instruction displayed with the relocation against it, if any.
+pub struct Relocated<'a> {
+    inst: &'a Instruction,
+    relocation: Option<&'a Relocation>,
+}
+
+impl<'a> fmt::Display for Relocated<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.relocation {
+            Some(relocation) => f.write_str(&substitute(self.inst, relocation)),
+            None => write!(f, "{}", self.inst),
+        }
+    }
+}
+
+/// a bundle displayed with the relocations against its instructions.
+pub struct RelocatedBundle<'a> {
+    relocations: &'a Relocations,
+    bundle: &'a InstructionBundle,
+    address: u64,
+}
+
+impl<'a> fmt::Display for RelocatedBundle<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.bundle.fmt_with(f, |f, i, inst| {
+            let address = SlotAddress::new(self.address, i as u8).expect("slot is in range");
+            write!(f, "{}", self.relocations.instruction(self.bundle, address, inst))
+        })
+    }
+}
+
+/// the text of `inst` with its first immediate operand, the only one any instruction relocation
+/// applies to, replaced by `relocation`.
+///
+/// `Display for Instruction` lays out operands differently for many opcodes, so this edits its
+/// output rather than repeating it: the immediate is the first operand token that reads as the
+/// immediate's value, or as an ip-relative `$+` target. registers never read as numbers, so
+/// this can only mistake one immediate for another, and instructions with a relocatable
+/// immediate have no other immediate before it.
+fn substitute(inst: &Instruction, relocation: &Relocation) -> String {
+    let text = inst.to_string();
+    let imm = match inst.operands().iter().find(|op| matches!(op, Operand::ImmI64(_) | Operand::ImmU64(_))) {
+        Some(imm) => imm.to_string(),
+        None => { return text; }
+    };
+    // skip the qualifying predicate and the mnemonic.
+    let start = if inst.predicate() != 0 { text.find(") ").map(|i| i + 2).unwrap_or(0) } else { 0 };
+    let operands = match text[start..].find(' ') {
+        Some(i) => start + i + 1,
+        None => { return text; }
+    };
+    let candidates = [imm.clone(), format!("$+{}", imm), format!("${}", imm)];
+    let mut token_start = operands;
+    for (i, c) in text[operands..].char_indices().map(|(i, c)| (operands + i, c)).chain(Some((text.len(), ','))) {
+        if c != ',' && c != '=' {
+            continue;
+        }
+        if candidates.iter().any(|candidate| candidate == &text[token_start..i]) {
+            return format!("{}{}{}", &text[..token_start], relocation, &text[i..]);
+        }
+        token_start = i + 1;
+    }
+    text
+}
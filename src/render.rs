@@ -0,0 +1,195 @@
+//! drawing a `Cfg` as Graphviz DOT or as a static HTML page.
+//!
+//! blocks are laid out bundle by bundle: a header with the bundle's address and template, then
+//! each of the block's instructions in that bundle with its slot number, as `Display` writes
+//! it, and `;;` where the template has a stop. edges out of a block are labelled with the
+//! predicate guarding them, `p6` for a branch taken under `p6` and `!p6` for falling through
+//! past it.
+//!
+//! the HTML page needs nothing but a browser: styles are inline, there are no scripts, and every
+//! branch target and edge in the range links to the instruction it goes to.
+
+use core::fmt::Write;
+
+use crate::{BUNDLE_TAGS, Bundles, Instruction, SlotAddress};
+use crate::cfg::{Block, Cfg, Edge, EdgeKind};
+use crate::debug::{self, BranchState};
+
+/// one line of a block, as drawn.
+enum Line<'a> {
+    /// the start of a bundle: its address and template, `MIB`.
+    Bundle { address: u64, template: String },
+    Instruction { address: SlotAddress, inst: &'a Instruction, stop: bool },
+}
+
+fn lines<'a>(bundles: &'a Bundles, block: &Block) -> Vec<Line<'a>> {
+    let mut lines = Vec::new();
+    let mut current = None;
+    for (address, inst) in block.instructions(bundles) {
+        let bundle = bundles.bundle_at(address.bundle()).expect("block is in range");
+        let (types, stops) = BUNDLE_TAGS[bundle.bundle_tag() as usize].expect("decoded bundles have valid templates");
+        if current != Some(address.bundle()) {
+            current = Some(address.bundle());
+            lines.push(Line::Bundle { address: address.bundle(), template: format!("{}{}{}", types[0], types[1], types[2]) });
+        }
+        // the `LX` instruction is at index 1, and its stop is recorded there too.
+        let stop = stops & (0b100 >> address.slot()) != 0;
+        lines.push(Line::Instruction { address, inst, stop });
+    }
+    lines
+}
+
+/// the label of an edge: the predicate it's taken under, if any.
+fn edge_label(edge: &Edge) -> Option<String> {
+    match (edge.predicate, edge.kind) {
+        (0, _) => None,
+        (p, EdgeKind::FallThrough) => Some(format!("!p{}", p)),
+        (p, _) => Some(format!("p{}", p)),
+    }
+}
+
+/// `text` as a quoted DOT string.
+pub(crate) fn dot_string(text: &str) -> String {
+    format!("\"{}\"", dot_escape(text))
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `cfg`, a graph of `bundles`, in Graphviz DOT. each block is a box of left-justified lines;
+/// edges to code outside the graph go to plain-text nodes with the target address.
+pub fn dot(bundles: &Bundles, cfg: &Cfg) -> String {
+    let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=\"monospace\"];\n");
+    let mut outside = Vec::new();
+    for block in cfg.blocks() {
+        let mut label = String::new();
+        for line in lines(bundles, block) {
+            match line {
+                Line::Bundle { address, template } => {
+                    write!(label, "{:#x} [{}]\\l", address, template).unwrap();
+                }
+                Line::Instruction { address, inst, stop } => {
+                    write!(label, "  {}  {}{}\\l", address.slot(), dot_escape(&inst.to_string()), if stop { " ;;" } else { "" }).unwrap();
+                }
+            }
+        }
+        writeln!(dot, "    \"{:#x}\" [label=\"{}\"];", block.start, label).unwrap();
+        for edge in block.edges.iter() {
+            let mut attributes = Vec::new();
+            if let Some(label) = edge_label(edge) {
+                attributes.push(format!("label={}", dot_string(&label)));
+            }
+            match edge.kind {
+                EdgeKind::Recovery => attributes.push("style=dashed".to_string()),
+                EdgeKind::JumpTable => attributes.push("style=dotted".to_string()),
+                _ => {}
+            }
+            let attributes = if attributes.is_empty() { String::new() } else { format!(" [{}]", attributes.join(", ")) };
+            writeln!(dot, "    \"{:#x}\" -> \"{:#x}\"{};", block.start, edge.target, attributes).unwrap();
+            if cfg.block(edge.target).is_none() && !outside.contains(&edge.target) {
+                outside.push(edge.target);
+            }
+        }
+    }
+    for target in outside {
+        writeln!(dot, "    \"{:#x}\" [label=\"{:#x}\", shape=plaintext];", target, target).unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+const STYLE: &str = "\
+body { font-family: monospace; }
+.block { border: 1px solid #888; margin: 1em 0; padding: 0.5em; }
+tr:target { background: #ffd; }
+.bundle td { color: #666; padding-top: 0.3em; }
+.slot { color: #666; padding-right: 1em; }
+.stop { color: #a00; padding-left: 1em; }
+.edges, .predecessors { margin-top: 0.5em; }
+";
+
+/// the id of the element for the instruction at `address`.
+fn html_id(address: SlotAddress) -> String {
+    format!("i{:x}", address)
+}
+
+/// a link to the instruction at `target`, if it's in `bundles`, or just its address.
+fn html_link(bundles: &Bundles, target: SlotAddress) -> String {
+    if bundles.contains(target) {
+        format!("<a href=\"#{}\">{:#x}</a>", html_id(target), target)
+    } else {
+        format!("{:#x}", target)
+    }
+}
+
+/// `inst` as `Display` writes it, with the ip-relative target linked to the instruction there.
+fn html_instruction(bundles: &Bundles, address: SlotAddress, inst: &Instruction) -> String {
+    let text = inst.to_string();
+    let bundle = bundles.bundle_at(address.bundle()).expect("instruction is in range");
+    let target = debug::successors(bundle, address, &BranchState::default()).into_iter()
+        .filter_map(|successor| successor.target)
+        .find(|target| *target != address.next_instruction(bundle.bundle_tag()));
+    match (text.find('$'), target) {
+        (Some(start), Some(target)) if bundles.contains(target) => {
+            let end = text[start..].find(&[',', ' ', ';'][..]).map(|end| start + end).unwrap_or(text.len());
+            format!("{}<a href=\"#{}\">{}</a>{}",
+                html_escape(&text[..start]), html_id(target), html_escape(&text[start..end]), html_escape(&text[end..]))
+        }
+        _ => html_escape(&text),
+    }
+}
+
+/// `cfg`, a graph of `bundles`, as a self-contained HTML page titled `title`. each block lists
+/// its edges, and the blocks with edges to it.
+pub fn html(bundles: &Bundles, cfg: &Cfg, title: &str) -> String {
+    let mut html = String::new();
+    write!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+        html_escape(title), STYLE).unwrap();
+    writeln!(html, "<h1>{}</h1>", html_escape(title)).unwrap();
+    for block in cfg.blocks() {
+        html.push_str("<div class=\"block\">\n<table>\n");
+        for line in lines(bundles, block) {
+            match line {
+                Line::Bundle { address, template } => {
+                    writeln!(html, "<tr class=\"bundle\"><td colspan=\"3\">{:#x} [{}]</td></tr>", address, template).unwrap();
+                }
+                Line::Instruction { address, inst, stop } => {
+                    writeln!(html, "<tr id=\"{}\"><td class=\"slot\">{}</td><td>{}</td><td class=\"stop\">{}</td></tr>",
+                        html_id(address), address.slot(), html_instruction(bundles, address, inst), if stop { ";;" } else { "" }).unwrap();
+                }
+            }
+        }
+        html.push_str("</table>\n");
+        if !block.edges.is_empty() {
+            let edges: Vec<String> = block.edges.iter().map(|edge| {
+                let kind = match edge.kind {
+                    EdgeKind::FallThrough => "fall through",
+                    EdgeKind::Branch => "branch",
+                    EdgeKind::Recovery => "recovery",
+                    EdgeKind::JumpTable => "jump table",
+                };
+                match edge_label(edge) {
+                    Some(label) => format!("{} {} ({})", kind, html_link(bundles, edge.target), label),
+                    None => format!("{} {}", kind, html_link(bundles, edge.target)),
+                }
+            }).collect();
+            writeln!(html, "<div class=\"edges\">to: {}</div>", edges.join(", ")).unwrap();
+        }
+        let predecessors = cfg.predecessors(block.start);
+        if !predecessors.is_empty() {
+            let links: Vec<String> = predecessors.iter().map(|start| {
+                let last = cfg.block(*start).expect("predecessors are blocks").last;
+                html_link(bundles, last)
+            }).collect();
+            writeln!(html, "<div class=\"predecessors\">from: {}</div>", links.join(", ")).unwrap();
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,252 @@
+//! matching instructions against patterns with wildcards.
+//!
+//! a pattern is written like an instruction, with `?` wildcards:
+//!
+//! * `ld8 ?r=[r1],?`: an `ld8` from `[r1]` with an increment, capturing the target.
+//! * `(?p) mov b6=?r`: a predicated move to `b6`, capturing the predicate and the source.
+//! * `br.call ?b=?b`: a call through a branch register.
+//! * `st*.rel`: any store with `.rel`, whatever its operands.
+//!
+//! the mnemonic is matched word by word: the base must be equal, or share the prefix before a
+//! trailing `*`, and every completer in the pattern must be among the opcode's completers, in
+//! any order. so `ld8` matches `ld8.acq` too. a mnemonic of `?` matches any opcode.
+//!
+//! without a `(qp)` prefix a pattern matches any qualifying predicate; `(p6)` matches only `p6`,
+//! and `(?p)` matches any predicate but `p0` and captures it.
+//!
+//! operands are matched in order, and an instruction may have operands past the last one the
+//! pattern names. `=` and `,` both just separate operands: `Display` writes some instructions,
+//! like `br.call` and `alloc`, with an `=` that does not follow `Instruction::last_write_index`.
+//! operands are written as `Display` writes them, or as one of:
+//!
+//! * `?`: any operand, not captured.
+//! * `?r`, `?f`, `?p`, `?b`, `?ar`, `?cr`: any register of that kind, captured.
+//! * `?imm`: any immediate, captured.
+//! * `[?r]`: any memory operand, capturing its address register.
+
+use core::str::FromStr;
+
+use yaxpeax_arch::{Decoder, U8Reader};
+
+use crate::{Bundles, InstDecoder, Instruction, Opcode, Operand, ParseError, PredicateRegister, SlotAddress};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RegisterKind {
+    General,
+    Float,
+    Predicate,
+    Branch,
+    Application,
+    Control,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum OperandPattern {
+    Any,
+    Register(RegisterKind),
+    Immediate,
+    Memory,
+    Exact(Operand),
+}
+
+impl OperandPattern {
+    /// does `op` match, and is it captured?
+    fn matches(&self, op: &Operand) -> Option<bool> {
+        let matched = match (self, op) {
+            (OperandPattern::Any, _) => return Some(false),
+            (OperandPattern::Exact(exact), op) => return if exact == op { Some(false) } else { None },
+            (OperandPattern::Register(RegisterKind::General), Operand::GPRegister(_)) |
+            (OperandPattern::Register(RegisterKind::Float), Operand::FloatRegister(_)) |
+            (OperandPattern::Register(RegisterKind::Predicate), Operand::PredicateRegister(_)) |
+            (OperandPattern::Register(RegisterKind::Branch), Operand::BranchRegister(_)) |
+            (OperandPattern::Register(RegisterKind::Application), Operand::ApplicationRegister(_)) |
+            (OperandPattern::Register(RegisterKind::Control), Operand::ControlRegister(_)) |
+            (OperandPattern::Immediate, Operand::ImmI64(_)) |
+            (OperandPattern::Immediate, Operand::ImmU64(_)) |
+            (OperandPattern::Memory, Operand::Memory(_)) => true,
+            _ => false,
+        };
+        if matched { Some(true) } else { None }
+    }
+}
+
+impl FromStr for OperandPattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<OperandPattern, ParseError> {
+        Ok(match s {
+            "?" => OperandPattern::Any,
+            "?r" => OperandPattern::Register(RegisterKind::General),
+            "?f" => OperandPattern::Register(RegisterKind::Float),
+            "?p" => OperandPattern::Register(RegisterKind::Predicate),
+            "?b" => OperandPattern::Register(RegisterKind::Branch),
+            "?ar" => OperandPattern::Register(RegisterKind::Application),
+            "?cr" => OperandPattern::Register(RegisterKind::Control),
+            "?imm" => OperandPattern::Immediate,
+            "[?r]" => OperandPattern::Memory,
+            other => OperandPattern::Exact(other.parse()?),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PredicatePattern {
+    Any,
+    AnyButP0,
+    Exact(PredicateRegister),
+}
+
+/// an instruction pattern. see the module documentation for the syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    predicate: PredicatePattern,
+    /// `None` for `?`.
+    base: Option<String>,
+    base_is_prefix: bool,
+    completers: Vec<String>,
+    operands: Vec<OperandPattern>,
+}
+
+/// an instruction that matched a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub address: SlotAddress,
+    /// the operands matched by capturing wildcards, in the order they are written in the
+    /// pattern. a `(?p)` capture comes first, and a `[?r]` capture is the `Operand::Memory`.
+    pub captures: Vec<Operand>,
+}
+
+impl Pattern {
+    /// if `inst` matches, the operands it captures.
+    pub fn matches(&self, inst: &Instruction) -> Option<Vec<Operand>> {
+        let mut captures = Vec::new();
+        let qp = inst.predicate();
+        match self.predicate {
+            PredicatePattern::Any => {}
+            PredicatePattern::AnyButP0 => {
+                if qp == 0 {
+                    return None;
+                }
+                captures.push(Operand::PredicateRegister(PredicateRegister(qp)));
+            }
+            PredicatePattern::Exact(reg) => {
+                if reg.0 != qp {
+                    return None;
+                }
+            }
+        }
+
+        if !self.opcode_matches(inst.opcode()) {
+            return None;
+        }
+
+        let operands = inst.operands();
+        if operands.len() < self.operands.len() {
+            return None;
+        }
+        for (pattern, op) in self.operands.iter().zip(operands.iter()) {
+            if pattern.matches(op)? {
+                captures.push(*op);
+            }
+        }
+        Some(captures)
+    }
+
+    fn opcode_matches(&self, opcode: Opcode) -> bool {
+        let base = match &self.base {
+            Some(base) => base,
+            None => { return true; }
+        };
+        let mut words = opcode.name().split('.');
+        let opcode_base = words.next().unwrap_or("");
+        let base_matches = if self.base_is_prefix {
+            opcode_base.starts_with(base.as_str())
+        } else {
+            opcode_base == base
+        };
+        if !base_matches {
+            return false;
+        }
+        let words: Vec<&str> = words.collect();
+        self.completers.iter().all(|c| words.contains(&c.as_str()))
+    }
+
+    /// every instruction in `bundles` that matches, in program order.
+    pub fn search(&self, bundles: &Bundles) -> Vec<Match> {
+        bundles.instructions()
+            .filter_map(|(address, inst)| {
+                self.matches(inst).map(|captures| Match { address, captures })
+            })
+            .collect()
+    }
+
+    /// every instruction in `bytes`, which holds code starting at the bundle address `base`,
+    /// that matches. unlike `Bundles::decode`, bundles that do not decode are skipped, so this
+    /// can scan a whole section that has data mixed in with code.
+    pub fn search_bytes(&self, decoder: &InstDecoder, bytes: &[u8], base: u64) -> Vec<Match> {
+        let mut matches = Vec::new();
+        for (i, chunk) in bytes.chunks_exact(16).enumerate() {
+            let bundle = match decoder.decode(&mut U8Reader::new(chunk)) {
+                Ok(bundle) => bundle,
+                Err(_) => { continue; }
+            };
+            let address = (base & !0xf).wrapping_add(i as u64 * 16);
+            for (address, inst) in bundle.instructions_at(address) {
+                if let Some(captures) = self.matches(inst) {
+                    matches.push(Match { address, captures });
+                }
+            }
+        }
+        matches
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Pattern, ParseError> {
+        let mut s = s.trim();
+        let mut predicate = PredicatePattern::Any;
+        if let Some(rest) = s.strip_prefix('(') {
+            let close = rest.find(')').ok_or(ParseError::Unrecognized)?;
+            predicate = match &rest[..close] {
+                "?p" => PredicatePattern::AnyButP0,
+                reg => PredicatePattern::Exact(reg.parse()?),
+            };
+            s = rest[close + 1..].trim_start();
+        }
+
+        let (mnemonic, operands) = match s.find(' ') {
+            Some(space) => (&s[..space], s[space + 1..].trim()),
+            None => (s, ""),
+        };
+        let (base, base_is_prefix, completers) = if mnemonic == "?" {
+            (None, false, Vec::new())
+        } else {
+            let mut words = mnemonic.split('.');
+            let base = words.next().unwrap_or("");
+            let (base, base_is_prefix) = match base.strip_suffix('*') {
+                Some(prefix) => (prefix, true),
+                None => (base, false),
+            };
+            if base.is_empty() && !base_is_prefix {
+                return Err(ParseError::Unrecognized);
+            }
+            (Some(base.to_string()), base_is_prefix, words.map(|w| w.to_string()).collect())
+        };
+
+        let mut pattern = Pattern {
+            predicate,
+            base,
+            base_is_prefix,
+            completers,
+            operands: Vec::new(),
+        };
+        if !operands.is_empty() {
+            for op in operands.split(&[',', '='][..]) {
+                pattern.operands.push(op.trim().parse()?);
+            }
+        }
+        Ok(pattern)
+    }
+}
@@ -0,0 +1,126 @@
+//! estimating function signatures from register use.
+//!
+//! the software conventions pass the first eight integer arguments in the stacked registers
+//! `in0`-`in7`, which a callee sees as `r32`-`r39`, and floating-point arguments in `f8`-`f15`.
+//! results come back in `r8`-`r11` and `f8`-`f15`. a function's arguments are the argument
+//! registers live on entry, and its results the result registers it writes on some path to a
+//! `br.ret`; arguments are positional, so the count is one more than the highest register
+//! used. `alloc` bounds the inputs further: they are part of the frame's locals.
+//!
+//! this is an estimate. an argument the function ignores isn't seen, and a result left by a call
+//! and returned unchanged isn't counted, since a call writes every result register. a function
+//! ending in a tail call or an indirect branch without a recovered jump table leaves every
+//! register live, so every argument register looks used.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Bundles, FloatRegister, GPRegister, Opcode, SlotAddress};
+use crate::cfg::Cfg;
+use crate::functions::{self, FunctionStart};
+use crate::liveness::{self, Frame, Liveness, RegisterSet};
+use crate::registers::{self, Location};
+
+/// the number of integer arguments passed in registers.
+const REGISTER_ARGUMENTS: u8 = 8;
+
+/// an estimate of a function's arguments and results.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Signature {
+    /// the function's first instruction.
+    pub start: SlotAddress,
+    /// the frame from the function's `alloc`, if it has exactly one.
+    pub frame: Option<Frame>,
+    /// how many of `in0`-`in7` hold arguments.
+    pub inputs: u8,
+    /// how many of `f8`-`f15` hold arguments.
+    pub float_inputs: u8,
+    /// how many of `r8`-`r11` hold results.
+    pub results: u8,
+    /// how many of `f8`-`f15` hold results.
+    pub float_results: u8,
+}
+
+/// one more than the highest register of `first..first + count` in `set`, or 0 if none are.
+fn highest(set: &RegisterSet, location: fn(u8) -> Location, first: u8, count: u8) -> u8 {
+    (0..count).rev().find(|i| set.contains(location(first + i))).map(|i| i + 1).unwrap_or(0)
+}
+
+fn gr(n: u8) -> Location {
+    Location::GPRegister(GPRegister(n))
+}
+
+fn fr(n: u8) -> Location {
+    Location::FloatRegister(FloatRegister(n))
+}
+
+impl Signature {
+    /// the signature of the function whose code is `bundles`, starting at its first instruction.
+    pub fn infer(bundles: &Bundles, cfg: &Cfg) -> Option<Signature> {
+        let start = cfg.blocks().next()?.start;
+        // a result register the function doesn't write is read by its returns only if it's an
+        // argument passed through, which is rare enough to ignore.
+        let written = written_results(bundles, cfg);
+        let liveness = Liveness::with_results(bundles, cfg, written);
+        let frame = liveness.frame();
+        let entry = liveness.live_in(start);
+        let limit = frame.map(|frame| frame.locals.min(REGISTER_ARGUMENTS)).unwrap_or(REGISTER_ARGUMENTS);
+        Some(Signature {
+            start,
+            frame,
+            inputs: highest(&entry, gr, 32, limit),
+            float_inputs: highest(&entry, fr, 8, 8),
+            results: highest(&written, gr, 8, 4),
+            float_results: highest(&written, fr, 8, 8),
+        })
+    }
+}
+
+/// the result registers written by the function itself on some path to a `br.ret`.
+fn written_results(bundles: &Bundles, cfg: &Cfg) -> RegisterSet {
+    let results = liveness::results();
+    let mut returned = RegisterSet::new();
+    let mut written_in: BTreeMap<SlotAddress, RegisterSet> = BTreeMap::new();
+    let mut worklist: BTreeSet<SlotAddress> = cfg.blocks().map(|block| block.start).collect();
+    while let Some(start) = worklist.iter().next().cloned() {
+        worklist.remove(&start);
+        let block = cfg.block(start).expect("worklist holds blocks");
+        let mut written = written_in.get(&start).cloned().unwrap_or_default();
+        for (_, inst) in block.instructions(bundles) {
+            match inst.opcode() {
+                Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh => {
+                    written = written.difference(&results);
+                }
+                Opcode::Br_ret => {
+                    returned = returned.union(&written);
+                }
+                _ => {
+                    for location in registers::accesses(inst).writes {
+                        if results.contains(location) {
+                            written.insert(location);
+                        }
+                    }
+                }
+            }
+        }
+        for edge in block.edges.iter() {
+            if cfg.block(edge.target).is_none() {
+                continue;
+            }
+            let before = written_in.get(&edge.target).cloned().unwrap_or_default();
+            let after = before.union(&written);
+            if after != before {
+                written_in.insert(edge.target, after);
+                worklist.insert(edge.target);
+            }
+        }
+    }
+    returned
+}
+
+/// a signature for each function in `starts`, each taken to run to the next start; see
+/// `functions::bodies`.
+pub fn infer_all(bundles: &Bundles, starts: &[FunctionStart]) -> Vec<Signature> {
+    functions::bodies(bundles, starts).iter()
+        .filter_map(|body| Signature::infer(body, &Cfg::new(body)))
+        .collect()
+}
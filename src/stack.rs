@@ -0,0 +1,263 @@
+//! the memory stack frame: how a function moves the stack pointer, `r12`, and where it spills
+//! registers.
+//!
+//! the memory stack is separate from the register stack. a function with a fixed-size frame
+//! allocates it with `adds r12=-N,r12` and frees it with `adds r12=N,r12` or by restoring a copy
+//! saved with `mov rX=r12`; one with a variable-size frame moves `r12` by a computed amount and
+//! restores the copy. `StackFrame::analyze` tracks every general register holding a constant
+//! offset from `r12` on entry, through a `Cfg` of the function, and reports where `r12` changes,
+//! where `st8.spill` and `stf.spill` store to the frame, and where `br.ret` is reached with `r12`
+//! not back where it started.
+//!
+//! the unwind descriptors of a function say where it allocates its frame and how big a fixed
+//! frame is; `StackFrame::check_unwind` compares them with the code.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Bundles, FloatRegister, GPRegister, Instruction, Opcode, Operand, SlotAddress};
+use crate::cfg::Cfg;
+use crate::functions::{self, FunctionStart};
+use crate::liveness::Frame;
+use crate::registers::{self, Location};
+use crate::unwind::{self, Descriptor};
+
+/// the stack pointer.
+const SP: usize = 12;
+
+/// a register spilled to the memory stack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Spill {
+    /// the `st8.spill` or `stf.spill`.
+    pub address: SlotAddress,
+    pub register: Location,
+    /// the spill slot's offset from `r12` on entry to the function.
+    pub offset: i64,
+}
+
+/// an instruction that writes `r12`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Adjustment {
+    pub address: SlotAddress,
+    /// `r12` after the instruction, as an offset from `r12` on entry, if it is a constant offset.
+    pub delta: Option<i64>,
+}
+
+/// a `br.ret` reached with `r12` not as it was on entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Unbalanced {
+    pub address: SlotAddress,
+    /// `r12` as an offset from `r12` on entry, if it is a constant offset.
+    pub delta: Option<i64>,
+}
+
+/// a disagreement between a function's unwind descriptors and its code.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnwindMismatch {
+    /// a `mem_stack_f` or `mem_stack_v` names an instruction that doesn't write `r12`.
+    NotAnAdjustment { address: SlotAddress },
+    /// a `mem_stack_f` records a frame of `expected` bytes, and the instruction it names leaves
+    /// `r12` lowered by `found` bytes, if by a constant amount.
+    Size { address: SlotAddress, expected: u64, found: Option<u64> },
+    /// the function allocates a frame, and no descriptor records it.
+    Missing,
+}
+
+/// what is known of a function's memory stack frame.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StackFrame {
+    /// the function's first instruction.
+    pub start: SlotAddress,
+    /// the most `r12` is lowered by a constant amount: the size of a fixed frame.
+    pub size: u64,
+    /// is `r12` ever set to something other than a constant offset from its value on entry, as
+    /// for a variable-size frame?
+    pub variable: bool,
+    pub adjustments: Vec<Adjustment>,
+    pub spills: Vec<Spill>,
+    pub unbalanced: Vec<Unbalanced>,
+}
+
+/// each general register's offset from `r12` on entry, where it holds one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct State {
+    offsets: [Option<i64>; 128],
+}
+
+impl State {
+    fn entry() -> State {
+        let mut offsets = [None; 128];
+        offsets[SP] = Some(0);
+        State { offsets }
+    }
+
+    fn join(&self, other: &State) -> State {
+        let mut offsets = self.offsets;
+        for (offset, other) in offsets.iter_mut().zip(other.offsets.iter()) {
+            if offset != other {
+                *offset = None;
+            }
+        }
+        State { offsets }
+    }
+
+    fn offset(&self, operand: &Operand) -> Option<i64> {
+        match operand {
+            Operand::GPRegister(GPRegister(r)) => self.offsets[*r as usize],
+            _ => None,
+        }
+    }
+}
+
+/// `state` after `inst`, and the spill `inst` makes, if it's one.
+fn step(inst: &Instruction, state: &State, frame: Option<Frame>) -> (State, Option<(Location, i64)>) {
+    let mut next = *state;
+    let mut assigned: Option<(u8, Option<i64>)> = None;
+    let mut spill = None;
+    let operands = inst.operands();
+    match (inst.opcode(), operands) {
+        (Opcode::Adds, [Operand::GPRegister(dest), Operand::ImmI64(imm), source]) |
+        (Opcode::Addl, [Operand::GPRegister(dest), Operand::ImmI64(imm), source]) => {
+            assigned = Some((dest.0, state.offset(source).map(|offset| offset.wrapping_add(*imm))));
+        }
+        (Opcode::Mov, [Operand::GPRegister(dest), source]) => {
+            assigned = Some((dest.0, state.offset(source)));
+        }
+        (Opcode::St8_spill, [Operand::Memory(base), Operand::GPRegister(GPRegister(r)), ..]) => {
+            spill = state.offsets[base.0 as usize].map(|offset| (Location::GPRegister(GPRegister(*r)), offset));
+        }
+        (Opcode::Stf_spill, [Operand::Memory(base), Operand::FloatRegister(FloatRegister(f)), ..]) => {
+            spill = state.offsets[base.0 as usize].map(|offset| (Location::FloatRegister(FloatRegister(*f)), offset));
+        }
+        _ => {}
+    }
+    let writes = registers::accesses(inst).writes;
+    // a post-increment moves the base register by a constant.
+    if let (Some(base), Some(Operand::ImmI64(increment))) = (operands.iter().find_map(|op| if let Operand::Memory(base) = op { Some(*base) } else { None }), operands.last()) {
+        if writes.contains(&Location::GPRegister(base)) {
+            assigned = Some((base.0, state.offsets[base.0 as usize].map(|offset| offset.wrapping_add(*increment))));
+        }
+    }
+
+    for location in writes {
+        if let Location::GPRegister(GPRegister(r)) = location {
+            next.offsets[r as usize] = None;
+        }
+    }
+    if let Some((r, offset)) = assigned {
+        // a predicated write might not happen.
+        let unchanged = inst.predicate() == 0 || state.offsets[r as usize] == offset;
+        next.offsets[r as usize] = if unchanged { offset } else { None };
+    }
+    if let Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh = inst.opcode() {
+        // the callee may change scratch registers and its inputs, our outputs.
+        let outputs = frame.map(|frame| 32 + frame.locals as usize).unwrap_or(128);
+        for r in (2..4).chain(8..12).chain(14..32).chain(outputs.min(128)..128) {
+            next.offsets[r] = None;
+        }
+    }
+    (next, spill)
+}
+
+impl StackFrame {
+    /// the memory stack frame of the function whose code is `bundles`, starting at its first
+    /// instruction. code not reachable from the start is not looked at.
+    pub fn analyze(bundles: &Bundles, cfg: &Cfg) -> Option<StackFrame> {
+        let start = cfg.blocks().next()?.start;
+        let frame = cfg.blocks()
+            .flat_map(|block| block.instructions(bundles))
+            .find_map(|(_, inst)| Frame::of(inst));
+
+        let mut states: BTreeMap<SlotAddress, State> = BTreeMap::new();
+        states.insert(start, State::entry());
+        let mut worklist: BTreeSet<SlotAddress> = BTreeSet::new();
+        worklist.insert(start);
+        while let Some(block_start) = worklist.iter().next().cloned() {
+            worklist.remove(&block_start);
+            let block = cfg.block(block_start).expect("worklist holds blocks");
+            let mut state = states[&block_start];
+            for (_, inst) in block.instructions(bundles) {
+                state = step(inst, &state, frame).0;
+            }
+            for edge in block.edges.iter() {
+                if cfg.block(edge.target).is_none() {
+                    continue;
+                }
+                let joined = match states.get(&edge.target) {
+                    Some(before) => before.join(&state),
+                    None => state,
+                };
+                if states.get(&edge.target) != Some(&joined) {
+                    states.insert(edge.target, joined);
+                    worklist.insert(edge.target);
+                }
+            }
+        }
+
+        let mut stack = StackFrame { start, size: 0, variable: false, adjustments: Vec::new(), spills: Vec::new(), unbalanced: Vec::new() };
+        for (block_start, entry) in states.iter() {
+            let block = cfg.block(*block_start).expect("states are for blocks");
+            let mut state = *entry;
+            for (address, inst) in block.instructions(bundles) {
+                if inst.opcode() == Opcode::Br_ret && state.offsets[SP] != Some(0) {
+                    stack.unbalanced.push(Unbalanced { address, delta: state.offsets[SP] });
+                }
+                let (next, spill) = step(inst, &state, frame);
+                if let Some((register, offset)) = spill {
+                    stack.spills.push(Spill { address, register, offset });
+                }
+                if registers::accesses(inst).writes.contains(&Location::GPRegister(GPRegister(SP as u8))) {
+                    let delta = next.offsets[SP];
+                    match delta {
+                        Some(delta) if delta < 0 => { stack.size = stack.size.max(delta.unsigned_abs()); }
+                        Some(_) => {}
+                        None => { stack.variable = true; }
+                    }
+                    stack.adjustments.push(Adjustment { address, delta });
+                }
+                state = next;
+            }
+        }
+        Some(stack)
+    }
+
+    /// compare the frame with the unwind descriptors of the function, from
+    /// `unwind::info_block`. `bundles` is the function's code.
+    pub fn check_unwind(&self, bundles: &Bundles, descriptors: &[Descriptor]) -> Vec<UnwindMismatch> {
+        let allocations = unwind::stack_allocations(descriptors);
+        let mut mismatches = Vec::new();
+        if allocations.is_empty() && (self.size > 0 || self.variable) {
+            mismatches.push(UnwindMismatch::Missing);
+        }
+        for allocation in allocations {
+            let bundle = self.start.bundle().wrapping_add(allocation.slot / 3 * 16);
+            let address = match SlotAddress::new(bundle, (allocation.slot % 3) as u8) {
+                Some(address) => address,
+                None => { continue; }
+            };
+            let address = match bundles.bundle_at(bundle) {
+                Some(bundle) => address.canonical(bundle.bundle_tag()),
+                None => address,
+            };
+            match self.adjustments.iter().find(|adjustment| adjustment.address == address) {
+                None => mismatches.push(UnwindMismatch::NotAnAdjustment { address }),
+                Some(adjustment) => {
+                    if let Some(expected) = allocation.size {
+                        let found = adjustment.delta.filter(|delta| *delta < 0).map(|delta| delta.unsigned_abs());
+                        if found != Some(expected) {
+                            mismatches.push(UnwindMismatch::Size { address, expected, found });
+                        }
+                    }
+                }
+            }
+        }
+        mismatches
+    }
+}
+
+/// the frame of each function in `starts`, each taken to run to the next start; see
+/// `functions::bodies`.
+pub fn analyze_all(bundles: &Bundles, starts: &[FunctionStart]) -> Vec<StackFrame> {
+    functions::bodies(bundles, starts).iter()
+        .filter_map(|body| StackFrame::analyze(body, &Cfg::new(body)))
+        .collect()
+}
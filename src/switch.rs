@@ -0,0 +1,260 @@
+//! recovering jump tables from `switch` dispatch code.
+//!
+//! compilers dispatch a dense `switch` by bounds-checking the index, scaling it into a table of
+//! addresses or offsets, and branching through a branch register. gcc's version is:
+//!
+//! ```text
+//! cmp.ltu p6,p7=0x7,r15       // index out of range?
+//! (p6) br.cond.dpnt default
+//! addl r14=@ltoff(.Ltable),r1;;
+//! ld8 r14=[r14];;             // table base, from the linkage table
+//! shladd r15=r15,0x3,r14;;    // entry address
+//! ld8 r16=[r15];;             // entry, an offset from the entry itself
+//! add r15=r16,r15;;
+//! mov b6=r15
+//! br.few b6;;
+//! ```
+//!
+//! `recover` follows the branch register of each indirect `br.cond` back through the `mov b=r`
+//! to the `shladd`, taking the table base and other constants from `values::Values` as
+//! `gp::resolve` does, and the number of entries from the last `cmp.ltu` of the index against a
+//! constant before the `shladd`. entries may be absolute addresses, offsets from the entry, or
+//! offsets from a constant base; 4-byte entries may be sign-extended with `sxt4`. a value is only
+//! followed back to an instruction that writes it unconditionally or under the same predicate
+//! as the instruction reading it, and a table is only read if the image says it is read-only.
+
+use crate::{Bundles, GPRegister, Instruction, Opcode, Operand, SlotAddress};
+use crate::cfg::Cfg;
+use crate::image::Image;
+use crate::mnemonic::{Completer, Relation};
+use crate::registers::{self, Location};
+use crate::values::Values;
+
+/// tables with more entries than this are assumed to be misidentified.
+const MAX_ENTRIES: u64 = 4096;
+
+/// how many `mov r=r` a value is followed back through.
+const MAX_MOVES: usize = 16;
+
+/// how an entry in a jump table is turned into a target address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// the entry is the target.
+    Absolute,
+    /// the target is the entry's own address plus the entry.
+    EntryRelative,
+    /// the target is this base address plus the entry.
+    Relative(u64),
+}
+
+/// a jump table and the indirect branch that dispatches through it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JumpTable {
+    /// the branch through the table.
+    pub branch: SlotAddress,
+    /// the `cmp` bounding the index.
+    pub bound: SlotAddress,
+    /// address of the first entry.
+    pub table: u64,
+    /// size of an entry in bytes.
+    pub entry_size: u8,
+    pub kind: EntryKind,
+    /// the target of each entry, in table order.
+    pub targets: Vec<SlotAddress>,
+}
+
+/// an entry of a table at `table`, indexed by general register `index`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Entry {
+    index: u8,
+    scale: u64,
+    table: u64,
+}
+
+/// an entry loaded with `size` bytes, sign-extended or not.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Loaded {
+    entry: Entry,
+    size: u8,
+    signed: bool,
+}
+
+/// the value of `op` as read by the instruction at `at`, if it is a known constant.
+fn constant<I: Image + ?Sized>(values: &Values<I>, at: SlotAddress, op: &Operand) -> Option<u64> {
+    match op {
+        Operand::ImmI64(imm) => Some(*imm as u64),
+        Operand::ImmU64(imm) => Some(*imm),
+        Operand::GPRegister(reg) => values.value(at, *reg),
+        _ => None,
+    }
+}
+
+/// the instruction that computed the value of `op` read by the instruction at `at`, looking
+/// through `mov r=r`.
+fn definition<'a, I: Image + ?Sized>(values: &Values<'a, I>, mut at: SlotAddress, op: &Operand) -> Option<(SlotAddress, &'a Instruction)> {
+    let mut reg = match op {
+        Operand::GPRegister(reg) => *reg,
+        _ => { return None; }
+    };
+    for _ in 0..MAX_MOVES {
+        let (def, inst) = values.definition(at, Location::GPRegister(reg))?;
+        match (inst.opcode(), inst.operands()) {
+            (Opcode::Mov, [Operand::GPRegister(dest), Operand::GPRegister(source)]) if *dest == reg => {
+                at = def;
+                reg = *source;
+            }
+            _ => { return Some((def, inst)); }
+        }
+    }
+    None
+}
+
+/// the `shladd` that computed the entry address in `op` read at `at`, and the entry.
+fn entry_address<I: Image + ?Sized>(values: &Values<I>, at: SlotAddress, op: &Operand) -> Option<(SlotAddress, Entry)> {
+    let (def, inst) = definition(values, at, op)?;
+    match (inst.opcode(), inst.operands()) {
+        (Opcode::Shladd, [Operand::GPRegister(_), Operand::GPRegister(index), count, base]) => {
+            let count = constant(values, def, count)?;
+            let table = constant(values, def, base)?;
+            Some((def, Entry { index: index.0, scale: 1 << count, table }))
+        }
+        _ => None,
+    }
+}
+
+/// the entry loaded into `op` read at `at`, and the `shladd` that computed its address.
+fn loaded_entry<I: Image + ?Sized>(values: &Values<I>, at: SlotAddress, op: &Operand) -> Option<(SlotAddress, Loaded)> {
+    let (def, inst) = definition(values, at, op)?;
+    let parts = inst.opcode().mnemonic_parts();
+    match (inst.opcode(), inst.operands()) {
+        (Opcode::Sxt4, [Operand::GPRegister(_), source]) => {
+            match loaded_entry(values, def, source)? {
+                (shladd, Loaded { entry, size: 4, signed: false }) => Some((shladd, Loaded { entry, size: 4, signed: true })),
+                _ => None,
+            }
+        }
+        (_, [Operand::GPRegister(_), Operand::Memory(base), ..]) if parts.base.starts_with("ld") => {
+            let size = parts.size().filter(|size| *size == 4 || *size == 8)?;
+            let (shladd, entry) = entry_address(values, def, &Operand::GPRegister(*base))?;
+            Some((shladd, Loaded { entry, size, signed: false }))
+        }
+        _ => None,
+    }
+}
+
+/// the target computed in `op` read at `at` from a table entry, the entry, how it is turned into
+/// a target, and the `shladd` that computed its address.
+fn target<I: Image + ?Sized>(values: &Values<I>, at: SlotAddress, op: &Operand) -> Option<(SlotAddress, Loaded, EntryKind)> {
+    let (def, inst) = definition(values, at, op)?;
+    match (inst.opcode(), inst.operands()) {
+        (Opcode::Add, [Operand::GPRegister(_), a, b]) => {
+            [(a, b), (b, a)].iter().find_map(|(loaded, other)| {
+                let (shladd, loaded) = loaded_entry(values, def, loaded)?;
+                let kind = if entry_address(values, def, other).map(|(_, entry)| entry) == Some(loaded.entry) {
+                    EntryKind::EntryRelative
+                } else {
+                    EntryKind::Relative(constant(values, def, other)?)
+                };
+                Some((shladd, loaded, kind))
+            })
+        }
+        _ => match loaded_entry(values, at, op)? {
+            (shladd, loaded @ Loaded { size: 8, .. }) => Some((shladd, loaded, EntryKind::Absolute)),
+            _ => None,
+        },
+    }
+}
+
+/// the last `cmp.ltu` of general register `index` against a constant on the path to `address`,
+/// and the number of values it allows, if the index isn't written after it.
+fn bound<I: Image + ?Sized>(values: &Values<I>, address: SlotAddress, index: u8) -> Option<(SlotAddress, u64)> {
+    let index_location = Location::GPRegister(GPRegister(index));
+    for (at, inst) in values.path_to(address) {
+        let parts = inst.opcode().mnemonic_parts();
+        if parts.base.starts_with("cmp") && parts.has(Completer::Relation(Relation::Ltu)) {
+            if let [Operand::PredicateRegister(_), Operand::PredicateRegister(_), a, Operand::GPRegister(b)] = inst.operands() {
+                match a {
+                    // `N < index` is the out-of-range test for an index in `0..=N`.
+                    Operand::ImmI64(n) if b.0 == index => { return Some((at, (*n as u64).wrapping_add(1))); }
+                    // `index < n` is the in-range test for an index in `0..n`.
+                    Operand::GPRegister(a) if a.0 == index => {
+                        if let Some(n) = values.value(at, *b) {
+                            return Some((at, n));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if registers::accesses(inst).writes.contains(&index_location) {
+            return None;
+        }
+    }
+    None
+}
+
+/// the jump table dispatched through by the `br.cond` at `address`, through `reg`.
+fn dispatch<I: Image + ?Sized>(values: &Values<I>, image: &I, address: SlotAddress, reg: Location) -> Option<JumpTable> {
+    let (moved_at, inst) = values.definition(address, reg)?;
+    let source = match (inst.opcode(), inst.operands()) {
+        (Opcode::Mov_mwh_ih, [Operand::BranchRegister(_), source, ..]) => source,
+        _ => { return None; }
+    };
+    let (shladd, Loaded { entry, size, signed }, kind) = target(values, moved_at, source)?;
+    let (bound_at, count) = bound(values, shladd, entry.index)?;
+    let targets = read_targets(image, entry, size, signed, kind, count)?;
+    Some(JumpTable {
+        branch: address,
+        bound: bound_at,
+        table: entry.table,
+        entry_size: size,
+        kind,
+        targets,
+    })
+}
+
+/// every jump table dispatched through in `bundles`, for a module whose global pointer is `gp`.
+/// a dispatch whose table base, bound, or entries can't be found is left out.
+pub fn recover<I: Image + ?Sized>(bundles: &Bundles, gp: u64, image: &I) -> Vec<JumpTable> {
+    let cfg = Cfg::new(bundles);
+    let values = Values::analyze(bundles, &cfg, image, &[(GPRegister(1), gp)]);
+    bundles.instructions().filter_map(|(address, inst)| {
+        match (inst.opcode(), inst.operands()) {
+            (Opcode::Br_cond, [Operand::BranchRegister(reg), ..]) => dispatch(&values, image, address, Location::BranchRegister(*reg)),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// the targets of the `count` entries of a table, if every entry is mapped and read-only and
+/// every target is bundle-aligned.
+fn read_targets<I: Image + ?Sized>(image: &I, entry: Entry, size: u8, signed: bool, kind: EntryKind, count: u64) -> Option<Vec<SlotAddress>> {
+    if count == 0 || count > MAX_ENTRIES {
+        return None;
+    }
+    let mut targets = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let at = entry.table.wrapping_add(i.wrapping_mul(entry.scale));
+        if !image.is_read_only(at) {
+            return None;
+        }
+        let raw = match size {
+            8 => image.read_u64(at)?,
+            _ => {
+                let bytes = image.bytes_at(at);
+                if bytes.len() < 4 {
+                    return None;
+                }
+                let word = image.byte_order().read_u32(bytes);
+                if signed { word as i32 as i64 as u64 } else { word as u64 }
+            }
+        };
+        let target = match kind {
+            EntryKind::Absolute => raw,
+            EntryKind::EntryRelative => at.wrapping_add(raw),
+            EntryKind::Relative(base) => base.wrapping_add(raw),
+        };
+        targets.push(SlotAddress::new(target, 0)?);
+    }
+    Some(targets)
+}
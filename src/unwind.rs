@@ -0,0 +1,270 @@
+//! the unwind table, from the software conventions and runtime architecture guide, chapter
+//! `11 Stack Unwinding and Exception Handling`.
+//!
+//! the unwind table (`.IA_64.unwind`, or `PT_IA_64_UNWIND`) is an array of three-doubleword
+//! entries, sorted by address, one for each function that has unwind information: the start and
+//! end of the function's code and the location of its unwind information block, all as offsets
+//! from the base of the text segment.
+//!
+//! an unwind information block is a header doubleword, with the format version, flags, and the
+//! length of the descriptor area in doublewords, then the descriptor records. records are a
+//! byte or more each, with ULEB128 fields; which record a byte starts depends on whether it's in
+//! a prologue or a body region. times in records are counted in instruction slots from the start
+//! of the region, three to a bundle.
+
+use crate::image::{ByteOrder, Image};
+
+/// one unwind table entry, with offsets resolved to addresses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TableEntry {
+    /// address of the function's first bundle.
+    pub start: u64,
+    /// address just past the function's last bundle.
+    pub end: u64,
+    /// address of the function's unwind information block.
+    pub info: u64,
+}
+
+/// the entries of the unwind table in `bytes`, for a text segment loaded at `segment_base`.
+/// trailing bytes that are not a whole entry are ignored, as are all-zero entries, which some
+/// linkers leave as padding.
+pub fn table_entries(bytes: &[u8], segment_base: u64, order: ByteOrder) -> impl Iterator<Item=TableEntry> + '_ {
+    bytes.chunks_exact(24).filter_map(move |entry| {
+        let start = order.read_u64(&entry[0..]);
+        let end = order.read_u64(&entry[8..]);
+        let info = order.read_u64(&entry[16..]);
+        if start == 0 && end == 0 && info == 0 {
+            return None;
+        }
+        Some(TableEntry {
+            start: segment_base.wrapping_add(start),
+            end: segment_base.wrapping_add(end),
+            info: segment_base.wrapping_add(info),
+        })
+    })
+}
+
+/// an unwind descriptor record. records this crate doesn't look into are kept as `Other`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Descriptor {
+    /// a region header (`R1`-`R3`): a prologue or body region `length` slots long.
+    Region { prologue: bool, length: u64 },
+    /// `mem_stack_f`: a fixed-size memory stack frame of `size` bytes is allocated by the
+    /// instruction `when` slots into the prologue.
+    MemStackF { when: u64, size: u64 },
+    /// `mem_stack_v`: a variable-size memory stack frame is allocated by the instruction `when`
+    /// slots into the prologue.
+    MemStackV { when: u64 },
+    /// `psp_gr`: the previous stack pointer is saved in general register `gr`.
+    PspGr { gr: u8 },
+    /// `epilogue`: `count + 1` levels of frames are popped `when` slots before the end of the body
+    /// region.
+    Epilogue { when: u64, count: u64 },
+    /// any other record, by its first byte.
+    Other(u8),
+}
+
+/// an unwind information block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InfoBlock {
+    pub version: u16,
+    /// `EHANDLER` is bit 0 and `UHANDLER` bit 1.
+    pub flags: u16,
+    pub descriptors: Vec<Descriptor>,
+}
+
+/// the unwind information block at `address`, as a table entry's `info` gives it, if it is
+/// mapped and its descriptors parse.
+pub fn info_block<I: Image + ?Sized>(image: &I, address: u64) -> Option<InfoBlock> {
+    let header = image.read_u64(address)?;
+    let length = ((header & 0xffff_ffff) as usize).checked_mul(8)?;
+    let bytes = image.bytes_at(address.wrapping_add(8));
+    if bytes.len() < length {
+        return None;
+    }
+    Some(InfoBlock {
+        version: (header >> 48) as u16,
+        flags: (header >> 32) as u16,
+        descriptors: parse_descriptors(&bytes[..length])?,
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let (first, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(*first)
+    }
+
+    fn skip(&mut self, count: usize) -> Option<()> {
+        self.bytes = self.bytes.get(count..)?;
+        Some(())
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+}
+
+/// the records in a descriptor area, or `None` if a record is reserved or cut off. zero bytes
+/// padding out the area read as empty prologue regions.
+pub fn parse_descriptors(bytes: &[u8]) -> Option<Vec<Descriptor>> {
+    let mut reader = Reader { bytes };
+    let mut descriptors = Vec::new();
+    let mut prologue = true;
+    // the length of the current region, which sizes the spill mask of a `P4` record.
+    let mut region_length = 0;
+    while let Some(first) = reader.byte() {
+        let descriptor = match first {
+            // R1
+            0x00..=0x3f => {
+                prologue = first & 0x20 == 0;
+                region_length = (first & 0x1f) as u64;
+                Descriptor::Region { prologue, length: region_length }
+            }
+            // R2, a prologue with saved registers.
+            0x40..=0x47 => {
+                reader.skip(1)?;
+                prologue = true;
+                region_length = reader.uleb()?;
+                Descriptor::Region { prologue, length: region_length }
+            }
+            // R3
+            0x60..=0x63 => {
+                prologue = first & 3 == 0;
+                region_length = reader.uleb()?;
+                Descriptor::Region { prologue, length: region_length }
+            }
+            0x48..=0x5f | 0x64..=0x7f => { return None; }
+            // X1-X4, in either kind of region.
+            0xf9 | 0xfb => {
+                reader.skip(if first == 0xf9 { 1 } else { 2 })?;
+                reader.uleb()?;
+                reader.uleb()?;
+                Descriptor::Other(first)
+            }
+            0xfa | 0xfc => {
+                reader.skip(if first == 0xfa { 2 } else { 3 })?;
+                reader.uleb()?;
+                Descriptor::Other(first)
+            }
+            _ if prologue => match first {
+                // P1
+                0x80..=0x9f => Descriptor::Other(first),
+                // P2
+                0xa0..=0xaf => {
+                    reader.skip(1)?;
+                    Descriptor::Other(first)
+                }
+                // P3
+                0xb0..=0xb7 => {
+                    let second = reader.byte()?;
+                    match ((first & 7) << 1) | (second >> 7) {
+                        0 => Descriptor::PspGr { gr: second & 0x7f },
+                        _ => Descriptor::Other(first),
+                    }
+                }
+                // P4, two bits of spill mask for each slot of the region.
+                0xb8 => {
+                    reader.skip((region_length.saturating_mul(2).saturating_add(7) / 8) as usize)?;
+                    Descriptor::Other(first)
+                }
+                // P5
+                0xb9 => {
+                    reader.skip(3)?;
+                    Descriptor::Other(first)
+                }
+                // P6
+                0xc0..=0xdf => Descriptor::Other(first),
+                // P7
+                0xe0..=0xef => {
+                    let when = reader.uleb()?;
+                    match first & 0xf {
+                        0 => Descriptor::MemStackF { when, size: reader.uleb()?.wrapping_mul(16) },
+                        1 => Descriptor::MemStackV { when },
+                        _ => Descriptor::Other(first),
+                    }
+                }
+                // P8
+                0xf0 => {
+                    reader.skip(1)?;
+                    reader.uleb()?;
+                    Descriptor::Other(first)
+                }
+                // P9, P10
+                0xf1 | 0xff => {
+                    reader.skip(2)?;
+                    Descriptor::Other(first)
+                }
+                _ => { return None; }
+            },
+            _ => match first {
+                // B1
+                0x80..=0xbf => Descriptor::Other(first),
+                // B2
+                0xc0..=0xdf => Descriptor::Epilogue { when: reader.uleb()?, count: (first & 0x1f) as u64 },
+                // B3
+                0xe0 => {
+                    let when = reader.uleb()?;
+                    Descriptor::Epilogue { when, count: reader.uleb()? }
+                }
+                // B4
+                0xf0 | 0xf8 => {
+                    reader.uleb()?;
+                    Descriptor::Other(first)
+                }
+                _ => { return None; }
+            },
+        };
+        descriptors.push(descriptor);
+    }
+    Some(descriptors)
+}
+
+/// a memory stack frame allocation an unwind descriptor records.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StackAllocation {
+    /// the allocating instruction, in slots from the start of the function.
+    pub slot: u64,
+    /// the size of the frame in bytes, or `None` for a variable-size frame.
+    pub size: Option<u64>,
+}
+
+/// the memory stack frames `descriptors` allocate, from `mem_stack_f` and `mem_stack_v` records.
+pub fn stack_allocations(descriptors: &[Descriptor]) -> Vec<StackAllocation> {
+    let mut allocations = Vec::new();
+    // the start of the current region, and of the next.
+    let mut start = 0u64;
+    let mut next = 0u64;
+    for descriptor in descriptors {
+        match *descriptor {
+            Descriptor::Region { length, .. } => {
+                start = next;
+                next = next.wrapping_add(length);
+            }
+            Descriptor::MemStackF { when, size } => {
+                allocations.push(StackAllocation { slot: start.wrapping_add(when), size: Some(size) });
+            }
+            Descriptor::MemStackV { when } => {
+                allocations.push(StackAllocation { slot: start.wrapping_add(when), size: None });
+            }
+            _ => {}
+        }
+    }
+    allocations
+}
@@ -0,0 +1,388 @@
+//! constant propagation over general registers.
+//!
+//! `Values::analyze` propagates known register values forward through a `Cfg`, through `mov`,
+//! `movl`, `mov r=ip`, additions, `shladd`, bitwise operations, `dep` and `extr`, and loads from
+//! memory the image says is read-only. where paths join, a register keeps its value only if
+//! every path agrees on it.
+//!
+//! predicated writes are tracked by the predicate that guards them: after `(p6) mov r14=1`,
+//! `r14` is 1 for instructions qualified by `p6`, along branches taken on `p6`, and nowhere
+//! else, until `p6` is written again. only one guarding predicate is remembered per register,
+//! so complementary pairs (`(p6) mov r14=1` then `(p7) mov r14=2`) leave the register unknown
+//! where neither predicate holds.
+//!
+//! a call is assumed to follow the software conventions: on return, the scratch registers
+//! `r2`-`r3`, `r8`-`r11` and `r14`-`r31` and `gp` are unknown, and every other register keeps
+//! its value.
+//!
+//! `gp::resolve`, `indirect::resolve_calls`, and `switch::recover` take their register values
+//! from here, and follow values back to the instructions that computed them with
+//! `Values::definition`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Bundles, GPRegister, Instruction, Opcode, Operand, SlotAddress};
+use crate::cfg::{Block, Cfg, EdgeKind};
+use crate::image::{ByteOrder, Image};
+use crate::registers::{self, Location};
+
+/// what is known about a general register's value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Unknown,
+    Known(u64),
+    /// `value` where `predicate` is true and `otherwise` where it is false, each `None` if it is
+    /// not known. the two are never equal.
+    Guarded { predicate: u8, value: Option<u64>, otherwise: Option<u64> },
+}
+
+impl Value {
+    fn from_option(value: Option<u64>) -> Value {
+        value.map(Value::Known).unwrap_or(Value::Unknown)
+    }
+
+    fn guarded(predicate: u8, value: Option<u64>, otherwise: Option<u64>) -> Value {
+        if value == otherwise {
+            Value::from_option(value)
+        } else {
+            Value::Guarded { predicate, value, otherwise }
+        }
+    }
+
+    /// the value as read by an instruction qualified by predicate `qp`.
+    pub fn under(&self, qp: u8) -> Option<u64> {
+        match *self {
+            Value::Known(value) => Some(value),
+            Value::Guarded { predicate, value, .. } if predicate == qp && qp != 0 => value,
+            _ => None,
+        }
+    }
+
+    /// the value on paths where `predicate` is `holds`.
+    fn assuming(&self, predicate: u8, holds: bool) -> Value {
+        match *self {
+            Value::Guarded { predicate: p, value, otherwise } if p == predicate => {
+                Value::from_option(if holds { value } else { otherwise })
+            }
+            other => other,
+        }
+    }
+
+    fn meet(&self, other: &Value) -> Value {
+        if self == other { *self } else { Value::Unknown }
+    }
+}
+
+/// the general registers, as known at one point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct State {
+    registers: [Value; 128],
+}
+
+/// `r1`, and the scratch registers of the software conventions.
+fn clobbered_by_call(reg: u8) -> bool {
+    matches!(reg, 1..=3 | 8..=11 | 14..=31)
+}
+
+fn mask(len: u64) -> u64 {
+    if len >= 64 { !0 } else { (1 << len) - 1 }
+}
+
+impl State {
+    fn new(initial: &[(GPRegister, u64)]) -> State {
+        let mut registers = [Value::Unknown; 128];
+        for (reg, value) in initial.iter() {
+            registers[reg.0 as usize] = Value::Known(*value);
+        }
+        registers[0] = Value::Known(0);
+        State { registers }
+    }
+
+    /// record a write of `value` to `reg` by an instruction qualified by `qp`.
+    fn write(&mut self, reg: u8, qp: u8, value: Option<u64>) {
+        if reg == 0 {
+            return;
+        }
+        let old = &mut self.registers[reg as usize];
+        *old = if qp == 0 {
+            Value::from_option(value)
+        } else {
+            let otherwise = match old.assuming(qp, false) {
+                Value::Known(value) => Some(value),
+                _ => None,
+            };
+            Value::guarded(qp, value, otherwise)
+        };
+    }
+
+    /// apply the effects of `inst`, at `address`.
+    fn step<I: Image + ?Sized>(&mut self, address: SlotAddress, inst: &Instruction, image: &I) {
+        let qp = inst.predicate();
+        let read = |op: &Operand| match op {
+            Operand::GPRegister(reg) => self.registers[reg.0 as usize].under(qp),
+            Operand::ImmI64(imm) => Some(*imm as u64),
+            Operand::ImmU64(imm) => Some(*imm),
+            Operand::IP => Some(address.bundle()),
+            _ => None,
+        };
+        let binary = |a: &Operand, b: &Operand, f: fn(u64, u64) -> u64| Some(f(read(a)?, read(b)?));
+
+        // the value written to the target register, and to a post-incremented base register.
+        let mut result: Option<(u8, Option<u64>)> = None;
+        let mut increment: Option<(u8, Option<u64>)> = None;
+        let parts = inst.opcode().mnemonic_parts();
+        match (inst.opcode(), inst.operands()) {
+            (Opcode::Addl, [Operand::GPRegister(dest), a, b]) |
+            (Opcode::Adds, [Operand::GPRegister(dest), a, b]) |
+            (Opcode::Add, [Operand::GPRegister(dest), a, b]) => {
+                result = Some((dest.0, binary(a, b, u64::wrapping_add)));
+            }
+            (Opcode::Sub, [Operand::GPRegister(dest), a, b]) => {
+                result = Some((dest.0, binary(a, b, u64::wrapping_sub)));
+            }
+            (Opcode::And, [Operand::GPRegister(dest), a, b]) => {
+                result = Some((dest.0, binary(a, b, |a, b| a & b)));
+            }
+            (Opcode::Or, [Operand::GPRegister(dest), a, b]) => {
+                result = Some((dest.0, binary(a, b, |a, b| a | b)));
+            }
+            (Opcode::Xor, [Operand::GPRegister(dest), a, b]) => {
+                result = Some((dest.0, binary(a, b, |a, b| a ^ b)));
+            }
+            (Opcode::Mov, [Operand::GPRegister(dest), source]) |
+            (Opcode::Movl, [Operand::GPRegister(dest), source]) => {
+                result = Some((dest.0, read(source)));
+            }
+            (Opcode::Shladd, [Operand::GPRegister(dest), a, count, b]) => {
+                let value = (|| Some((read(a)? << read(count)?).wrapping_add(read(b)?)))();
+                result = Some((dest.0, value));
+            }
+            (Opcode::Dep_z, [Operand::GPRegister(dest), source, pos, len]) => {
+                let value = (|| Some((read(source)? & mask(read(len)?)) << read(pos)?))();
+                result = Some((dest.0, value));
+            }
+            (Opcode::Dep, [Operand::GPRegister(dest), source, target, pos, len]) => {
+                let value = (|| {
+                    let source = match source {
+                        // the `I14` form deposits a sign-extended one-bit immediate.
+                        Operand::ImmU64(imm) => if *imm != 0 { !0 } else { 0 },
+                        other => read(other)?,
+                    };
+                    let (pos, len) = (read(pos)?, read(len)?);
+                    let field = mask(len) << pos;
+                    Some((read(target)? & !field) | ((source << pos) & field))
+                })();
+                result = Some((dest.0, value));
+            }
+            (Opcode::Extr, [Operand::GPRegister(dest), source, pos, len]) |
+            (Opcode::Extr_u, [Operand::GPRegister(dest), source, pos, len]) => {
+                let signed = inst.opcode() == Opcode::Extr;
+                let value = (|| {
+                    let (pos, len) = (read(pos)?, read(len)?);
+                    let field = (read(source)? >> pos) & mask(len);
+                    if signed && len < 64 && field >> (len - 1) & 1 != 0 {
+                        Some(field | !mask(len))
+                    } else {
+                        Some(field)
+                    }
+                })();
+                result = Some((dest.0, value));
+            }
+            (_, [Operand::GPRegister(dest), Operand::Memory(base), rest @ ..]) if matches!(parts.base, "ld1" | "ld2" | "ld4" | "ld8") => {
+                let address = read(&Operand::GPRegister(*base));
+                let size = parts.size().unwrap_or(8) as usize;
+                let loaded = address.filter(|address| image.is_read_only(*address)).and_then(|address| {
+                    let bytes = image.bytes_at(address);
+                    if bytes.len() < size {
+                        return None;
+                    }
+                    let mut word = [0u8; 8];
+                    match image.byte_order() {
+                        ByteOrder::Little => word[..size].copy_from_slice(&bytes[..size]),
+                        ByteOrder::Big => word[8 - size..].copy_from_slice(&bytes[..size]),
+                    }
+                    Some(image.byte_order().read_u64(&word))
+                });
+                result = Some((dest.0, loaded));
+                if let Some(inc) = rest.first() {
+                    increment = Some((base.0, binary(&Operand::GPRegister(*base), inc, u64::wrapping_add)));
+                }
+            }
+            _ => {}
+        }
+
+        for write in registers::accesses(inst).writes {
+            match write {
+                Location::GPRegister(reg) => {
+                    let value = [result, increment].iter().flatten()
+                        .find(|(written, _)| *written == reg.0)
+                        .and_then(|(_, value)| *value);
+                    self.write(reg.0, qp, value);
+                }
+                Location::PredicateRegister(p) => {
+                    for value in self.registers.iter_mut() {
+                        if let Value::Guarded { predicate, .. } = value {
+                            if *predicate == p.0 {
+                                *value = Value::Unknown;
+                            }
+                        }
+                    }
+                }
+                Location::PR => {
+                    for value in self.registers.iter_mut() {
+                        if let Value::Guarded { .. } = value {
+                            *value = Value::Unknown;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn meet(&self, other: &State) -> State {
+        let mut registers = self.registers;
+        for (mine, theirs) in registers.iter_mut().zip(other.registers.iter()) {
+            *mine = mine.meet(theirs);
+        }
+        State { registers }
+    }
+}
+
+/// known register values throughout a graph, from `Values::analyze`.
+pub struct Values<'a, I: Image + ?Sized> {
+    bundles: &'a Bundles,
+    cfg: &'a Cfg,
+    image: &'a I,
+    /// the state at the start of each reachable block.
+    entries: BTreeMap<SlotAddress, State>,
+}
+
+impl<'a, I: Image + ?Sized> Values<'a, I> {
+    /// propagate constants through `cfg`, a graph of `bundles`. the first block, and blocks that
+    /// no edge in the graph leads to, are entered with the registers in `initial` known,
+    /// typically `r1` if `gp` is known, and every other register unknown. edges back to the first
+    /// block meet with that.
+    pub fn analyze(bundles: &'a Bundles, cfg: &'a Cfg, image: &'a I, initial: &[(GPRegister, u64)]) -> Values<'a, I> {
+        let mut values = Values { bundles, cfg, image, entries: BTreeMap::new() };
+        let targets: BTreeSet<SlotAddress> = cfg.blocks().flat_map(|block| block.edges.iter().map(|edge| edge.target)).collect();
+        let first = cfg.blocks().next().map(|block| block.start);
+        let mut worklist = BTreeSet::new();
+        for block in cfg.blocks().filter(|block| Some(block.start) == first || !targets.contains(&block.start)) {
+            values.entries.insert(block.start, State::new(initial));
+            worklist.insert(block.start);
+        }
+
+        while let Some(start) = worklist.iter().next().cloned() {
+            worklist.remove(&start);
+            let block = cfg.block(start).expect("worklist holds blocks");
+            let exit = values.exit(block);
+            let last = bundles.instruction_at(block.last).expect("block is in range");
+            for edge in block.edges.iter() {
+                if cfg.block(edge.target).is_none() {
+                    continue;
+                }
+                let mut state = exit.clone();
+                let is_call = matches!(last.opcode(), Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh);
+                if is_call {
+                    for reg in (0..128).filter(|reg| clobbered_by_call(*reg)) {
+                        state.write(reg, last.predicate(), None);
+                    }
+                } else if edge.predicate != 0 {
+                    let holds = edge.kind != EdgeKind::FallThrough;
+                    for value in state.registers.iter_mut() {
+                        *value = value.assuming(edge.predicate, holds);
+                    }
+                }
+                let merged = match values.entries.get(&edge.target) {
+                    Some(old) => old.meet(&state),
+                    None => state,
+                };
+                if values.entries.get(&edge.target) != Some(&merged) {
+                    values.entries.insert(edge.target, merged);
+                    worklist.insert(edge.target);
+                }
+            }
+        }
+        values
+    }
+
+    /// the state after the last instruction of `block`.
+    fn exit(&self, block: &Block) -> State {
+        let mut state = self.entries[&block.start].clone();
+        for (address, inst) in block.instructions(self.bundles) {
+            state.step(address, inst, self.image);
+        }
+        state
+    }
+
+    /// the state before the instruction at `address`, or after it if `after`. `None` if the
+    /// instruction is not in a block reachable in the graph.
+    fn state_at(&self, address: SlotAddress, after: bool) -> Option<State> {
+        let block = self.cfg.block_containing(address)?;
+        let mut state = self.entries.get(&block.start)?.clone();
+        for (at, inst) in block.instructions(self.bundles) {
+            if at > address || (at == address && !after) {
+                break;
+            }
+            state.step(at, inst, self.image);
+        }
+        Some(state)
+    }
+
+    /// what is known about `reg` just before the instruction at `address` executes. `Unknown` if
+    /// the instruction is not in a block reachable in the graph.
+    pub fn state(&self, address: SlotAddress, reg: GPRegister) -> Value {
+        self.state_at(address, false).map(|state| state.registers[reg.0 as usize]).unwrap_or(Value::Unknown)
+    }
+
+    /// the value of `reg` as read by the instruction at `address`, if it is known.
+    pub fn value(&self, address: SlotAddress, reg: GPRegister) -> Option<u64> {
+        let qp = self.bundles.instruction_at(address)?.predicate();
+        self.state(address, reg).under(qp)
+    }
+
+    /// the value of `reg` just after the instruction at `address` executes, as read by an
+    /// instruction with the same qualifying predicate, if it is known.
+    pub fn value_after(&self, address: SlotAddress, reg: GPRegister) -> Option<u64> {
+        let qp = self.bundles.instruction_at(address)?.predicate();
+        self.state_at(address, true)?.registers[reg.0 as usize].under(qp)
+    }
+
+    /// the instructions before `address` on the single path leading there, latest first: back
+    /// through its block, then through each block's only predecessor. the path ends at a call,
+    /// which is not included, and where control joins.
+    pub(crate) fn path_to(&self, address: SlotAddress) -> impl Iterator<Item=(SlotAddress, &'a Instruction)> + 'a {
+        let (bundles, cfg) = (self.bundles, self.cfg);
+        let mut block = cfg.block_containing(address);
+        let mut pending: Vec<(SlotAddress, &'a Instruction)> = match block {
+            Some(block) => block.instructions(bundles).take_while(|(at, _)| *at < address).collect(),
+            None => Vec::new(),
+        };
+        let mut visited: BTreeSet<SlotAddress> = block.iter().map(|block| block.start).collect();
+        core::iter::from_fn(move || loop {
+            if let Some((at, inst)) = pending.pop() {
+                if let Opcode::Br_call | Opcode::Brl_call_bwh_ph_dh = inst.opcode() {
+                    block = None;
+                    pending.clear();
+                    return None;
+                }
+                return Some((at, inst));
+            }
+            block = match cfg.predecessors(block?.start).as_slice() {
+                [only] if visited.insert(*only) => cfg.block(*only),
+                _ => None,
+            };
+            pending = block?.instructions(bundles).collect();
+        })
+    }
+
+    /// the instruction that wrote the value of `location` read by the instruction at `address`,
+    /// if there is one on the path from `path_to`. a write under a different predicate might not
+    /// have happened, and isn't taken to be it.
+    pub(crate) fn definition(&self, address: SlotAddress, location: Location) -> Option<(SlotAddress, &'a Instruction)> {
+        let qp = self.bundles.instruction_at(address)?.predicate();
+        let (at, inst) = self.path_to(address).find(|(_, inst)| registers::accesses(inst).writes.contains(&location))?;
+        if inst.predicate() == 0 || inst.predicate() == qp { Some((at, inst)) } else { None }
+    }
+}
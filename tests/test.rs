@@ -2488,3 +2488,1244 @@ fn test_predication_not_allowed() {
     assert_eq!(format!("{}", inst), expected);
     assert!(!inst.well_defined());
 }
+
+// fields the decoder skips over should still be visible to `canonical::check`.
+#[test]
+fn test_noncanonical_fields() {
+    use yaxpeax_ia64::canonical::{self, Irregularity, IrregularityKind};
+    let decoder = InstDecoder::default();
+
+    // compiler output is canonical.
+    let data = [0x0a, 0x88, 0x00, 0x22, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0xc0, 0x21, 0x71, 0xdc, 0x4f];
+    assert_eq!(canonical::check(&data[..]).unwrap(), vec![]);
+
+    // `M1` ignores bits 13..20, so this decodes identically but is not what an assembler emits.
+    let expected = "[MMI] ld1 r17=[r17];; nop.m 0x0; dep r14=r18,r14,0x0,0x8";
+    let data = [0x0a, 0x88, 0x04, 0x22, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0xc0, 0x21, 0x71, 0xdc, 0x4f];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), expected);
+    assert_eq!(canonical::check(&data[..]).unwrap(), vec![
+        Irregularity { slot: 0, kind: IrregularityKind::IgnoredBits, bits: 1 << 13 },
+    ]);
+
+    // `A7` compares against `r0`, but the `r2` field here is r35. this used to panic the decoder.
+    let expected = "[MIB] mov r1=r40; cmp.gt.or p6,p7=r0,r37; (p06) br.cond.dptk.few $-0x40";
+    let data = [0x10, 0x08, 0x00, 0x50, 0x00, 0x21, 0x60, 0x18, 0x95, 0x0e, 0x6c, 0x03, 0xc0, 0xff, 0xff, 0x4a];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), expected);
+    assert_eq!(canonical::check(&data[..]).unwrap(), vec![
+        Irregularity { slot: 1, kind: IrregularityKind::ReservedBits, bits: 35 << 13 },
+    ]);
+
+    // `B5` has no `x6` field, so bits 16..32 are all ignored.
+    let expected = "[MIB] ld8 r1=[r14]; mov b6=r15; br.call.sptk.many b0=b6;;";
+    let data = [0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x60, 0x78, 0x04, 0x80, 0x03, 0x00, 0x68, 0x00, 0x88, 0x10];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), expected);
+    assert_eq!(canonical::check(&data[..]).unwrap(), vec![
+        Irregularity { slot: 2, kind: IrregularityKind::IgnoredBits, bits: 1 << 28 },
+    ]);
+
+    // `B9` ignores bit 26, as `M37`, `I19`, `F15`, and `X1` do.
+    let expected = "[MIB] nop.m 0x0; nop.i 0x0; nop.b 0x0;;";
+    let data = [0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x20];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), expected);
+    assert_eq!(canonical::check(&data[..]).unwrap(), vec![
+        Irregularity { slot: 2, kind: IrregularityKind::IgnoredBits, bits: 1 << 26 },
+    ]);
+
+    assert!(canonical::check(&data[..8]).is_err());
+}
+
+#[test]
+fn test_slot_address() {
+    use yaxpeax_ia64::SlotAddress;
+
+    let addr: SlotAddress = "0xa000000100012342".parse().unwrap();
+    assert_eq!(addr.bundle(), 0xa000000100012340);
+    assert_eq!(addr.slot(), 2);
+    assert_eq!(format!("{}", addr), "0xa000000100012340.2");
+    assert_eq!(format!("{:#x}", addr), "0xa000000100012342");
+    assert_eq!("a000000100012340.2".parse::<SlotAddress>(), Ok(addr));
+    assert_eq!("0xa000000100012300+0x42".parse::<SlotAddress>(), Ok(addr));
+    assert!("0xa000000100012343".parse::<SlotAddress>().is_err());
+    assert!("0xa000000100012341.1".parse::<SlotAddress>().is_err());
+    assert!("0xa000000100012340.3".parse::<SlotAddress>().is_err());
+
+    assert_eq!(addr + 1, SlotAddress::new(0xa000000100012350, 0).unwrap());
+    assert_eq!(addr - 3, SlotAddress::new(0xa000000100012330, 2).unwrap());
+    assert_eq!((addr + 7) - addr, 7);
+    assert_eq!(SlotAddress::new(0, 0).unwrap() - 1, SlotAddress::from_linear(0xffff_ffff_ffff_fff2).unwrap());
+
+    // the second bundle here is an `MLX` bundle, where slot 2 is the `X` half of `movl`.
+    let data = [
+        0x01, 0x38, 0x31, 0x12, 0x80, 0x05, 0x60, 0x02, 0x00, 0x62, 0x00, 0x00, 0x05, 0x08, 0x00, 0x84,
+        0x05, 0x10, 0x15, 0x0a, 0x80, 0xc5, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00, 0x84, 0xf7, 0xaf, 0x6f,
+    ];
+    let decoder = InstDecoder::default();
+    let base = 0x4000_0000_0019_f500;
+    let movl = SlotAddress::new(base + 0x10, 1).unwrap();
+    let inst = decoder.decode_slot(&data[..], base, movl).unwrap();
+    assert_eq!(format!("{}", inst), "movl r32=0xfffffffffffff5f8");
+    assert_eq!(decoder.decode_slot(&data[..], base, movl + 1).unwrap(), inst);
+    assert_eq!(format!("{}", decoder.decode_slot(&data[..], base, movl - 2).unwrap()), "mov r40=r1");
+    assert!(decoder.decode_slot(&data[..], base, movl + 2).is_err());
+
+    let bundle = decoder.decode_bundle_at(&data[..], base, movl).unwrap();
+    assert_eq!(movl.canonical(bundle.bundle_tag()), movl);
+    assert_eq!((movl + 1).canonical(bundle.bundle_tag()), movl);
+    assert_eq!(movl.next_instruction(bundle.bundle_tag()), SlotAddress::new(base + 0x20, 0).unwrap());
+    let addresses: Vec<SlotAddress> = bundle.instructions_at(base + 0x10).map(|(addr, _)| addr).collect();
+    assert_eq!(addresses, vec![movl - 1, movl]);
+}
+
+#[test]
+fn test_debug_support() {
+    use yaxpeax_ia64::SlotAddress;
+    use yaxpeax_ia64::debug::{BranchState, PatchError, Successor, SuccessorKind, insert_break, successors};
+
+    let decoder = InstDecoder::default();
+
+    let data = [0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00, 0xf0, 0xff, 0xff, 0xc8];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), "[MLX] nop.m 0x0; brl.few $-0x10;;");
+    let data = [0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0xd1];
+    let mut reader = U8Reader::new(&data[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), "[MLX] nop.m 0x0; brl.call.spnt.few b0=$+0x100");
+
+    let patched = insert_break(&data[..], 2, 0x2000_0000_0012_3456).unwrap();
+    let mut reader = U8Reader::new(&patched[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), "[MLX] nop.m 0x0; break.x 0x2000000000123456");
+    assert_eq!(insert_break(&data[..], 0, 1 << 21), Err(PatchError::ImmediateTooLarge));
+    assert_eq!(insert_break(&data[..], 3, 0), Err(PatchError::BadSlot));
+
+    // chk.s.m, nop.i, and a predicated br.cond.
+    let data = [0x11, 0x20, 0x14, 0x00, 0x40, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0xe0, 0xff, 0xff, 0x49];
+    let mut reader = U8Reader::new(&data[..]);
+    let bundle = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", bundle), "[MIB] chk.s.m.int r5,$+0x40; nop.i 0x0; (p06) br.cond.spnt.few $-0x20;;");
+    let patched = insert_break(&data[..], 1, 0x12345).unwrap();
+    let mut reader = U8Reader::new(&patched[..]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", inst), "[MIB] chk.s.m.int r5,$+0x40; break.i 0x12345; (p06) br.cond.spnt.few $-0x20;;");
+
+    let state = BranchState::default();
+    let at = |slot| SlotAddress::new(0x1000, slot).unwrap();
+    assert_eq!(successors(&bundle, at(0), &state), vec![
+        Successor { kind: SuccessorKind::FallThrough, target: Some(at(1)) },
+        Successor { kind: SuccessorKind::Recovery, target: SlotAddress::new(0x1040, 0) },
+    ]);
+    assert_eq!(successors(&bundle, at(2), &state), vec![
+        Successor { kind: SuccessorKind::FallThrough, target: SlotAddress::new(0x1010, 0) },
+        Successor { kind: SuccessorKind::Branch, target: SlotAddress::new(0xfe0, 0) },
+    ]);
+
+    let data = [0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00];
+    let mut reader = U8Reader::new(&data[..]);
+    let bundle = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", bundle), "[MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;");
+    assert_eq!(successors(&bundle, at(2), &state), vec![
+        Successor { kind: SuccessorKind::Return, target: None },
+    ]);
+    let mut state = BranchState::default();
+    state.branch_registers[0] = Some(0x2000);
+    state.interruption_ip = SlotAddress::new(0x3000, 1);
+    assert_eq!(successors(&bundle, at(2), &state), vec![
+        Successor { kind: SuccessorKind::Return, target: SlotAddress::new(0x2000, 0) },
+    ]);
+
+    let data = [0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00];
+    let mut reader = U8Reader::new(&data[..]);
+    let bundle = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", bundle), "[MIB] nop.m 0x0; nop.i 0x0; rfi;;");
+    assert_eq!(successors(&bundle, at(2), &state), vec![
+        Successor { kind: SuccessorKind::InterruptionReturn, target: SlotAddress::new(0x3000, 1) },
+    ]);
+}
+
+#[test]
+fn test_instruction_groups() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::group::GroupEnd;
+
+    // `MII;;`, `MLX;;`, `MIB` ending in a predicated branch, then `M;MI`.
+    let data = [
+        0x01, 0x38, 0x31, 0x12, 0x80, 0x05, 0x60, 0x02, 0x00, 0x62, 0x00, 0x00, 0x05, 0x08, 0x00, 0x84,
+        0x05, 0x10, 0x15, 0x0a, 0x80, 0xc5, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00, 0x84, 0xf7, 0xaf, 0x6f,
+        0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0xe0, 0xff, 0xff, 0x49,
+        0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+    ];
+    let decoder = InstDecoder::default();
+    let base = 0x4000_0000_0019_f500;
+    let bundles = Bundles::decode(&decoder, &data[..], base).unwrap();
+    assert_eq!(bundles.instructions().count(), 11);
+
+    let groups: Vec<(Vec<u64>, GroupEnd)> = bundles.groups().map(|group| {
+        (group.instructions().iter().map(|(addr, _)| addr.to_linear() - base).collect(), group.end())
+    }).collect();
+    assert_eq!(groups, vec![
+        (vec![0x00, 0x01, 0x02], GroupEnd::Stop),
+        (vec![0x10, 0x11], GroupEnd::Stop),
+        (vec![0x20, 0x21, 0x22], GroupEnd::Branch),
+        (vec![0x30], GroupEnd::Stop),
+        (vec![0x31, 0x32], GroupEnd::EndOfInput),
+    ]);
+
+    let movl = bundles.groups().nth(1).unwrap().instructions()[1].1;
+    assert_eq!(format!("{}", movl), "movl r32=0xfffffffffffff5f8");
+    assert_eq!(bundles.instruction_at(SlotAddress::new(base + 0x10, 2).unwrap()), Some(movl));
+}
+
+#[test]
+fn test_dispersal() {
+    use yaxpeax_ia64::dispersal::{Issue, Port, PortKind, Processor, Split, SplitReason, disperse};
+
+    let decoder = InstDecoder::default();
+    let decode = |data: &[u8]| decoder.decode(&mut U8Reader::new(data)).unwrap();
+    let mii = decode(&[0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00]);
+    let mi_i = decode(&[0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00]);
+    let tbits = decode(&[0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x60, 0x10, 0x10, 0x0e, 0x28, 0x00, 0x41, 0x28, 0x24, 0x50]);
+    assert_eq!(format!("{}", tbits), "[MII] nop.m 0x0; tbit.z p6,p7=r4,0x1; tbit.z p8,p9=r5,0x2;;");
+    let loads = decode(&[0x08, 0x40, 0x00, 0x08, 0x18, 0x10, 0x80, 0x00, 0x10, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00]);
+    assert_eq!(format!("{}", loads), "[MMI] ld8 r8=[r4]; ld8 r8=[r4]; nop.i 0x0");
+
+    // four `I`-slot instructions, `nop`s or not, is two too many.
+    let dispersal = disperse(Processor::Itanium2, &mii, &mii);
+    assert_eq!(dispersal.issued, vec![
+        Issue { bundle: 0, slot: 0, port: Port::M0 },
+        Issue { bundle: 0, slot: 1, port: Port::I0 },
+        Issue { bundle: 0, slot: 2, port: Port::I1 },
+        Issue { bundle: 1, slot: 0, port: Port::M1 },
+    ]);
+    assert_eq!(dispersal.split, Some(Split { bundle: 1, slot: 1, reason: SplitReason::NoPort(PortKind::I) }));
+
+    // `tbit` only issues on `I0`.
+    let dispersal = disperse(Processor::Itanium2, &tbits, &mii);
+    assert_eq!(dispersal.split, Some(Split { bundle: 0, slot: 2, reason: SplitReason::RestrictedPort(&[Port::I0]) }));
+
+    // ports are taken in order: the `nop.i` takes `I0`, which leaves the first `tbit` none.
+    let mib = decode(&[0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20]);
+    assert_eq!(format!("{}", mib), "[MIB] nop.m 0x0; nop.i 0x0; nop.b 0x0");
+    let dispersal = disperse(Processor::Itanium2, &mib, &tbits);
+    assert_eq!(dispersal.issued, vec![
+        Issue { bundle: 0, slot: 0, port: Port::M0 },
+        Issue { bundle: 0, slot: 1, port: Port::I0 },
+        Issue { bundle: 0, slot: 2, port: Port::B0 },
+        Issue { bundle: 1, slot: 0, port: Port::M1 },
+    ]);
+    assert_eq!(dispersal.split, Some(Split { bundle: 1, slot: 1, reason: SplitReason::RestrictedPort(&[Port::I0]) }));
+
+    let dispersal = disperse(Processor::Itanium2, &mi_i, &mii);
+    assert_eq!(dispersal.issued.len(), 2);
+    assert_eq!(dispersal.split, Some(Split { bundle: 0, slot: 2, reason: SplitReason::Stop }));
+
+    // only two load ports, until the 9500 series.
+    let dispersal = disperse(Processor::Itanium9300, &loads, &loads);
+    assert_eq!(dispersal.split, Some(Split { bundle: 1, slot: 0, reason: SplitReason::RestrictedPort(&[Port::M0, Port::M1]) }));
+    let dispersal = disperse(Processor::Itanium9500, &loads, &loads);
+    assert_eq!(dispersal.issued.len(), 6);
+    assert_eq!(dispersal.split, None);
+}
+
+#[test]
+fn test_latency_estimate() {
+    use yaxpeax_ia64::{Bundles, GPRegister, SlotAddress};
+    use yaxpeax_ia64::dispersal::Processor;
+    use yaxpeax_ia64::latency::{CacheLevel, Latencies, LatencyClass, estimate};
+    use yaxpeax_ia64::registers::{Location, accesses};
+
+    // ld8 r8=[r4];; add r9=r8,r8;;
+    let data = [
+        0x01, 0x40, 0x00, 0x08, 0x18, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        0x01, 0x48, 0x20, 0x10, 0x00, 0x20, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+    ];
+    let decoder = InstDecoder::default();
+    let block = Bundles::decode(&decoder, &data[..], 0x1000).unwrap();
+    let ld = SlotAddress::new(0x1000, 0).unwrap();
+    let add = SlotAddress::new(0x1010, 0).unwrap();
+    assert_eq!(format!("{}", block.instruction_at(add).unwrap()), "add r9=r8,r8");
+
+    let load = block.instruction_at(ld).unwrap();
+    assert_eq!(load.opcode().latency_class(), LatencyClass::IntegerLoad);
+    let ld_accesses = accesses(load);
+    assert_eq!(ld_accesses.reads, vec![Location::GPRegister(GPRegister(4))]);
+    assert_eq!(ld_accesses.writes, vec![Location::GPRegister(GPRegister(8))]);
+
+    let l1 = estimate(&block, Processor::Itanium2, &Latencies::itanium2(CacheLevel::L1));
+    assert_eq!(l1.issue_cycles, 2);
+    assert_eq!(l1.cycles, 2);
+    assert_eq!(l1.critical_path, vec![ld, add]);
+
+    let l2 = estimate(&block, Processor::Itanium2, &Latencies::itanium2(CacheLevel::L2));
+    assert_eq!(l2.schedule[3].address, add);
+    assert_eq!(l2.schedule[3].issue, 5);
+    assert_eq!(l2.cycles, 6);
+    assert_eq!(l2.critical_path, vec![ld, add]);
+}
+
+#[test]
+fn test_compare_semantics() {
+    use yaxpeax_ia64::{Opcode, PredicateRegister};
+    use yaxpeax_ia64::predicate::{CompareType, PredicateEffect, TargetRelation, predicate_write};
+
+    let decoder = InstDecoder::default();
+    let data = [0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x70, 0x00, 0x38, 0x8c, 0x73, 0x03, 0x80, 0x00, 0x00, 0x42];
+    let mut reader = U8Reader::new(&data[..]);
+    let bundle = decoder.decode(&mut reader).unwrap();
+    assert_eq!(format!("{}", bundle), "[MIB] nop.m 0x0; cmp4.eq.or.andcm p7,p6=0x0,r14; (p06) br.cond.dptk.few $+0x80");
+    let write = predicate_write(&bundle.instructions()[1]).unwrap();
+    assert_eq!(write.qp, PredicateRegister(0));
+    assert_eq!((write.p1, write.p2), (PredicateRegister(7), PredicateRegister(6)));
+    assert_eq!(write.semantics.compare_type, CompareType::OrAndcm);
+    assert_eq!(write.semantics.when_true, (PredicateEffect::SetIfResult, PredicateEffect::ClearIfResult));
+    assert_eq!(write.semantics.when_nat, Some((PredicateEffect::Unchanged, PredicateEffect::Unchanged)));
+    assert_eq!(write.semantics.relation, TargetRelation::OppositeUpdate);
+    assert!(predicate_write(&bundle.instructions()[2]).is_none());
+
+    let unc = Opcode::Cmp_lt_unc.compare_semantics().unwrap();
+    assert_eq!(unc.when_false, (PredicateEffect::Clear, PredicateEffect::Clear));
+    assert_eq!(unc.relation, TargetRelation::Complementary);
+    let and = Opcode::Tbit_z_and.compare_semantics().unwrap();
+    assert!(!and.when_true.0.apply(true, false));
+    assert!(and.when_true.1.apply(true, true));
+    assert_eq!(and.when_nat, Some((PredicateEffect::Clear, PredicateEffect::Clear)));
+    assert_eq!(Opcode::Tnat_z_or.compare_semantics().unwrap().when_nat, None);
+    assert_eq!(Opcode::Fcmp_unord_unc.compare_type(), Some(CompareType::Unc));
+    assert_eq!(Opcode::Tf_nz_or.compare_type(), Some(CompareType::Or));
+    assert_eq!(Opcode::Add.compare_type(), None);
+}
+
+#[test]
+fn test_predicate_relations() {
+    use yaxpeax_ia64::{Bundles, PredicateRegister};
+    use yaxpeax_ia64::predicate::analyze;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x60, 0x40, 0x24, 0x0e, 0x70, 0x03, 0xa9, 0x58, 0x24, 0xe0,
+        0x01, 0x71, 0x08, 0x06, 0x00, 0xe0, 0xe1, 0x20, 0x14, 0x00, 0xc0, 0xc4, 0x61, 0x38, 0x00, 0x80,
+        0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x60, 0x40, 0x28, 0x0e, 0x70, 0xc3, 0x41, 0x28, 0x00, 0x80,
+    ];
+    let bundles = Bundles::decode(&decoder, &data[..], 0x4000).unwrap();
+    assert_eq!(format!("{}", bundles.bundles()[0]), "[MII] nop.m 0x0; cmp.eq p6,p7=r8,r9; (p06) cmp.eq.unc p8,p9=r10,r11");
+    assert_eq!(format!("{}", bundles.bundles()[1]), "[MII] (p08) add r14=r2,r3; (p07) add r14=r4,r5; (p09) add r14=r6,r7;;");
+
+    let block = analyze(bundles.instructions().map(|(_, inst)| inst));
+    let p = PredicateRegister;
+    let before_adds = block.before(3).unwrap();
+    assert!(before_adds.complementary(p(6), p(7)));
+    assert!(!before_adds.complementary(p(8), p(9)));
+    assert!(before_adds.disjoint(p(8), p(9)));
+    assert!(before_adds.implies(p(9), p(6)));
+    assert!(before_adds.disjoint(p(7), p(9)));
+    assert!(!before_adds.disjoint(p(6), p(8)));
+    assert!(before_adds.implies(p(6), p(0)));
+
+    // the three writes of r14 are mutually exclusive.
+    assert!(!block.may_both_execute(3, 4));
+    assert!(!block.may_both_execute(4, 5));
+    assert!(!block.may_both_execute(3, 5));
+    // ... but `p6` is rewritten before the last one.
+    assert!(block.may_both_execute(4, 8));
+    assert!(block.may_both_execute(0, 3));
+    let before_last = block.before(8).unwrap();
+    assert!(before_last.complementary(p(7), p(6)));
+    assert!(!before_last.implies(p(8), p(6)));
+}
+
+#[test]
+fn test_lift() {
+    use yaxpeax_ia64::Bundles;
+    use yaxpeax_ia64::lift::lift;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        0x11, 0x18, 0x01, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x18, 0x5c, 0xe8, 0x58,
+        0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0xe0, 0x40, 0x90, 0x30, 0x28, 0x20, 0x05, 0x00, 0x01, 0x84,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x60, 0x40, 0x24, 0x0e, 0x70, 0x03, 0xa9, 0x58, 0x24, 0xe0,
+        0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x70, 0x00, 0x38, 0x8c, 0x73, 0x03, 0x80, 0x00, 0x00, 0x42,
+    ];
+    let bundles = Bundles::decode(&decoder, &data[..], 0x200000).unwrap();
+    let lifted: Vec<Vec<String>> = bundles.instructions().map(|(address, inst)| {
+        lift(inst, address).iter().map(|statement| statement.to_string()).collect()
+    }).collect();
+
+    // mov r35=r0; nop.i 0x0; br.call.sptk.many b0=$-0x17a3f0
+    assert_eq!(lifted[0], ["r35 = 0x0", "r35.nat = 0x0"]);
+    assert!(lifted[1].is_empty());
+    assert_eq!(lifted[2], ["b0 = 0x200010", "ar.pfs = undefined", "br.call 0x85c10"]);
+    // ld8 r14=[r36],0x8
+    assert_eq!(lifted[4], ["r14 = load8 [r36]", "r14.nat = 0x0", "r36 = add r36, 0x8"]);
+    // (p06) cmp.eq.unc p8,p9=r10,r11
+    assert_eq!(lifted[8], [
+        "t0 = or r10.nat, r11.nat",
+        "t1 = eq r10, r11",
+        "t2 = xor t0, 0x1",
+        "t3 = and t1, t2",
+        "t4 = xor t1, 0x1",
+        "t5 = and t4, t2",
+        "t6 = and t3, p6",
+        "t7 = and t5, p6",
+        "p8 = t6",
+        "p9 = t7",
+    ]);
+    // cmp4.eq.or.andcm p7,p6=0x0,r14; (p06) br.cond.dptk.few $+0x80
+    assert_eq!(lifted[10], [
+        "t0 = extr r14, 0, 32",
+        "t1 = eq 0x0, t0",
+        "t2 = xor r14.nat, 0x1",
+        "t3 = and t1, t2",
+        "(t3) p7 = 0x1",
+        "(t3) p6 = 0x0",
+    ]);
+    assert_eq!(lifted[11], ["(p6) br 0x2000b0"]);
+
+    // (p01) cmp.eq p1,p2=r8,r9: writing p1 mustn't decide whether p2 is written.
+    let data = [0x21, 0x08, 0x20, 0x12, 0x02, 0x38, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00];
+    let bundles = Bundles::decode(&decoder, &data[..], 0x200000).unwrap();
+    let (address, inst) = bundles.instructions().next().unwrap();
+    let lifted: Vec<String> = lift(inst, address).iter().map(|statement| statement.to_string()).collect();
+    assert_eq!(lifted, [
+        "t0 = or r8.nat, r9.nat",
+        "t1 = eq r8, r9",
+        "t2 = xor t0, 0x1",
+        "t3 = and t1, t2",
+        "t4 = xor t1, 0x1",
+        "t5 = and t4, t2",
+        "t6 = p1",
+        "(t6) p1 = t3",
+        "(t6) p2 = t5",
+    ]);
+}
+
+#[test]
+fn test_opcode_metadata() {
+    use yaxpeax_ia64::Opcode;
+    use yaxpeax_ia64::metadata::Unit;
+
+    assert!(Opcode::Rfi.flags().privileged);
+    assert!(Opcode::Itr_d.flags().privileged);
+    assert!(Opcode::Ssm.flags().privileged);
+    assert!(!Opcode::Sum.flags().privileged);
+    assert!(Opcode::Srlz_i.flags().serializing);
+    assert!(Opcode::Mf_a.flags().fence);
+    let sa = Opcode::Ld8_sa.flags();
+    assert!(sa.speculative && sa.advanced && !sa.may_fault);
+    assert!(Opcode::Ld8_c_clr.flags().may_fault);
+    assert!(Opcode::Fetchadd8_acq.flags().atomic);
+    assert!(!Opcode::Ld16.flags().atomic);
+    assert!(Opcode::White.flags().hint);
+    assert!(Opcode::Nop_x.flags().hint);
+    assert!(!Opcode::Lfetch.flags().may_fault);
+    assert!(Opcode::Lfetch_fault.flags().may_fault);
+
+    assert_eq!(Opcode::Cmp4_lt_unc.unit(), Some(Unit::A));
+    assert_eq!(Opcode::Tbit_z.unit(), Some(Unit::I));
+    assert_eq!(Opcode::Fcmp_eq.unit(), Some(Unit::F));
+    assert_eq!(Opcode::Brl_call_bwh_ph_dh.unit(), Some(Unit::LX));
+    assert_eq!(Opcode::Mov.unit(), None);
+    assert_eq!(Opcode::Ld8_sa.instruction_class(), Some("ld-sa"));
+    assert_eq!(Opcode::Cmp4_ge_or_andcm.instruction_class(), Some("cmp4"));
+    assert_eq!(Opcode::Fmpy_s.instruction_class(), Some("fma"));
+    assert_eq!(Opcode::Mov_m.instruction_class(), None);
+
+    let decoder = InstDecoder::default();
+    // [MII] alloc r39=ar.pfs,12,9,0; mov r38=b0; mov r40=r1;;
+    let data = [0x01, 0x38, 0x31, 0x12, 0x80, 0x05, 0x60, 0x02, 0x00, 0x62, 0x00, 0x00, 0x05, 0x08, 0x00, 0x84];
+    let bundle = decoder.decode(&mut U8Reader::new(&data[..])).unwrap();
+    let mov_b0 = &bundle.instructions()[1];
+    assert_eq!(mov_b0.unit(), Some(Unit::I));
+    assert_eq!(mov_b0.instruction_class(), Some("mov-from-BR"));
+    assert!(!mov_b0.flags().privileged);
+    assert_eq!(bundle.instructions()[0].instruction_class(), Some("alloc"));
+    let mov_r1 = &bundle.instructions()[2];
+    assert_eq!(mov_r1.unit(), Some(Unit::A));
+    assert_eq!(mov_r1.instruction_class(), Some("adds"));
+    assert!(!mov_r1.flags().may_fault);
+}
+
+#[test]
+fn test_mnemonic_parts() {
+    use yaxpeax_ia64::Opcode;
+    use yaxpeax_ia64::metadata::Unit;
+    use yaxpeax_ia64::mnemonic::{Completer, Ordering, Precision, Relation, Speculation};
+    use yaxpeax_ia64::predicate::CompareType;
+
+    let parts = Opcode::Ld8_c_clr_acq.mnemonic_parts();
+    assert_eq!(parts.base, "ld8");
+    assert_eq!(parts.completers, vec![
+        Completer::Size(8),
+        Completer::Speculation(Speculation::CheckClear),
+        Completer::Ordering(Ordering::Acquire),
+    ]);
+    let parts = Opcode::Cmp4_lt_or_andcm.mnemonic_parts();
+    assert_eq!(parts.base, "cmp4");
+    assert_eq!(parts.size(), Some(4));
+    assert!(parts.has(Completer::Relation(Relation::Lt)));
+    assert!(parts.has(Completer::CompareType(CompareType::OrAndcm)));
+    assert_eq!(parts.completers.len(), 3);
+    let parts = Opcode::Ldfps_c_nc.mnemonic_parts();
+    assert_eq!(parts.base, "ldfps");
+    assert_eq!(parts.completers, vec![
+        Completer::Precision(Precision::Single),
+        Completer::Speculation(Speculation::CheckNoClear),
+    ]);
+    assert!(Opcode::Fetchadd4_acq.mnemonic_parts().has(Completer::Ordering(Ordering::Acquire)));
+    assert_eq!(Opcode::Cmp8xchg16_acq.mnemonic_parts().size(), Some(16));
+    assert_eq!(Opcode::Fma_s.mnemonic_parts().completers, vec![Completer::Precision(Precision::Single)]);
+    assert_eq!(Opcode::Getf_sig.mnemonic_parts().completers, vec![Completer::Precision(Precision::Significand)]);
+    assert_eq!(Opcode::Nop_i.mnemonic_parts().completers, vec![Completer::Unit(Unit::I)]);
+    assert_eq!(Opcode::Brl_call_bwh_ph_dh.mnemonic_parts().completers, vec![Completer::Other("call")]);
+    assert_eq!(Opcode::AddPlusOne.mnemonic_parts().base, "add");
+    assert_eq!(Opcode::Mf_a.mnemonic_parts().completers, vec![Completer::Other("a")]);
+}
+
+#[test]
+fn test_parse_round_trip() {
+    use yaxpeax_ia64::{ApplicationRegister, ControlRegister, GPRegister, IndirectionReg, Opcode, Operand, ParseError};
+
+    for text in ["cmp4.lt.or.andcm", "ldfps.c.nc", "fetchadd4.acq", "tf.z.unc", "addplusone", "mov.m"].iter() {
+        assert_eq!(text.parse::<Opcode>().unwrap().to_string(), *text);
+    }
+    assert_eq!("mov".parse::<Opcode>(), Ok(Opcode::Mov));
+    assert_eq!("ld8.bogus".parse::<Opcode>(), Err(ParseError::Unrecognized));
+
+    assert_eq!("r127".parse::<GPRegister>(), Ok(GPRegister(127)));
+    assert_eq!("r128".parse::<GPRegister>(), Err(ParseError::OutOfRange));
+    assert_eq!("r01".parse::<GPRegister>(), Err(ParseError::BadNumber));
+    assert_eq!("ar.bspstore".parse::<ApplicationRegister>(), Ok(ApplicationRegister::BSPSTORE));
+    assert_eq!("ar48".parse::<ApplicationRegister>(), Ok(ApplicationRegister(48)));
+    assert_eq!("ar18".parse::<ApplicationRegister>(), Err(ParseError::Unrecognized));
+    assert_eq!("cr19".parse::<ControlRegister>(), Ok(ControlRegister(19)));
+    assert_eq!("cpuid".parse::<IndirectionReg>(), Ok(IndirectionReg::Cpuid));
+
+    assert_eq!("pkr[r3]".parse::<Operand>(), Ok(Operand::Indirection(IndirectionReg::Pkr, GPRegister(3))));
+    assert_eq!("cpuid[r5]".parse::<Operand>(), Ok(Operand::Indirection(IndirectionReg::Cpuid, GPRegister(5))));
+    assert_eq!("psr.um".parse::<Operand>(), Ok(Operand::PSR_um));
+    assert_eq!("[r12]".parse::<Operand>(), Ok(Operand::Memory(GPRegister(12))));
+    assert_eq!("-0x8000000000000000".parse::<Operand>(), Ok(Operand::ImmI64(i64::MIN)));
+    assert_eq!("0x0".parse::<Operand>(), Ok(Operand::ImmU64(0)));
+    assert_eq!("0X10".parse::<Operand>(), Err(ParseError::BadNumber));
+
+    let decoder = InstDecoder::default();
+    // [MII] alloc r39=ar.pfs,12,9,0; mov r38=b0; mov r40=r1;;
+    let data = [0x01, 0x38, 0x31, 0x12, 0x80, 0x05, 0x60, 0x02, 0x00, 0x62, 0x00, 0x00, 0x05, 0x08, 0x00, 0x84];
+    let bundle = decoder.decode(&mut U8Reader::new(&data[..])).unwrap();
+    for inst in bundle.instructions() {
+        assert_eq!(inst.opcode().to_string().parse::<Opcode>(), Ok(inst.opcode()));
+        for op in inst.operands().iter().filter(|op| **op != Operand::None) {
+            assert_eq!(op.to_string().parse::<Operand>().as_ref(), Ok(op));
+        }
+    }
+}
+
+#[test]
+fn test_pattern_search() {
+    use yaxpeax_ia64::{BranchRegister, GPRegister, Operand, SlotAddress};
+    use yaxpeax_ia64::search::Pattern;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] ld8 r14=[r8],0x8;; nop.m 0x0; mov b6=r14
+        0x0a, 0x70, 0x20, 0x10, 0x18, 0x14, 0x00, 0x00, 0x00, 0x02, 0x00, 0xc0, 0xe0, 0x08, 0x00, 0x07,
+        // a reserved template, skipped
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        // [MMB] ld8 r1=[r8]; nop.m 0x0; br.call.sptk.many b0=b6;;
+        0x19, 0x08, 0x00, 0x10, 0x18, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x68, 0x00, 0x80, 0x10,
+    ];
+    let search = |pattern: &str| {
+        pattern.parse::<Pattern>().unwrap().search_bytes(&decoder, &data, 0x4000)
+    };
+
+    let found = search("ld8 ?r=[r8],?");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].address, SlotAddress::new(0x4000, 0).unwrap());
+    assert_eq!(found[0].captures, vec![Operand::GPRegister(GPRegister(14))]);
+    assert_eq!(search("ld8 ?r=[?r]").len(), 2);
+
+    let found = search("mov b6=?r");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].address, SlotAddress::new(0x4000, 2).unwrap());
+    assert_eq!(found[0].captures, vec![Operand::GPRegister(GPRegister(14))]);
+
+    let found = search("br.call ?b=?b");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].address, SlotAddress::new(0x4020, 2).unwrap());
+    assert_eq!(found[0].captures, vec![
+        Operand::BranchRegister(BranchRegister(0)),
+        Operand::BranchRegister(BranchRegister(6)),
+    ]);
+    assert_eq!(search("nop*").len(), 2);
+    assert_eq!(search("? ?r=[r8]").len(), 2);
+    assert_eq!(search("(?p) ?").len(), 0);
+    assert_eq!(search("st*.rel").len(), 0);
+    assert!("ld8 ?x=[r8]".parse::<Pattern>().is_err());
+}
+
+#[test]
+fn test_function_starts() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::functions::find_starts;
+    use yaxpeax_ia64::image::ByteOrder;
+    use yaxpeax_ia64::unwind::table_entries;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // 0x1000: [MMB] nop.m 0x0; adds r12=0x10,r12; br.ret.sptk.many b0;;
+        0x19, 0x00, 0x00, 0x00, 0x01, 0x00, 0xc0, 0x80, 0x30, 0x00, 0x42, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // 0x1010: [MII] nop.m 0x0; nop.i 0x0; nop.i 0x0
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // 0x1020: [MMI] alloc r39=ar.pfs,12,9,0; adds r12=-0x10,r12; mov r38=b0
+        0x08, 0x38, 0x31, 0x12, 0x80, 0x05, 0xc0, 0x80, 0x33, 0x7e, 0x46, 0xc0, 0x04, 0x00, 0xc4, 0x00,
+        // 0x1030: [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.many b0=$-0x10;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xf8, 0xff, 0xff, 0x58,
+        // 0x1040: [MMB] nop.m 0x0; adds r12=0x10,r12; br.ret.sptk.many b0;;
+        0x19, 0x00, 0x00, 0x00, 0x01, 0x00, 0xc0, 0x80, 0x30, 0x00, 0x42, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // 0x1050: [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.many b0=$-0x10;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xf8, 0xff, 0xff, 0x58,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+
+    let starts = find_starts(&bundles, &[]);
+    let addresses: Vec<u64> = starts.iter().map(|s| s.address.bundle()).collect();
+    assert_eq!(addresses, vec![0x1020, 0x1040, 0x1050]);
+    let prologue = &starts[0].evidence;
+    assert!(prologue.alloc && prologue.saves_b0 && prologue.allocates_stack);
+    assert!(prologue.call_target && prologue.after_return && !prologue.unwind);
+    assert_eq!(starts[0].confidence(), 100);
+    assert!(starts[1].evidence.call_target && !starts[1].evidence.after_return);
+    assert_eq!(starts[1].confidence(), 50);
+    assert_eq!(starts[2].confidence(), 20);
+
+    // one entry, for 0x1000..0x1020, in a segment based at 0x800, and one of padding.
+    let mut table = Vec::new();
+    for word in [0x800u64, 0x820, 0x900, 0, 0, 0].iter() {
+        table.extend_from_slice(&word.to_be_bytes());
+    }
+    let entries: Vec<_> = table_entries(&table, 0x800, ByteOrder::Big).collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].start, entries[0].end, entries[0].info), (0x1000, 0x1020, 0x1100));
+    let starts = find_starts(&bundles, &entries);
+    assert_eq!(starts[0].address, SlotAddress::new(0x1000, 0).unwrap());
+    assert_eq!(starts[0].confidence(), 100);
+}
+
+#[test]
+fn test_gp_relative() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::gp::{resolve, ReferenceKind};
+    use yaxpeax_ia64::image::{ByteOrder, Image, MemoryImage};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] addl r14=0x20,r1;; ld8 r14=[r14]; nop.i 0x0
+        0x0a, 0x70, 0x80, 0x02, 0x00, 0x24, 0xe0, 0x00, 0x38, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MLX] nop.m 0x0; movl r16=0x30;;
+        0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x00, 0x60,
+        // [MMI] add r16=r16,r1;; ld8 r17=[r16]; nop.i 0x0
+        0x0a, 0x80, 0x40, 0x02, 0x00, 0x20, 0x10, 0x01, 0x40, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+
+    // a linkage table at gp 0x6000, with slots for `counter` and `greeting`.
+    let mut table = [0u8; 0x40];
+    table[0x20..0x28].copy_from_slice(&0x5000u64.to_le_bytes());
+    table[0x30..0x38].copy_from_slice(&0x5100u64.to_le_bytes());
+    let strings = b"hello\0";
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_read_only_segment(0x6000, &table);
+    image.add_segment(0x5100, strings);
+    image.add_symbol(0x5000, "counter");
+
+    let references = resolve(&bundles, 0x6000, &image);
+    let summary: Vec<_> = references.iter()
+        .map(|r| (r.address, r.kind, r.target, r.symbol.as_deref()))
+        .collect();
+    assert_eq!(summary, vec![
+        (SlotAddress::new(0x1000, 0).unwrap(), ReferenceKind::GpRelative, 0x6020, None),
+        (SlotAddress::new(0x1000, 1).unwrap(), ReferenceKind::Loaded, 0x5000, Some("counter")),
+        (SlotAddress::new(0x1020, 0).unwrap(), ReferenceKind::GpRelative, 0x6030, None),
+        (SlotAddress::new(0x1020, 1).unwrap(), ReferenceKind::Loaded, 0x5100, None),
+    ]);
+    assert_eq!(image.c_string(references[3].target), Some("hello"));
+
+    // a writable linkage table might be changed before the loads run.
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_segment(0x6000, &table);
+    let kinds: Vec<_> = resolve(&bundles, 0x6000, &image).iter().map(|r| r.kind).collect();
+    assert_eq!(kinds, vec![ReferenceKind::GpRelative, ReferenceKind::GpRelative]);
+}
+
+#[test]
+fn test_indirect_calls() {
+    use yaxpeax_ia64::{BranchRegister, Bundles, SlotAddress};
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+    use yaxpeax_ia64::indirect::{resolve_calls, FunctionDescriptor};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] addl r14=0x20,r1;; ld8 r14=[r14]; nop.i 0x0
+        0x0a, 0x70, 0x80, 0x02, 0x00, 0x24, 0xe0, 0x00, 0x38, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MMI] nop.m 0x0; ld8 r15=[r14],0x8; nop.i 0x0;;
+        0x09, 0x00, 0x00, 0x00, 0x01, 0x00, 0xf0, 0x40, 0x38, 0x30, 0x28, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] ld8 r1=[r14]; mov b6=r15; br.call.sptk.many b0=b6;;
+        0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x60, 0x78, 0x04, 0x80, 0x03, 0x00, 0x68, 0x00, 0x80, 0x10,
+    ];
+
+    // the linkage table slot at gp+0x20 points to a descriptor for `frame_dummy`.
+    let mut data_segment = [0u8; 0x40];
+    data_segment[0x20..0x28].copy_from_slice(&0x6030u64.to_le_bytes());
+    data_segment[0x30..0x38].copy_from_slice(&0x2000u64.to_le_bytes());
+    data_segment[0x38..0x40].copy_from_slice(&0x8000u64.to_le_bytes());
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_read_only_segment(0x6000, &data_segment);
+    image.add_symbol(0x2000, "frame_dummy");
+    assert_eq!(FunctionDescriptor::read(&image, 0x6030), Some(FunctionDescriptor { entry: 0x2000, gp: 0x8000 }));
+
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let calls = resolve_calls(&bundles, 0x6000, &image);
+    assert_eq!(calls.len(), 1);
+    let call = &calls[0];
+    assert_eq!(call.address, SlotAddress::new(0x1020, 2).unwrap());
+    assert_eq!(call.register, BranchRegister(6));
+    assert_eq!(call.load, Some(SlotAddress::new(0x1010, 1).unwrap()));
+    assert_eq!(call.descriptor, Some(0x6030));
+    assert_eq!(call.target, Some(0x2000));
+    assert_eq!(call.gp, Some(0x8000));
+    assert_eq!(call.symbol.as_deref(), Some("frame_dummy"));
+
+    // without the first bundle, as in `__libc_csu_init`, `r14` comes from elsewhere.
+    let bundles = Bundles::decode(&decoder, &data[16..], 0x1010).unwrap();
+    let calls = resolve_calls(&bundles, 0x6000, &image);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].load, Some(SlotAddress::new(0x1010, 1).unwrap()));
+    assert_eq!((calls[0].descriptor, calls[0].target, calls[0].gp), (None, None, None));
+}
+
+#[test]
+fn test_plt_stubs() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+    use yaxpeax_ia64::plt::{self, StubKind, find_stubs};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] mov r2=r14;; addl r14=0x0,r2; nop.i 0x0;;
+        0x0b, 0x10, 0x00, 0x1c, 0x00, 0x21, 0xe0, 0x00, 0x08, 0x00, 0x48, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MMI] ld8 r16=[r14],0x8;; ld8 r17=[r14],0x8; nop.i 0x0;;
+        0x0b, 0x80, 0x20, 0x1c, 0x18, 0x14, 0x10, 0x41, 0x38, 0x30, 0x28, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] ld8 r1=[r14]; mov b6=r17; br.few b6;;
+        0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x60, 0x88, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00,
+        // [MIB] mov r15=0x3; nop.i 0x0; br.few $-0x30;;
+        0x11, 0x78, 0x0c, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xd0, 0xff, 0xff, 0x48,
+        // [MMI] addl r15=0x20,r1;; ld8.acq r16=[r15],0x8; mov r14=r1;;
+        0x0b, 0x78, 0x80, 0x02, 0x00, 0x24, 0x00, 0x41, 0x3c, 0x70, 0x29, 0xc0, 0x01, 0x08, 0x00, 0x84,
+        // [MIB] ld8 r1=[r15]; mov b6=r16; br.few b6;;
+        0x11, 0x08, 0x00, 0x1e, 0x18, 0x10, 0x60, 0x80, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00,
+        // an HP-UX import stub:
+        // [MMI] addl r15=0x30,r1;; ld8 r16=[r15],0x8; mov r14=r1;;
+        0x0b, 0x78, 0xc0, 0x02, 0x00, 0x24, 0x00, 0x41, 0x3c, 0x30, 0x28, 0xc0, 0x01, 0x08, 0x00, 0x84,
+        // [MIB] ld8 r1=[r15]; mov b6=r16; br.few b6;;
+        0x11, 0x08, 0x00, 0x1e, 0x18, 0x10, 0x60, 0x80, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00,
+        // [MMI] nop.m 0x0; nop.m 0x0; nop.i 0x0;;
+        0x09, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.few b0=$-0x50;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xb0, 0xff, 0xff, 0x58,
+    ];
+
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_relocation(0x6020, "printf");
+    image.add_relocation(0x6030, "puts");
+
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let stubs = find_stubs(&bundles, 0x6000, &image);
+    assert_eq!(stubs.len(), 4);
+    assert_eq!((stubs[0].address, stubs[0].size, stubs[0].kind), (0x1000, 0x30, StubKind::Header));
+    assert_eq!(stubs[1].address, 0x1030);
+    assert_eq!(stubs[1].kind, StubKind::Lazy { index: 3, header: SlotAddress::new(0x1000, 0).unwrap() });
+    assert_eq!(stubs[1].label(), None);
+    assert_eq!((stubs[2].address, stubs[2].size), (0x1040, 0x20));
+    assert_eq!(stubs[2].kind, StubKind::Full { descriptor: 0x6020 });
+    assert_eq!(stubs[2].label().as_deref(), Some("printf@plt"));
+    assert_eq!((stubs[3].address, stubs[3].size), (0x1060, 0x20));
+    assert_eq!(stubs[3].kind, StubKind::Import { descriptor: 0x6030 });
+    assert_eq!(stubs[3].label().as_deref(), Some("puts@plt"));
+
+    let call = bundles.bundle_at(0x1090).unwrap();
+    assert_eq!(plt::bundle(&stubs, call, 0x1090).to_string(), "[MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.few b0=printf@plt;;");
+    let stub = bundles.bundle_at(0x1040).unwrap();
+    assert_eq!(plt::bundle(&stubs, stub, 0x1040).to_string(), stub.to_string());
+}
+
+#[test]
+fn test_relocated_display() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::reloc::{Relocation, RelocationType, Relocations};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] addl r14=0x0,r1;; ld8 r14=[r14]; nop.i 0x0
+        0x0a, 0x70, 0x00, 0x02, 0x00, 0x24, 0xe0, 0x00, 0x38, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] ld8 r1=[r14]; nop.i 0x0; br.call.sptk.few b0=$+0x0;;
+        0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x50,
+        // [MLX] nop.m 0x0; movl r14=0x0;;
+        0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x01, 0x00, 0x00, 0x60,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x40).unwrap();
+
+    let mut relocations = Relocations::new();
+    assert!(relocations.add(0x40, Relocation::from_rela(0x0000_0005_0000_0032, 0, "var").unwrap()));
+    assert!(relocations.add(0x52, Relocation::from_rela(0x0000_0006_0000_0049, 0, "printf").unwrap()));
+    // binutils records relocations of `movl` against the `X` slot.
+    assert!(relocations.add(0x62, Relocation { kind: RelocationType::LTOFF_FPTR64I, symbol: "f".to_string(), addend: 8 }));
+    assert!(!relocations.add(0x43, Relocation::from_rela(0x22, 0, "x").unwrap()));
+    assert_eq!(Relocation::from_rela(0x87, 0, "ldxmov"), None);
+
+    let text: Vec<String> = bundles.bundles().iter().enumerate()
+        .map(|(i, bundle)| relocations.bundle(bundle, 0x40 + i as u64 * 16).to_string())
+        .collect();
+    assert_eq!(text[0], "[MMI] addl r14=@ltoff(var),r1;; ld8 r14=[r14]; nop.i 0x0");
+    assert_eq!(text[1], "[MIB] ld8 r1=[r14]; nop.i 0x0; br.call.sptk.few b0=printf;;");
+    assert_eq!(text[2], "[MLX] nop.m 0x0; movl r14=@ltoff(@fptr(f+0x8));;");
+
+    // gas emits the relaxable `LTOFF22X` for `@ltoffx(sym)`.
+    let mut relaxable = Relocations::new();
+    assert!(relaxable.add(0x40, Relocation::from_rela(0x0000_0005_0000_0086, 0, "var").unwrap()));
+    assert_eq!(relaxable.bundle(&bundles.bundles()[0], 0x40).to_string(), "[MMI] addl r14=@ltoffx(var),r1;; ld8 r14=[r14]; nop.i 0x0");
+
+    let bundle = &bundles.bundles()[2];
+    let movl = SlotAddress::new(0x60, 1).unwrap();
+    assert_eq!(relocations.get(bundle, movl).map(|r| r.kind), Some(RelocationType::LTOFF_FPTR64I));
+    assert_eq!(relocations.instruction(bundle, movl, &bundle.instructions()[1]).to_string(), "movl r14=@ltoff(@fptr(f+0x8))");
+}
+
+#[test]
+fn test_jump_tables() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::cfg::{Cfg, Edge, EdgeKind};
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+    use yaxpeax_ia64::switch::{EntryKind, recover};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] cmp.ltu p6,p7=0x2,r32; addl r14=0x10,r1; nop.i 0x0;;
+        0x09, 0x30, 0x08, 0x40, 0x07, 0x35, 0xe0, 0x80, 0x04, 0x00, 0x48, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] ld8 r14=[r14]; nop.i 0x0; (p06) br.cond.dpnt.few $+0x60;;
+        0x11, 0x70, 0x00, 0x1c, 0x18, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x60, 0x00, 0x00, 0x43,
+        // [MMI] shladd r15=r32,0x3,r14;; ld8 r16=[r15]; nop.i 0x0;;
+        0x0b, 0x78, 0x80, 0x1c, 0x12, 0x20, 0x00, 0x01, 0x3c, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MMI] add r15=r16,r15; nop.m 0x0; nop.i 0x0;;
+        0x09, 0x78, 0x40, 0x1e, 0x00, 0x20, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] nop.m 0x0; mov b6=r15; br.few b6;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x60, 0x78, 0x00, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+
+    // a table of offsets from each entry at 0x5000, found through the linkage table slot at gp+0x10.
+    let mut table = [0u8; 0x18];
+    for (i, target) in [0x1050u64, 0x1060, 0x1050].iter().enumerate() {
+        let entry = target.wrapping_sub(0x5000 + i as u64 * 8);
+        table[i * 8..][..8].copy_from_slice(&entry.to_le_bytes());
+    }
+    let linkage_table = 0x5000u64.to_le_bytes();
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_read_only_segment(0x5000, &table);
+    image.add_read_only_segment(0x6010, &linkage_table);
+
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let tables = recover(&bundles, 0x6000, &image);
+    assert_eq!(tables.len(), 1);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+    assert_eq!(tables[0].branch, slot(0x1042));
+    assert_eq!(tables[0].bound, slot(0x1000));
+    assert_eq!((tables[0].table, tables[0].entry_size, tables[0].kind), (0x5000, 8, EntryKind::EntryRelative));
+    assert_eq!(tables[0].targets, vec![slot(0x1050), slot(0x1060), slot(0x1050)]);
+
+    let cfg = Cfg::with_jump_tables(&bundles, &tables);
+    let starts: Vec<SlotAddress> = cfg.blocks().map(|block| block.start).collect();
+    assert_eq!(starts, vec![slot(0x1000), slot(0x1020), slot(0x1050), slot(0x1060), slot(0x1070)]);
+    let entry = cfg.block(slot(0x1000)).unwrap();
+    assert_eq!(entry.last, slot(0x1012));
+    assert_eq!(entry.edges, vec![
+        Edge { kind: EdgeKind::FallThrough, target: slot(0x1020), predicate: 6 },
+        Edge { kind: EdgeKind::Branch, target: slot(0x1070), predicate: 6 },
+    ]);
+    let dispatch = cfg.block_containing(slot(0x1031)).unwrap();
+    assert_eq!(dispatch.start, slot(0x1020));
+    assert_eq!(dispatch.instructions(&bundles).count(), 9);
+    assert_eq!(dispatch.edges, vec![
+        Edge { kind: EdgeKind::JumpTable, target: slot(0x1050), predicate: 0 },
+        Edge { kind: EdgeKind::JumpTable, target: slot(0x1060), predicate: 0 },
+    ]);
+    assert_eq!(cfg.predecessors(slot(0x1050)), vec![slot(0x1020)]);
+    assert!(cfg.block(slot(0x1060)).unwrap().edges.is_empty());
+
+    // without the table, the dispatch is a dead end.
+    let cfg = Cfg::new(&bundles);
+    assert!(cfg.block(slot(0x1020)).unwrap().edges.is_empty());
+    assert_eq!(cfg.predecessors(slot(0x1050)), vec![]);
+}
+
+#[test]
+fn test_value_tracking() {
+    use yaxpeax_ia64::{Bundles, GPRegister, SlotAddress};
+    use yaxpeax_ia64::cfg::Cfg;
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+    use yaxpeax_ia64::values::{Value, Values};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MII] nop.m 0x0; mov r2=ip;; addl r2=0x40,r2;;
+        0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x20, 0x00, 0x00, 0x60, 0x00, 0x40, 0x00, 0x14, 0x00, 0x90,
+        // [MLX] nop.m 0x0; movl r14=0x2000;;
+        0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x01, 0x00, 0x00, 0x61,
+        // [MII] ld8 r15=[r14]; shladd r16=r2,0x2,r14;; extr.u r17=r14,0xc,0x4;;
+        0x03, 0x78, 0x00, 0x1c, 0x18, 0x10, 0x00, 0x11, 0x38, 0x22, 0x40, 0x20, 0x82, 0x71, 0x0c, 0x52,
+        // [MMI] cmp.ltu p6,p7=0x5,r17; (p06) mov r18=0x7; dep r19=r17,r16,0x0,0x4;;
+        0x09, 0x30, 0x14, 0x22, 0x07, 0xb5, 0x21, 0x39, 0x00, 0x00, 0x48, 0x60, 0x12, 0x81, 0xcc, 0x4f,
+        // [MIB] nop.m 0x0; nop.i 0x0; (p06) br.cond.dptk.few $+0x20;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x20, 0x00, 0x00, 0x42,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+    let constant = 0x1234u64.to_le_bytes();
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_read_only_segment(0x2000, &constant);
+
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let cfg = Cfg::new(&bundles);
+    let values = Values::analyze(&bundles, &cfg, &image, &[]);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+    let r = GPRegister;
+    assert_eq!(values.value(slot(0x1020), r(2)), Some(0x1040));
+    assert_eq!(values.value(slot(0x1020), r(14)), Some(0x2000));
+    assert_eq!(values.value(slot(0x1030), r(15)), Some(0x1234));
+    assert_eq!(values.value(slot(0x1030), r(16)), Some(0x6100));
+    assert_eq!(values.value(slot(0x1030), r(17)), Some(0x2));
+    assert_eq!(values.value(slot(0x1040), r(19)), Some(0x6102));
+
+    // `r18` is only known where `p6` holds: by the branch, and at its target.
+    assert_eq!(values.state(slot(0x1040), r(18)), Value::Guarded { predicate: 6, value: Some(7), otherwise: None });
+    assert_eq!(values.value(slot(0x1040), r(18)), None);
+    assert_eq!(values.value(slot(0x1042), r(18)), Some(7));
+    assert_eq!(values.value(slot(0x1052), r(18)), None);
+    assert_eq!(values.value(slot(0x1062), r(18)), Some(7));
+
+    // memory that might be written is not read.
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_segment(0x2000, &constant);
+    let values = Values::analyze(&bundles, &cfg, &image, &[(r(1), 0x6000)]);
+    assert_eq!(values.value(slot(0x1030), r(15)), None);
+    assert_eq!(values.value(slot(0x1062), r(1)), Some(0x6000));
+
+    // a loop back to the first instruction still enters it with `initial`.
+    let data = [
+        // [MII] nop.m 0x0; mov r2=ip;; addl r2=0x40,r2;;
+        0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x20, 0x00, 0x00, 0x60, 0x00, 0x40, 0x00, 0x14, 0x00, 0x90,
+        // [MIB] nop.m 0x0; nop.i 0x0; (p06) br.cond.sptk.few $-0x10;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0xf0, 0xff, 0xff, 0x48,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let cfg = Cfg::new(&bundles);
+    let values = Values::analyze(&bundles, &cfg, &image, &[(r(1), 0x6000)]);
+    assert_eq!(values.value(slot(0x1000), r(1)), Some(0x6000));
+    assert_eq!(values.value(slot(0x1010), r(2)), Some(0x1040));
+    assert_eq!(values.value(slot(0x1000), r(2)), None);
+}
+
+#[test]
+fn test_liveness() {
+    use yaxpeax_ia64::{BranchRegister, Bundles, GPRegister, SlotAddress};
+    use yaxpeax_ia64::cfg::Cfg;
+    use yaxpeax_ia64::liveness::{Frame, Liveness};
+    use yaxpeax_ia64::registers::Location;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MII] alloc r34=ar.pfs,5,3,0; mov r35=r32; mov r33=b0;;
+        0x01, 0x10, 0x15, 0x06, 0x80, 0x05, 0x30, 0x02, 0x80, 0x00, 0x42, 0x20, 0x04, 0x00, 0xc4, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.few b0=$+0x1000;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10, 0x00, 0x50,
+        // [MII] nop.m 0x0; mov b0=r33; mov.i ar.pfs=r34;;
+        0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x08, 0x01, 0x80, 0x03, 0x00, 0x20, 0x02, 0xaa, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let cfg = Cfg::new(&bundles);
+    let liveness = Liveness::analyze(&bundles, &cfg);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+    let r = |n| Location::GPRegister(GPRegister(n));
+    let b = |n| Location::BranchRegister(BranchRegister(n));
+    assert_eq!(liveness.frame(), Some(Frame { size: 5, locals: 3, rotating: 0 }));
+
+    // the input and return address are live on entry; the locals and outputs aren't.
+    let entry = liveness.live_in(slot(0x1000));
+    assert!(entry.contains(r(32)) && entry.contains(b(0)) && entry.contains(r(4)));
+    assert!(!entry.contains(r(33)) && !entry.contains(r(34)) && !entry.contains(r(35)) && !entry.contains(r(14)));
+
+    // the call reads the outputs, which become the callee's inputs.
+    let call = liveness.live_before(slot(0x1012));
+    assert!(call.contains(r(35)) && call.contains(r(36)) && call.contains(r(33)) && call.contains(r(1)));
+    assert!(!call.contains(r(32)));
+
+    // after the call, the return value and the saved state are live, the outputs and scratch
+    // registers aren't.
+    let returned = liveness.live_after(slot(0x1012));
+    assert!(returned.contains(r(8)) && returned.contains(r(33)) && returned.contains(r(34)));
+    assert!(!returned.contains(r(35)) && !returned.contains(r(14)) && !returned.contains(b(0)));
+    assert!(liveness.live_after(slot(0x1021)).contains(b(0)));
+    assert!(!liveness.live_after(slot(0x1022)).contains(r(34)));
+    assert!(liveness.live_out(slot(0x1020)).is_empty());
+}
+
+#[test]
+fn test_signatures() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::functions;
+    use yaxpeax_ia64::liveness::Frame;
+    use yaxpeax_ia64::signature::{self, Signature};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MII] alloc r34=ar.pfs,3,3,0; add r8=r32,r33; nop.i 0x0;;
+        0x01, 0x10, 0x0d, 0x06, 0x80, 0x05, 0x80, 0x00, 0x85, 0x00, 0x40, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // [MFI] nop.m 0x0; fma.s0 f8=f9,f10,f8; nop.i 0x0;;
+        0x0d, 0x00, 0x00, 0x00, 0x01, 0x00, 0x80, 0x40, 0x24, 0x14, 0x40, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let starts = functions::find_starts(&bundles, &[]);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+    assert_eq!(signature::infer_all(&bundles, &starts), vec![
+        Signature {
+            start: slot(0x1000),
+            frame: Some(Frame { size: 3, locals: 3, rotating: 0 }),
+            inputs: 2,
+            float_inputs: 0,
+            results: 1,
+            float_results: 0,
+        },
+        Signature {
+            start: slot(0x1020),
+            frame: None,
+            inputs: 0,
+            float_inputs: 3,
+            results: 0,
+            float_results: 1,
+        },
+    ]);
+}
+
+#[test]
+fn test_call_graph() {
+    use yaxpeax_ia64::{Bundles, SlotAddress};
+    use yaxpeax_ia64::callgraph::{Call, CallGraph, CallKind, NodeKind};
+    use yaxpeax_ia64::functions::{self, FunctionStart};
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MMI] addl r15=0x40,r1;; ld8.acq r16=[r15],0x8; mov r14=r1;;
+        0x0b, 0x78, 0x00, 0x03, 0x00, 0x24, 0x00, 0x41, 0x3c, 0x70, 0x29, 0xc0, 0x01, 0x08, 0x00, 0x84,
+        // [MIB] ld8 r1=[r15]; mov b6=r16; br.few b6;;
+        0x11, 0x08, 0x00, 0x1e, 0x18, 0x10, 0x60, 0x80, 0x04, 0x80, 0x03, 0x00, 0x60, 0x00, 0x80, 0x00,
+        // main:
+        // [MMI] addl r14=0x20,r1;; ld8 r14=[r14]; nop.i 0x0
+        0x0a, 0x70, 0x80, 0x02, 0x00, 0x24, 0xe0, 0x00, 0x38, 0x30, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MMI] nop.m 0x0; ld8 r15=[r14],0x8; nop.i 0x0;;
+        0x09, 0x00, 0x00, 0x00, 0x01, 0x00, 0xf0, 0x40, 0x38, 0x30, 0x28, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] ld8 r1=[r14]; mov b6=r15; br.call.sptk.many b0=b6;;
+        0x11, 0x08, 0x00, 0x1c, 0x18, 0x10, 0x60, 0x78, 0x04, 0x80, 0x03, 0x00, 0x68, 0x00, 0x80, 0x10,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.few b0=$-0x50;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xb0, 0xff, 0xff, 0x58,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.call.sptk.few b0=$+0x20;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x20, 0x00, 0x00, 0x50,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.few $+0x20;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x20, 0x00, 0x00, 0x40,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+
+    // the linkage table slot at gp+0x20 points to a descriptor for the function at 0x1080, and
+    // the one at gp+0x40 is `printf`'s descriptor.
+    let mut data_segment = [0u8; 0x50];
+    data_segment[0x20..0x28].copy_from_slice(&0x6030u64.to_le_bytes());
+    data_segment[0x30..0x38].copy_from_slice(&0x1080u64.to_le_bytes());
+    data_segment[0x38..0x40].copy_from_slice(&0x6000u64.to_le_bytes());
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_read_only_segment(0x6000, &data_segment);
+    image.add_relocation(0x6040, "printf");
+    image.add_symbol(0x1020, "main");
+
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let mut starts = functions::find_starts(&bundles, &[]);
+    starts.push(FunctionStart { address: SlotAddress::new(0x1020, 0).unwrap(), evidence: Default::default() });
+    let graph = CallGraph::build(&bundles, &starts, 0x6000, &image);
+
+    let nodes: Vec<(u64, NodeKind, String)> = graph.nodes().map(|node| (node.address, node.kind, node.label())).collect();
+    assert_eq!(nodes, vec![
+        (0x1000, NodeKind::Stub, "printf@plt".to_string()),
+        (0x1020, NodeKind::Function, "main".to_string()),
+        (0x1080, NodeKind::Function, "0x1080".to_string()),
+        (0x10a0, NodeKind::Function, "0x10a0".to_string()),
+    ]);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+    assert_eq!(graph.calls(), &[
+        Call { site: slot(0x1042), caller: 0x1020, callee: 0x1080, kind: CallKind::Indirect },
+        Call { site: slot(0x1052), caller: 0x1020, callee: 0x1000, kind: CallKind::Direct },
+        Call { site: slot(0x1062), caller: 0x1020, callee: 0x1080, kind: CallKind::Direct },
+        Call { site: slot(0x1082), caller: 0x1080, callee: 0x10a0, kind: CallKind::Tail },
+    ]);
+    assert_eq!(graph.callers(0x1080).count(), 2);
+    assert!(graph.unresolved().is_empty());
+
+    assert_eq!(graph.to_dot(), "\
+digraph calls {
+    \"0x1000\" [label=\"printf@plt\", shape=box];
+    \"0x1020\" [label=\"main\"];
+    \"0x1080\" [label=\"0x1080\"];
+    \"0x10a0\" [label=\"0x10a0\"];
+    \"0x1020\" -> \"0x1000\";
+    \"0x1020\" -> \"0x1080\";
+    \"0x1020\" -> \"0x1080\" [style=dashed];
+    \"0x1080\" -> \"0x10a0\" [style=dotted];
+}
+");
+    assert!(graph.to_json().starts_with("{\"nodes\":[{\"address\":\"0x1000\",\"kind\":\"stub\",\"name\":\"printf@plt\"},"));
+    assert!(graph.to_json().contains("{\"address\":\"0x1080\",\"kind\":\"function\",\"name\":null}"));
+    assert!(graph.to_json().ends_with(",{\"site\":\"0x1082\",\"caller\":\"0x1080\",\"callee\":\"0x10a0\",\"kind\":\"tail\"}],\"unresolved\":[]}"));
+}
+
+#[test]
+fn test_render_cfg() {
+    use yaxpeax_ia64::Bundles;
+    use yaxpeax_ia64::cfg::Cfg;
+    use yaxpeax_ia64::render;
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MII] nop.m 0x0; mov r2=ip;; addl r2=0x40,r2;;
+        0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x20, 0x00, 0x00, 0x60, 0x00, 0x40, 0x00, 0x14, 0x00, 0x90,
+        // [MLX] nop.m 0x0; movl r14=0x2000;;
+        0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x01, 0x00, 0x00, 0x61,
+        // [MIB] nop.m 0x0; nop.i 0x0; (p06) br.cond.dptk.few $+0x20;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x20, 0x00, 0x00, 0x42,
+        // [MIB] nop.m 0x0; nop.i 0x0; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let cfg = Cfg::new(&bundles);
+
+    assert_eq!(render::dot(&bundles, &cfg), "\
+digraph cfg {
+    node [shape=box, fontname=\"monospace\"];
+    \"0x1000\" [label=\"0x1000 [MII]\\l  0  nop.m 0x0\\l  1  mov r2=ip ;;\\l  2  addl r2=0x40,r2 ;;\\l\
+0x1010 [MLX]\\l  0  nop.m 0x0\\l  1  movl r14=0x2000 ;;\\l\
+0x1020 [MIB]\\l  0  nop.m 0x0\\l  1  nop.i 0x0\\l  2  (p06) br.cond.dptk.few $+0x20 ;;\\l\"];
+    \"0x1000\" -> \"0x1030\" [label=\"!p6\"];
+    \"0x1000\" -> \"0x1040\" [label=\"p6\"];
+    \"0x1030\" [label=\"0x1030 [MIB]\\l  0  nop.m 0x0\\l  1  nop.i 0x0\\l  2  br.ret.sptk.many b0 ;;\\l\"];
+    \"0x1040\" [label=\"0x1040 [MIB]\\l  0  nop.m 0x0\\l  1  nop.i 0x0\\l  2  br.ret.sptk.many b0 ;;\\l\"];
+}
+");
+
+    let html = render::html(&bundles, &cfg, "<dispatch>");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>&lt;dispatch&gt;</title>"));
+    assert!(html.contains("<tr id=\"i1011\"><td class=\"slot\">1</td><td>movl r14=0x2000</td><td class=\"stop\">;;</td></tr>"));
+    assert!(html.contains("<td>(p06) br.cond.dptk.few <a href=\"#i1040\">$+0x20</a></td>"));
+    assert!(html.contains("to: fall through <a href=\"#i1030\">0x1030</a> (!p6), branch <a href=\"#i1040\">0x1040</a> (p6)"));
+    assert!(html.contains("from: <a href=\"#i1022\">0x1022</a>"));
+    assert!(!html.contains("<script") && !html.contains("http"));
+}
+
+#[test]
+fn test_stack_frames() {
+    use yaxpeax_ia64::{Bundles, FloatRegister, GPRegister, SlotAddress};
+    use yaxpeax_ia64::functions;
+    use yaxpeax_ia64::image::{ByteOrder, MemoryImage};
+    use yaxpeax_ia64::registers::Location;
+    use yaxpeax_ia64::stack::{self, Adjustment, Spill, Unbalanced, UnwindMismatch};
+    use yaxpeax_ia64::unwind::{self, Descriptor};
+
+    let decoder = InstDecoder::default();
+    let data = [
+        // [MII] alloc r34=ar.pfs,4,4,0; mov r35=r12; adds r12=-0x20,r12;;
+        0x01, 0x10, 0x11, 0x08, 0x80, 0x05, 0x30, 0x02, 0x30, 0x00, 0x42, 0x80, 0x01, 0x66, 0xfc, 0x8c,
+        // [MMI] mov r2=r12;; st8.spill [r2]=r4,0x10; nop.i 0x0;;
+        0x0b, 0x10, 0x00, 0x18, 0x00, 0x21, 0x00, 0x21, 0x08, 0xb0, 0x2b, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MMI] stf.spill [r2]=f2; nop.m 0x0; nop.i 0x0;;
+        0x09, 0x00, 0x08, 0x04, 0xd8, 0x19, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // [MIB] nop.m 0x0; mov r12=r35; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0xc0, 0x00, 0x8c, 0x00, 0x42, 0x80, 0x08, 0x00, 0x84, 0x00,
+        // [MIB] nop.m 0x0; adds r12=-0x10,r12; br.ret.sptk.many b0;;
+        0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0xc0, 0x80, 0x33, 0x7e, 0x46, 0x80, 0x08, 0x00, 0x84, 0x00,
+    ];
+    let bundles = Bundles::decode(&decoder, &data, 0x1000).unwrap();
+    let starts = functions::find_starts(&bundles, &[]);
+    let frames = stack::analyze_all(&bundles, &starts);
+    assert_eq!(frames.len(), 2);
+    let slot = |address| SlotAddress::from_linear(address).unwrap();
+
+    let frame = &frames[0];
+    assert_eq!((frame.start, frame.size, frame.variable), (slot(0x1000), 32, false));
+    assert_eq!(frame.adjustments, vec![
+        Adjustment { address: slot(0x1002), delta: Some(-32) },
+        Adjustment { address: slot(0x1031), delta: Some(0) },
+    ]);
+    assert_eq!(frame.spills, vec![
+        Spill { address: slot(0x1011), register: Location::GPRegister(GPRegister(4)), offset: -32 },
+        Spill { address: slot(0x1020), register: Location::FloatRegister(FloatRegister(2)), offset: -16 },
+    ]);
+    assert!(frame.unbalanced.is_empty());
+
+    // prologue of 6 slots with `mem_stack_f` at slot 2, 0x20 bytes; a body of 6 slots with an
+    // epilogue at its end.
+    let mut info = vec![0u8; 16];
+    info[0..8].copy_from_slice(&0x0001_0000_0000_0001u64.to_le_bytes());
+    info[8..16].copy_from_slice(&[0x06, 0xe0, 0x02, 0x02, 0x26, 0xc0, 0x00, 0x00]);
+    let mut image = MemoryImage::new(ByteOrder::Little);
+    image.add_segment(0x8000, &info);
+    let block = unwind::info_block(&image, 0x8000).unwrap();
+    assert_eq!((block.version, block.flags), (1, 0));
+    assert_eq!(&block.descriptors[..4], &[
+        Descriptor::Region { prologue: true, length: 6 },
+        Descriptor::MemStackF { when: 2, size: 32 },
+        Descriptor::Region { prologue: false, length: 6 },
+        Descriptor::Epilogue { when: 0, count: 0 },
+    ]);
+    let body = &functions::bodies(&bundles, &starts)[0];
+    assert_eq!(frame.check_unwind(body, &block.descriptors), vec![]);
+    let wrong = unwind::parse_descriptors(&[0x06, 0xe0, 0x02, 0x01]).unwrap();
+    assert_eq!(frame.check_unwind(body, &wrong), vec![
+        UnwindMismatch::Size { address: slot(0x1002), expected: 16, found: Some(32) },
+    ]);
+    let elsewhere = unwind::parse_descriptors(&[0x06, 0xe1, 0x01]).unwrap();
+    assert_eq!(frame.check_unwind(body, &elsewhere), vec![UnwindMismatch::NotAnAdjustment { address: slot(0x1001) }]);
+
+    // the second function returns with its frame still allocated.
+    let frame = &frames[1];
+    assert_eq!(frame.unbalanced, vec![Unbalanced { address: slot(0x1042), delta: Some(-16) }]);
+    assert_eq!(frame.check_unwind(&functions::bodies(&bundles, &starts)[1], &[]), vec![UnwindMismatch::Missing]);
+}